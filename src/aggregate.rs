@@ -0,0 +1,221 @@
+use crate::batch::BatchJobResult;
+use crate::dashboard::render_page;
+use crate::exit_status::ConfigError;
+use crate::number_format::NumberFormat;
+use crate::output_file::{read_output, uncompressed_path, write_output};
+use crate::unused_detector::UnusedReport;
+use crate::utils::escape_html;
+use crate::version_info::REPORT_SCHEMA_VERSION;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+const WORST_OFFENDERS: usize = 10;
+
+// Read before the full report, whose layout depends on it
+#[derive(Deserialize)]
+struct SchemaProbe {
+    schema_version: Option<u32>,
+}
+
+/// One project's report, as written by `unused-classes --output` or a batch job
+pub struct ProjectReport {
+    pub name: String,
+    pub report: UnusedReport,
+}
+
+/// Cross-repository summary of several projects' reports
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AggregateReport {
+    pub total_classes: usize,
+    pub unused_classes: usize,
+    /// Most unused classes first
+    pub projects: Vec<ProjectTotals>,
+    /// Stylesheets with the most unused classes over all projects
+    pub worst_offenders: Vec<FileOffender>,
+    /// Classes defined in more than one project that are used in some and unused in others
+    pub shared_class_conflicts: Vec<SharedClassConflict>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTotals {
+    pub name: String,
+    pub total_classes: usize,
+    pub unused_classes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileOffender {
+    pub project: String,
+    pub file: String,
+    pub unused_classes: usize,
+    pub total_classes: usize,
+}
+
+/// A shared class (e.g. from a copied design system) that one project could delete and another still needs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedClassConflict {
+    pub class: String,
+    pub used_in: Vec<String>,
+    pub unused_in: Vec<String>,
+}
+
+/* ============================================================================================== */
+impl ProjectReport {
+    /// Reads a JSON report, compressed or not; `name` defaults to the file name, `shop` for `shop.json.gz`
+    pub fn load(path: &str, name: Option<String>) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = read_output(path).map_err(|e| format!("Cannot read report '{}': {}", path, e))?;
+        let probe: SchemaProbe = serde_json::from_str(&content).map_err(|e| format!("Invalid report '{}': {}", path, e))?;
+        if let Some(version) = probe.schema_version.filter(|version| *version > REPORT_SCHEMA_VERSION) {
+            return Err(ConfigError::new(format!("Report '{}' has schema version {}, this tag-finder reads up to {}; upgrade tag-finder to aggregate it",
+                path, version, REPORT_SCHEMA_VERSION)).into());
+        }
+        let report = serde_json::from_str(&content).map_err(|e| format!("Invalid report '{}': {}", path, e))?;
+        let name = name.unwrap_or_else(|| Path::new(uncompressed_path(path)).file_stem()
+            .map_or_else(|| path.to_string(), |stem| stem.to_string_lossy().to_string()));
+        Ok(Self { name, report })
+    }
+
+    /* ========================================================================================== */
+    /// The reports of the jobs in a batch summary that finished; failed jobs have nothing to merge
+    pub fn load_batch_summary(path: &str) -> Result<Vec<Self>, Box<dyn std::error::Error>> {
+        let content = read_output(path).map_err(|e| format!("Cannot read batch summary '{}': {}", path, e))?;
+        let results: Vec<BatchJobResult> = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid batch summary '{}': {}", path, e))?;
+        results.into_iter()
+            .filter(|result| result.error.is_none())
+            .map(|result| Self::load(&result.output, Some(result.name)))
+            .collect()
+    }
+}
+
+/* ============================================================================================== */
+impl AggregateReport {
+    pub fn from_projects(projects: &[ProjectReport]) -> Self {
+        let mut aggregate = Self::default();
+        // class -> (projects using it, projects where it is unused)
+        let mut verdicts: BTreeMap<&str, (BTreeSet<&str>, BTreeSet<&str>)> = BTreeMap::new();
+
+        for project in projects {
+            let report = &project.report;
+            aggregate.total_classes += report.total_classes;
+            aggregate.unused_classes += report.unused_classes.len();
+            aggregate.projects.push(ProjectTotals {
+                name: project.name.clone(),
+                total_classes: report.total_classes,
+                unused_classes: report.unused_classes.len(),
+            });
+
+            for (file, classes) in &report.by_file {
+                let unused_classes = classes.iter().filter(|class| class.is_unused).count();
+                if unused_classes > 0 {
+                    aggregate.worst_offenders.push(FileOffender {
+                        project: project.name.clone(),
+                        file: file.clone(),
+                        unused_classes,
+                        total_classes: classes.len(),
+                    });
+                }
+            }
+
+            for class in &report.used_classes {
+                verdicts.entry(&class.name).or_default().0.insert(&project.name);
+            }
+            for class in &report.unused_classes {
+                verdicts.entry(&class.name).or_default().1.insert(&project.name);
+            }
+        }
+
+        aggregate.projects.sort_by(|a, b| b.unused_classes.cmp(&a.unused_classes).then_with(|| a.name.cmp(&b.name)));
+        aggregate.worst_offenders.sort_by(|a, b| b.unused_classes.cmp(&a.unused_classes)
+            .then_with(|| a.project.cmp(&b.project))
+            .then_with(|| a.file.cmp(&b.file)));
+        aggregate.worst_offenders.truncate(WORST_OFFENDERS);
+
+        // A class used and unused within the same project is a per-file question, not a cross-repo one
+        aggregate.shared_class_conflicts = verdicts.into_iter()
+            .filter_map(|(class, (used_in, unused_in))| {
+                let unused_in: Vec<String> = unused_in.difference(&used_in).map(|name| name.to_string()).collect();
+                (!used_in.is_empty() && !unused_in.is_empty()).then(|| SharedClassConflict {
+                    class: class.to_string(),
+                    used_in: used_in.iter().map(|name| name.to_string()).collect(),
+                    unused_in,
+                })
+            })
+            .collect();
+
+        aggregate
+    }
+
+    /* ========================================================================================== */
+    pub fn unused_percentage(&self) -> f64 {
+        match self.total_classes {
+            0 => 0.0,
+            total => self.unused_classes as f64 / total as f64 * 100.0,
+        }
+    }
+
+    /* ========================================================================================== */
+    /// HTML for a `.html`/`.htm` path (before any `.gz`/`.zst`), JSON otherwise
+    pub fn write(&self, path: &str, numbers: NumberFormat) -> Result<(), Box<dyn std::error::Error>> {
+        let content = match is_html_path(uncompressed_path(path)) {
+            true => self.render_html(numbers),
+            false => serde_json::to_string_pretty(self)?,
+        };
+        write_output(path, content.as_bytes()).map_err(|e| format!("Cannot write aggregate report '{}': {}", path, e))?;
+        Ok(())
+    }
+
+    /* ========================================================================================== */
+    fn render_html(&self, numbers: NumberFormat) -> String {
+        let mut body = format!(
+            "<h1>Unused CSS across projects</h1>\n\
+             <p><strong>{}</strong> of {} classes unused ({}%) in {} projects.</p>\n",
+            numbers.count(self.unused_classes),
+            numbers.count(self.total_classes),
+            numbers.percentage(self.unused_percentage()),
+            numbers.count(self.projects.len()),
+        );
+
+        body.push_str("<h2>Projects</h2>\n<table><tr><th>Project</th><th class=\"num\">Unused</th><th class=\"num\">Total</th></tr>\n");
+        for project in &self.projects {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                escape_html(&project.name), numbers.count(project.unused_classes), numbers.count(project.total_classes)
+            ));
+        }
+        body.push_str("</table>\n");
+
+        body.push_str("<h2>Worst offenders</h2>\n<table><tr><th>Project</th><th>File</th><th class=\"num\">Unused</th><th class=\"num\">Total</th></tr>\n");
+        for offender in &self.worst_offenders {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td><code>{}</code></td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                escape_html(&offender.project), escape_html(&offender.file),
+                numbers.count(offender.unused_classes), numbers.count(offender.total_classes)
+            ));
+        }
+        body.push_str("</table>\n");
+
+        body.push_str(&format!("<h2>Shared class conflicts ({})</h2>\n", numbers.count(self.shared_class_conflicts.len())));
+        if self.shared_class_conflicts.is_empty() {
+            body.push_str("<p>No class is used in one project and unused in another.</p>\n");
+        } else {
+            body.push_str("<table><tr><th>Class</th><th>Used in</th><th>Unused in</th></tr>\n");
+            for conflict in &self.shared_class_conflicts {
+                body.push_str(&format!(
+                    "<tr><td><code>.{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&conflict.class), escape_html(&conflict.used_in.join(", ")), escape_html(&conflict.unused_in.join(", "))
+                ));
+            }
+            body.push_str("</table>\n");
+        }
+
+        render_page("Unused CSS across projects", &body)
+    }
+}
+
+/* ============================================================================================== */
+fn is_html_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".html") || lower.ends_with(".htm")
+}
@@ -0,0 +1,129 @@
+use flate2::read::GzDecoder;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+const ARCHIVE_SUFFIXES: &[&str] = &[".zip", ".tar", ".tar.gz", ".tgz"];
+// Everything is decompressed up front, so a zip bomb would otherwise take all memory
+const MAX_ENTRY_BYTES: u64 = 32 * 1024 * 1024;
+const MAX_TOTAL_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Text files of a zip or tar(.gz) archive, held in memory.
+/// Entries are keyed as `<archive path>/<entry path>` so reports point into the archive.
+pub struct Archive {
+    root: PathBuf,
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl Archive {
+    pub fn open(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = File::open(path).map_err(|e| format!("Cannot open archive '{}': {}", path, e))?;
+        let root = PathBuf::from(path);
+        let lower = path.to_lowercase();
+
+        let entries = if lower.ends_with(".zip") {
+            read_zip_entries(file)
+        } else if lower.ends_with(".tar") {
+            read_tar_entries(BufReader::new(file))
+        } else {
+            read_tar_entries(GzDecoder::new(BufReader::new(file)))
+        }
+        .map_err(|e| format!("Cannot read archive '{}': {}", path, e))?;
+
+        let files = entries
+            .into_iter()
+            .map(|(name, content)| (root.join(name.trim_start_matches("./")), content))
+            .collect();
+
+        Ok(Self { root, files })
+    }
+
+    /* ========================================================================================== */
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /* ========================================================================================== */
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.keys()
+    }
+
+    /* ========================================================================================== */
+    pub fn read(&self, path: &Path) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+}
+
+/* ============================================================================================== */
+pub fn is_archive_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    ARCHIVE_SUFFIXES.iter().any(|suffix| lower.ends_with(suffix)) && Path::new(path).is_file()
+}
+
+/* ============================================================================================== */
+fn read_zip_entries(file: File) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(file))?;
+    let mut entries = Vec::new();
+    let mut total_bytes = 0;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if !entry.is_file() {
+            continue;
+        }
+        // Entries with `..` or absolute paths have no safe place below the archive root
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let name = name.to_string_lossy().to_string();
+
+        if let Some(content) = read_text_entry(&mut entry, &mut total_bytes)? {
+            entries.push((name, content));
+        }
+    }
+
+    Ok(entries)
+}
+
+/* ============================================================================================== */
+fn read_tar_entries<R: Read>(reader: R) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = Vec::new();
+    let mut total_bytes = 0;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.into_owned();
+        if path.is_absolute() || path.components().any(|c| c == std::path::Component::ParentDir) {
+            continue;
+        }
+        let name = path.to_string_lossy().to_string();
+
+        if let Some(content) = read_text_entry(&mut entry, &mut total_bytes)? {
+            entries.push((name, content));
+        }
+    }
+
+    Ok(entries)
+}
+
+/* ============================================================================================== */
+fn read_text_entry(entry: impl Read, total_bytes: &mut u64) -> Result<Option<String>, String> {
+    // Reading one byte past the cap is enough to tell, whatever size the header claims
+    let mut content = String::new();
+    let readable = entry.take(MAX_ENTRY_BYTES + 1).read_to_string(&mut content).is_ok();
+    // Binary and oversized entries (images, fonts, videos) are skipped like unreadable files on disk
+    if !readable || content.len() as u64 > MAX_ENTRY_BYTES {
+        return Ok(None);
+    }
+
+    *total_bytes += content.len() as u64;
+    if *total_bytes > MAX_TOTAL_BYTES {
+        return Err(format!("more than {} MB of text files; extract it and analyze the directory instead", MAX_TOTAL_BYTES / (1024 * 1024)));
+    }
+    Ok(Some(content))
+}
@@ -0,0 +1,141 @@
+use crate::css_rules::CssRule;
+use crate::duplicate_blocks::BlockLocation;
+use crate::media_queries::is_dead_rule;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+const FONT_FACE_PATTERN: &str = r"(?is)@font-face\s*\{([^}]*)\}";
+const FONT_FAMILY_PATTERN: &str = r"(?i)font-family\s*:\s*([^;}]+)";
+const URL_PATTERN: &str = r#"(?i)url\(\s*(?:"([^"]*)"|'([^']*)'|([^)\s]*))\s*\)"#;
+// Where a family can be applied: `font-family`, the `font` shorthand, and custom properties or
+// SCSS variables holding a font stack for later use
+const FONT_USAGE_PATTERN: &str = r"(?i)(?:^|[\s;{])(?:font-family|font|--[\w-]+|\$[\w-]+)\s*:\s*([^;{}]+)";
+const FONT_FACE_SELECTOR: &str = "@font-face";
+
+/// A `@font-face` declaration and the files it loads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontFace {
+    pub family: String,
+    pub file: String,
+    pub line: usize,
+    /// `src` URLs, data URIs left out
+    pub sources: Vec<String>,
+}
+
+/// A file only referenced from rules that can't match, e.g. a background image of an unused class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnusedAsset {
+    /// As written in `url(…)`
+    pub url: String,
+    pub references: Vec<BlockLocation>,
+}
+
+/* ============================================================================================== */
+/*                      Font faces and files referenced from stylesheets                          */
+/* ============================================================================================== */
+pub fn extract_font_faces(path: &Path, content: &str) -> Vec<FontFace> {
+    let font_face = Regex::new(FONT_FACE_PATTERN).unwrap();
+    let family = Regex::new(FONT_FAMILY_PATTERN).unwrap();
+    let file = path.to_string_lossy().to_string();
+
+    font_face.captures_iter(content)
+        .filter_map(|captures| {
+            let block = captures.get(1).unwrap().as_str();
+            let name = unquote(family.captures(block)?.get(1)?.as_str());
+            Some(FontFace {
+                family: name.to_string(),
+                file: file.clone(),
+                line: content[..captures.get(0).unwrap().start()].matches('\n').count() + 1,
+                sources: urls(block),
+            })
+        })
+        .collect()
+}
+
+/* ============================================================================================== */
+/// Lowercased families named in font declarations and font stack variables, `@font-face` blocks excluded
+pub fn used_font_families(content: &str) -> HashSet<String> {
+    let font_face = Regex::new(FONT_FACE_PATTERN).unwrap();
+    let font_usage = Regex::new(FONT_USAGE_PATTERN).unwrap();
+    let without_faces = font_face.replace_all(content, "");
+
+    font_usage.captures_iter(&without_faces)
+        .flat_map(|captures| {
+            let stack = captures[1].split('!').next().unwrap_or_default().to_lowercase();
+            stack.split(',').flat_map(|family| [unquote(family).to_string(), shorthand_family(family).to_string()]).collect::<Vec<_>>()
+        })
+        .filter(|family| !family.is_empty())
+        .collect()
+}
+
+/* ============================================================================================== */
+pub fn unused_font_faces(font_faces: &[FontFace], used_families: &HashSet<String>) -> Vec<FontFace> {
+    font_faces.iter()
+        .filter(|face| !used_families.contains(&face.family.to_lowercase()))
+        .cloned()
+        .collect()
+}
+
+/* ============================================================================================== */
+/// URLs referenced only from rules targeting unused classes and from unused font faces
+pub fn unused_assets(rules: &[CssRule], unused_classes: &HashSet<&str>, font_faces: &[FontFace], unused_font_faces: &[FontFace]) -> Vec<UnusedAsset> {
+    // url -> (references, referenced from a live rule)
+    let mut assets: BTreeMap<String, (Vec<BlockLocation>, bool)> = BTreeMap::new();
+
+    for rule in rules {
+        let dead = is_dead_rule(rule, unused_classes);
+        for declaration in &rule.declarations {
+            for url in urls(&declaration.value) {
+                let (references, live) = assets.entry(url).or_default();
+                references.push(BlockLocation { selector: rule.selector.clone(), file: rule.file.clone(), line: rule.line });
+                *live |= !dead;
+            }
+        }
+    }
+
+    for face in font_faces {
+        let dead = unused_font_faces.iter().any(|unused| unused.file == face.file && unused.line == face.line);
+        for url in &face.sources {
+            let (references, live) = assets.entry(url.clone()).or_default();
+            references.push(BlockLocation { selector: FONT_FACE_SELECTOR.to_string(), file: face.file.clone(), line: face.line });
+            *live |= !dead;
+        }
+    }
+
+    assets.into_iter()
+        .filter(|(_, (_, live))| !live)
+        .map(|(url, (references, _))| UnusedAsset { url, references })
+        .collect()
+}
+
+/* ============================================================================================== */
+fn urls(text: &str) -> Vec<String> {
+    let url = Regex::new(URL_PATTERN).unwrap();
+    url.captures_iter(text)
+        .filter_map(|captures| captures.get(1).or(captures.get(2)).or(captures.get(3)))
+        .map(|url| url.as_str().trim())
+        // Inline data has no file to delete, and `#id` points into the same document
+        .filter(|url| !url.is_empty() && !url.starts_with("data:") && !url.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/* ============================================================================================== */
+/// The family at the end of a `font` shorthand, which puts size and style first: `italic 700 1rem/1.2 "Open Sans"`
+fn shorthand_family(value: &str) -> &str {
+    let value = value.trim();
+    match value.chars().last() {
+        Some(quote @ ('"' | '\'')) => {
+            let inner = &value[..value.len() - 1];
+            inner.rfind(quote).map_or(inner, |start| &inner[start + 1..]).trim()
+        }
+        _ => value.rsplit(char::is_whitespace).next().unwrap_or_default(),
+    }
+}
+
+/* ============================================================================================== */
+fn unquote(text: &str) -> &str {
+    text.trim().trim_matches(|c| c == '"' || c == '\'').trim()
+}
@@ -0,0 +1,56 @@
+use crate::config::{Config, GuiConfig};
+use crate::i18n::{format_message, Locale};
+use crate::traits::ConfigConfigurable;
+use crate::unused_detector::UnusedDetector;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Outcome of one background re-scan of a pinned project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RescanResult {
+    pub directory: String,
+    pub total_classes: usize,
+    pub unused_classes: usize,
+    pub previous_unused: Option<usize>,
+    /// Text for the native notification; only set when the unused count changed
+    pub notification: Option<String>,
+}
+
+/* ============================================================================================== */
+/*                    Pinned projects whose interval has passed since their last scan             */
+/* ============================================================================================== */
+pub fn due_projects(gui: &GuiConfig, last_scans: &HashMap<String, u64>, now: u64) -> Vec<String> {
+    let interval = gui.rescan_interval_minutes.saturating_mul(60);
+    // An interval of 0 turns background scanning off
+    if interval == 0 {
+        return Vec::new();
+    }
+
+    gui.pinned_projects.iter()
+        .filter(|project| last_scans.get(*project).is_none_or(|last| now.saturating_sub(*last) >= interval))
+        .cloned()
+        .collect()
+}
+
+/* ============================================================================================== */
+pub fn rescan_project(directory: &str, previous_unused: Option<usize>) -> Result<RescanResult, Box<dyn std::error::Error>> {
+    let config = Config::load_for_directory(directory);
+    let locale = Locale::detect(config.output.locale.as_deref());
+    let report = UnusedDetector::new(directory.to_string())
+        .with_config(config)
+        .generate_report()?;
+
+    let unused_classes = report.unused_classes.len();
+    // The first scan only sets the baseline, there is nothing to compare with yet
+    let notification = previous_unused
+        .filter(|previous| *previous != unused_classes)
+        .map(|previous| format_message(locale, "gui.unused_changed", &[&directory, &previous, &unused_classes]));
+
+    Ok(RescanResult {
+        directory: directory.to_string(),
+        total_classes: report.total_classes,
+        unused_classes,
+        previous_unused,
+        notification,
+    })
+}
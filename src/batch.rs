@@ -0,0 +1,256 @@
+use crate::config::Config;
+use crate::exit_status::{ConfigError, ExitStatus};
+use crate::output_file::{write_output, STDOUT_PATH};
+use crate::traits::{ConfigConfigurable, ProcessorBuilder};
+use crate::unused_detector::UnusedDetector;
+use crate::system_limits::available_cores;
+use crate::utils::create_thread_pool;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+/// A list of analyses to run in one go, e.g. from a scheduled container auditing many repositories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchManifest {
+    /// Jobs run at the same time; 1 runs them one after another
+    #[serde(default = "default_parallel")]
+    pub parallel: usize,
+    /// Where the outcome of every job is written as JSON, next to the per-job reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub jobs: Vec<BatchJob>,
+}
+
+/// One directory to analyze and where its report goes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchJob {
+    /// Label in progress and summary; defaults to the directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub directory: String,
+    /// Config file for this job; without one, the directory's own tag-finder.toml is used if it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// JSON report, the same as `unused-classes --output`; `.gz`/`.zst` compress it
+    pub output: String,
+}
+
+/// Outcome of one job; a failed job doesn't stop the others
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchJobResult {
+    pub name: String,
+    pub directory: String,
+    pub output: String,
+    pub total_classes: usize,
+    pub unused_classes: usize,
+    /// No error-level rule has findings
+    pub passed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// How `error` would have ended a single run, config or I/O
+    #[serde(skip)]
+    pub failure: Option<ExitStatus>,
+    pub elapsed_ms: u64,
+}
+
+fn default_parallel() -> usize {
+    1
+}
+
+/* ============================================================================================== */
+impl BatchManifest {
+    /// Relative paths in the manifest are taken from the manifest's own directory, so a mounted
+    /// jobs folder works wherever the container's working directory is
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Cannot read batch manifest '{}': {}", path, e))?;
+        let mut manifest: BatchManifest = serde_json::from_str(&content)
+            .map_err(|e| ConfigError::new(format!("Invalid batch manifest '{}': {}", path, e)))?;
+        let base = Path::new(path).parent().unwrap_or(Path::new(""));
+
+        manifest.summary = manifest.summary.map(|summary| resolve(base, &summary));
+        for job in &mut manifest.jobs {
+            job.directory = resolve(base, &job.directory);
+            job.config = job.config.as_deref().map(|config| resolve(base, config));
+            job.output = resolve(base, &job.output);
+        }
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /* ========================================================================================== */
+    fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.jobs.is_empty() {
+            return Err(ConfigError::new("Batch manifest has no jobs").into());
+        }
+        if self.parallel == 0 {
+            return Err(ConfigError::new("'parallel' in the batch manifest must be at least 1").into());
+        }
+        // Reports of jobs running side by side would interleave on stdout or overwrite each other
+        let mut outputs: Vec<&str> = self.jobs.iter().map(|job| job.output.as_str()).collect();
+        if outputs.contains(&STDOUT_PATH) {
+            return Err(ConfigError::new(format!("Batch jobs need an output file each, '{}' is not allowed", STDOUT_PATH)).into());
+        }
+        outputs.sort_unstable();
+        if let Some(duplicate) = outputs.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(ConfigError::new(format!("More than one batch job writes to '{}'", duplicate[0])).into());
+        }
+        Ok(())
+    }
+
+    /* ========================================================================================== */
+    /// Runs every job and hands each result to `on_finished` as soon as it is done; the returned
+    /// results are in manifest order
+    pub fn run<F>(&self, on_finished: F) -> Result<Vec<BatchJobResult>, Box<dyn std::error::Error>>
+    where
+        F: Fn(&BatchJobResult) + Send + Sync,
+    {
+        let parallel = self.parallel.min(self.jobs.len());
+        // Jobs side by side share the cores instead of each starting a thread per core
+        let threads_per_job = (parallel > 1).then(|| (available_cores() / parallel).max(1));
+        let run_job = |job: &BatchJob| {
+            let result = job.run(threads_per_job);
+            on_finished(&result);
+            result
+        };
+
+        let results: Vec<BatchJobResult> = match parallel {
+            1 => self.jobs.iter().map(run_job).collect(),
+            _ => create_thread_pool(Some(parallel))?.install(|| self.jobs.par_iter().map(run_job).collect()),
+        };
+
+        if let Some(summary) = &self.summary {
+            write_output(summary, serde_json::to_string_pretty(&results)?.as_bytes())
+                .map_err(|e| format!("Cannot write batch summary '{}': {}", summary, e))?;
+        }
+        Ok(results)
+    }
+}
+
+/* ============================================================================================== */
+impl BatchJob {
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.directory)
+    }
+
+    /* ========================================================================================== */
+    fn run(&self, threads: Option<usize>) -> BatchJobResult {
+        let started = Instant::now();
+        let mut result = BatchJobResult {
+            name: self.label().to_string(),
+            directory: self.directory.clone(),
+            output: self.output.clone(),
+            total_classes: 0,
+            unused_classes: 0,
+            passed: false,
+            error: None,
+            failure: None,
+            elapsed_ms: 0,
+        };
+
+        if let Err(e) = self.analyze(threads, &mut result) {
+            result.error = Some(e.to_string());
+            result.failure = Some(ExitStatus::for_error(e.as_ref()));
+        }
+        result.elapsed_ms = started.elapsed().as_millis() as u64;
+        result
+    }
+
+    /* ========================================================================================== */
+    fn analyze(&self, threads: Option<usize>, result: &mut BatchJobResult) -> Result<(), Box<dyn std::error::Error>> {
+        let config = self.load_config()?;
+        let rules = config.rules.clone();
+        let report = UnusedDetector::new(self.directory.clone())
+            .configure_threads(threads)
+            .with_config(config)
+            .generate_report()?;
+
+        write_output(&self.output, serde_json::to_string_pretty(&report)?.as_bytes())
+            .map_err(|e| format!("Cannot write report '{}': {}", self.output, e))?;
+        result.total_classes = report.total_classes;
+        result.unused_classes = report.unused_classes.len();
+        result.passed = report.failed_rules(&rules).is_empty();
+        Ok(())
+    }
+
+    /* ========================================================================================== */
+    fn load_config(&self) -> Result<Config, Box<dyn std::error::Error>> {
+        let config_path = self.config.clone().or_else(|| Config::find_config_file_in(&self.directory));
+        let config = match (config_path, self.profile.as_deref()) {
+            (Some(config_path), profile) => Config::from_file_with_profile(&config_path, profile)
+                .map_err(|e| ConfigError::new(format!("Cannot load config '{}': {}", config_path, e)))?,
+            (None, Some(profile)) => return Err(ConfigError::new(format!("Profile '{}' needs a config file, none was found", profile)).into()),
+            (None, None) => Config::default(),
+        };
+        // Environment variables of the container apply to every job
+        config.with_env_overrides()
+    }
+}
+
+/* ============================================================================================== */
+/// A job that didn't finish outweighs findings of the ones that did, and I/O failures outweigh
+/// config errors
+pub fn batch_exit_status(results: &[BatchJobResult]) -> ExitStatus {
+    let failures: Vec<ExitStatus> = results.iter().filter_map(|result| result.failure).collect();
+    if failures.contains(&ExitStatus::IoError) {
+        ExitStatus::IoError
+    } else if !failures.is_empty() {
+        ExitStatus::ConfigError
+    } else {
+        ExitStatus::from_passed(results.iter().all(|result| result.passed))
+    }
+}
+
+/* ============================================================================================== */
+fn resolve(base: &Path, path: &str) -> String {
+    if path == STDOUT_PATH || Path::new(path).is_absolute() {
+        return path.to_string();
+    }
+    base.join(path).to_string_lossy().to_string()
+}
+
+/* ============================================================================================== */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_with_a_broken_config_is_a_config_error() {
+        let directory = std::env::temp_dir().join(format!("tag-finder-batch-config-{}", std::process::id()));
+        fs::create_dir_all(directory.join("site")).unwrap();
+        fs::write(directory.join("site").join("styles.css"), ".card { color: red; }\n").unwrap();
+        fs::write(directory.join("broken.toml"), "[scan\nmax_files = 10\n").unwrap();
+        fs::write(directory.join("jobs.json"), r#"{ "jobs": [
+            { "directory": "site", "config": "broken.toml", "output": "site.json" },
+            { "directory": "site", "profile": "ci", "output": "site-ci.json" }
+        ] }"#).unwrap();
+
+        let manifest = BatchManifest::from_file(&directory.join("jobs.json").to_string_lossy()).unwrap();
+        let results = manifest.run(|_| {}).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(results.iter().all(|result| result.failure == Some(ExitStatus::ConfigError)), "{:?}", results);
+        assert_eq!(batch_exit_status(&results), ExitStatus::ConfigError);
+    }
+
+    #[test]
+    fn io_failures_outweigh_config_errors() {
+        let result = |failure: Option<ExitStatus>| BatchJobResult {
+            name: "site".to_string(),
+            directory: "site".to_string(),
+            output: "site.json".to_string(),
+            total_classes: 0,
+            unused_classes: 0,
+            passed: failure.is_none(),
+            error: failure.map(|_| "failed".to_string()),
+            failure,
+            elapsed_ms: 0,
+        };
+        assert_eq!(batch_exit_status(&[result(None)]), ExitStatus::Clean);
+        assert_eq!(batch_exit_status(&[result(None), result(Some(ExitStatus::ConfigError))]), ExitStatus::ConfigError);
+        assert_eq!(batch_exit_status(&[result(Some(ExitStatus::IoError)), result(Some(ExitStatus::ConfigError))]), ExitStatus::IoError);
+    }
+}
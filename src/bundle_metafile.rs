@@ -0,0 +1,81 @@
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use crate::utils::has_extension;
+
+// Only script modules go through the bundler, markup and templates are always considered live
+const SCRIPT_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs"];
+
+pub struct BundleMetafile {
+    modules: HashSet<String>,
+}
+
+impl BundleMetafile {
+    /* ============================ Load esbuild metafile or webpack stats ============================ */
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read bundle metafile '{}': {}", path, e))?;
+        let json: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid bundle metafile '{}': {}", path, e))?;
+
+        let mut modules = HashSet::new();
+
+        // esbuild: { "inputs": { "src/app.js": {...} } }
+        if let Some(inputs) = json.get("inputs").and_then(|i| i.as_object()) {
+            modules.extend(inputs.keys().map(|name| normalize_module_name(name)));
+        }
+
+        // webpack: { "modules": [{ "name": "./src/app.js" }] } and per-chunk module lists
+        collect_webpack_modules(json.get("modules"), &mut modules);
+        if let Some(chunks) = json.get("chunks").and_then(|c| c.as_array()) {
+            for chunk in chunks {
+                collect_webpack_modules(chunk.get("modules"), &mut modules);
+            }
+        }
+
+        if modules.is_empty() {
+            return Err(format!("No bundled modules found in '{}' (expected esbuild metafile or webpack stats)", path).into());
+        }
+
+        Ok(Self { modules })
+    }
+
+    /* ========================================================================================== */
+    pub fn is_dead_module(&self, file_path: &Path, project_root: &str) -> bool {
+        if !has_extension(file_path, SCRIPT_EXTENSIONS) {
+            return false;
+        }
+
+        let relative = file_path.strip_prefix(project_root).unwrap_or(file_path);
+        let name = normalize_module_name(&relative.to_string_lossy());
+        !self.modules.contains(&name)
+    }
+
+    /* ========================================================================================== */
+    pub fn module_count(&self) -> usize {
+        self.modules.len()
+    }
+}
+
+/* ============================================================================================== */
+fn collect_webpack_modules(modules: Option<&Value>, into: &mut HashSet<String>) {
+    let Some(modules) = modules.and_then(|m| m.as_array()) else {
+        return;
+    };
+
+    for module in modules {
+        if let Some(name) = module.get("name").and_then(|n| n.as_str()) {
+            into.insert(normalize_module_name(name));
+        }
+        // Concatenated modules list their members separately
+        collect_webpack_modules(module.get("modules"), into);
+    }
+}
+
+/* ============================================================================================== */
+fn normalize_module_name(name: &str) -> String {
+    // "./src/app.js + 3 modules" -> "src/app.js"
+    let name = name.split(" + ").next().unwrap_or(name);
+    name.replace('\\', "/").trim_start_matches("./").to_string()
+}
@@ -0,0 +1,29 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Set from the Ctrl-C handler, which can't reach the analysis any other way
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Returned by the analysis and the report writer once a cancel was requested
+#[derive(Debug)]
+pub struct Cancelled;
+
+/* ============================================================================================== */
+/// Asks running scans and writes to stop; returns whether one was already requested
+pub fn request_cancel() -> bool {
+    CANCELLED.swap(true, Ordering::SeqCst)
+}
+
+/* ============================================================================================== */
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cancelled")
+    }
+}
+
+impl Error for Cancelled {}
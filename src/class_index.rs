@@ -0,0 +1,92 @@
+use crate::exit_status::ConfigError;
+use crate::explanation::UsageHit;
+use crate::output_file::{read_output, write_output};
+use crate::output_style::OutputStyle;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Bumped when the index layout changes in a way older readers can't handle
+pub const CLASS_INDEX_VERSION: u32 = 1;
+
+/// Every class with all of its definitions and usages, for editor plugins and other tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassIndex {
+    pub version: u32,
+    /// Directory the paths in the index are relative to (as given on the command line)
+    pub root: String,
+    /// Sorted by name, so indexes of the same tree diff cleanly
+    pub classes: BTreeMap<String, IndexedClass>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexedClass {
+    pub definitions: Vec<UsageHit>,
+    /// From an `@doc` comment above a definition, the first one in extraction order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    /// Lines in usage files mentioning the class, after style sections are stripped
+    pub usages: Vec<UsageHit>,
+}
+
+impl ClassIndex {
+    pub fn new(root: String) -> Self {
+        Self { version: CLASS_INDEX_VERSION, root, classes: BTreeMap::new() }
+    }
+
+    /* ========================================================================================== */
+    pub fn get(&self, class_name: &str) -> Option<&IndexedClass> {
+        self.classes.get(class_name.trim_start_matches('.'))
+    }
+
+    /* ========================================================================================== */
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        write_output(path, serde_json::to_string_pretty(self)?.as_bytes())
+            .map_err(|e| format!("Cannot write class index '{}': {}", path, e).into())
+    }
+
+    /* ========================================================================================== */
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = read_output(path)
+            .map_err(|e| format!("Cannot read class index '{}': {}", path, e))?;
+        let index: ClassIndex = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid class index '{}': {}", path, e))?;
+        if index.version > CLASS_INDEX_VERSION {
+            return Err(ConfigError::new(format!("Class index '{}' has version {}, this build reads up to {}", path, index.version, CLASS_INDEX_VERSION)).into());
+        }
+        Ok(index)
+    }
+
+    /* ========================================================================================== */
+    pub fn usage_count(&self) -> usize {
+        self.classes.values().map(|class| class.usages.len()).sum()
+    }
+
+    /* ========================================================================================== */
+    /// Definitions and usages of one class, as `file:line:column` so terminals and editors can jump there
+    pub fn print_class(&self, class_name: &str, style: &OutputStyle) {
+        let class_name = class_name.trim_start_matches('.');
+        println!("\n{} .{}", style.icon("🔎"), class_name);
+        style.print_header_line();
+
+        let Some(class) = self.get(class_name) else {
+            println!("{}", style.message("where.not_found", &[&class_name]));
+            return;
+        };
+
+        if let Some(doc) = &class.doc {
+            println!("{} {}\n", style.icon("📖"), doc);
+        }
+        println!("{} {}", style.icon("📁"), style.message("where.definitions", &[&class.definitions.len()]));
+        for definition in &class.definitions {
+            println!("  {}:{}:{}", definition.file, definition.line, definition.column);
+        }
+
+        println!("\n{} {}", style.icon("🎯"), style.message("where.usages", &[&class.usages.len()]));
+        if class.usages.is_empty() {
+            println!("  {}", style.text("where.no_usages"));
+        }
+        for usage in &class.usages {
+            println!("  {}:{}:{}", usage.file, usage.line, usage.column);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::output_file::{uncompressed_path, write_output};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Defined classes linked by how often they're applied together on one element
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoUsageGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub class: String,
+    pub used: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    /// Elements carrying both classes
+    pub weight: usize,
+}
+
+impl CoUsageGraph {
+    /* ========================================================================================== */
+    /// `pairs` holds each class pair once, in sorted order, with the elements they share
+    pub fn new(defined: &[&str], used: &HashSet<&str>, pairs: &HashMap<(String, String), usize>) -> Self {
+        let nodes: BTreeMap<&str, bool> = defined.iter().map(|class| (*class, used.contains(class))).collect();
+
+        let mut edges: Vec<GraphEdge> = pairs.iter()
+            .map(|((source, target), weight)| GraphEdge { source: source.clone(), target: target.clone(), weight: *weight })
+            .collect();
+        edges.sort_by(|a, b| a.source.cmp(&b.source).then_with(|| a.target.cmp(&b.target)));
+
+        Self {
+            nodes: nodes.into_iter().map(|(class, used)| GraphNode { class: class.to_string(), used }).collect(),
+            edges,
+        }
+    }
+
+    /* ========================================================================================== */
+    /// Graphviz source; unused classes are drawn dashed and grey so dead clusters stand out
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph classes {\n    node [shape=box, fontname=\"monospace\"];\n");
+        for node in &self.nodes {
+            match node.used {
+                true => dot.push_str(&format!("    \"{}\";\n", escape(&node.class))),
+                false => dot.push_str(&format!("    \"{}\" [style=dashed, color=grey, fontcolor=grey];\n", escape(&node.class))),
+            }
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!("    \"{}\" -- \"{}\" [weight={}, label=\"{}\"];\n",
+                escape(&edge.source), escape(&edge.target), edge.weight, edge.weight));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /* ========================================================================================== */
+    /// JSON for `.json` paths (Gephi, scripts), DOT for anything else; `.gz`/`.zst` compress either
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let is_json = Path::new(uncompressed_path(path)).extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let content = match is_json {
+            true => serde_json::to_string_pretty(self)?,
+            false => self.to_dot(),
+        };
+        write_output(path, content.as_bytes()).map_err(|e| format!("Cannot write co-usage graph '{}': {}", path, e).into())
+    }
+}
+
+/* ============================================================================================== */
+/*                          Class pairs applied together on one element                           */
+/* ============================================================================================== */
+pub fn record_co_usage(pairs: &mut HashMap<(String, String), usize>, element: &HashSet<&str>, defined: &HashSet<&str>) {
+    let mut classes: Vec<&str> = element.iter().copied().filter(|class| defined.contains(class)).collect();
+    classes.sort_unstable();
+
+    for (index, source) in classes.iter().enumerate() {
+        for target in &classes[index + 1..] {
+            *pairs.entry((source.to_string(), target.to_string())).or_default() += 1;
+        }
+    }
+}
+
+/* ============================================================================================== */
+fn escape(class: &str) -> String {
+    class.replace('\\', "\\\\").replace('"', "\\\"")
+}
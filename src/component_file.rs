@@ -0,0 +1,23 @@
+use regex::Regex;
+
+/* ============================================================================================== */
+/*                  Stylesheet view of a component: only its <style> blocks remain                */
+/* ============================================================================================== */
+pub fn component_style_text(content: &str) -> String {
+    // Astro, MDX (and Vue/Svelte) components define classes in <style> and use them in the markup,
+    // MJML email templates in <mj-style>. Everything outside the blocks is reduced to its newlines
+    // so definitions keep their line numbers.
+    let style_block = Regex::new(r"(?is)<(?:mj-)?style\b[^>]*>(.*?)</(?:mj-)?style>").unwrap();
+    let mut text = String::with_capacity(content.len());
+    let mut position = 0;
+
+    for captures in style_block.captures_iter(content) {
+        let styles = captures.get(1).unwrap();
+        text.extend(content[position..styles.start()].chars().filter(|c| *c == '\n'));
+        text.push_str(styles.as_str());
+        position = styles.end();
+    }
+
+    text.extend(content[position..].chars().filter(|c| *c == '\n'));
+    text
+}
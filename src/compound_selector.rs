@@ -0,0 +1,117 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::LazyLock;
+
+// Two or more chained classes like `.promo.banner--xmas`; group 1 catches a leading `&`
+const COMPOUND_PATTERN: &str = r"(&?)((?:\.-?[_a-zA-Z][\w-]*){2,})";
+// Static class lists on one element: class="…", className='…', className={"…"} and className={`…`}.
+// `\b` also matches after a hyphen, so MJML's css-class="…" counts too.
+const CLASS_ATTRIBUTE_PATTERN: &str = r#"\b(?:class|className)\s*=\s*(?:"([^"]*)"|'([^']*)'|\{\s*["'`]([^"'`]*)["'`]\s*\})"#;
+// Classes added at runtime can end up on any element, so their combinations can't be checked statically
+const SCRIPT_APPLIED_PATTERN: &str = r#"classList\.(?:add|toggle|replace)\(([^)]*)\)"#;
+
+// Compiled once, the usage scan runs these for every file
+static CLASS_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(CLASS_ATTRIBUTE_PATTERN).unwrap());
+static SCRIPT_APPLIED: LazyLock<Regex> = LazyLock::new(|| Regex::new(SCRIPT_APPLIED_PATTERN).unwrap());
+static STRING_LITERAL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"["'`]([^"'`]+)["'`]"#).unwrap());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompoundSelector {
+    /// The chained part of the selector, e.g. `.promo.banner--xmas`
+    pub selector: String,
+    pub classes: Vec<String>,
+    pub file: String,
+    pub line: usize,
+}
+
+impl CompoundSelector {
+    /* ========================================================================================== */
+    pub fn is_applied_to(&self, elements: &[HashSet<&str>]) -> bool {
+        elements.iter().any(|element| self.classes.iter().all(|class| element.contains(class.as_str())))
+    }
+}
+
+/* ============================================================================================== */
+/*                               Compound selectors in a stylesheet                               */
+/* ============================================================================================== */
+pub fn extract_compound_selectors(path: &Path, content: &str) -> Vec<CompoundSelector> {
+    let comment = Regex::new(r"(?s)/\*.*?\*/").unwrap();
+    let compound = Regex::new(COMPOUND_PATTERN).unwrap();
+    // Comments become spaces of the same length so offsets still map to lines
+    let content = comment.replace_all(content, |caps: &regex::Captures| {
+        caps[0].chars().map(|c| if c == '\n' { '\n' } else { ' ' }).collect::<String>()
+    });
+
+    let file = path.to_string_lossy().to_string();
+    let mut selectors = Vec::new();
+    let mut seen = HashSet::new();
+    let mut segment_start = 0;
+
+    // Only the text right before a `{` is a selector, declarations and at-rule preludes are not
+    for (brace, _) in content.match_indices('{') {
+        let prelude_start = content[segment_start..brace]
+            .rfind(['}', ';', '{'])
+            .map_or(segment_start, |offset| segment_start + offset + 1);
+        segment_start = brace + 1;
+
+        let prelude = &content[prelude_start..brace];
+        if prelude.trim_start().starts_with('@') {
+            continue;
+        }
+
+        for captures in compound.captures_iter(prelude) {
+            // `&.is-open.is-active` also depends on the parent selector, which isn't known here
+            if !captures[1].is_empty() {
+                continue;
+            }
+
+            let chain = captures.get(2).unwrap();
+            let classes: Vec<String> = chain.as_str()
+                .split('.')
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect();
+            if !seen.insert(classes.clone()) {
+                continue;
+            }
+
+            let offset = prelude_start + chain.start();
+            selectors.push(CompoundSelector {
+                selector: chain.as_str().to_string(),
+                classes,
+                file: file.clone(),
+                line: content[..offset].matches('\n').count() + 1,
+            });
+        }
+    }
+
+    selectors
+}
+
+/* ============================================================================================== */
+/*                       Classes applied together, one set per element                            */
+/* ============================================================================================== */
+pub fn element_class_sets(content: &str) -> Vec<HashSet<&str>> {
+    CLASS_ATTRIBUTE
+        .captures_iter(content)
+        .filter_map(|captures| captures.get(1).or(captures.get(2)).or(captures.get(3)))
+        .map(|value| {
+            value.as_str()
+                .split_whitespace()
+                .filter(|token| !token.contains('{') && !token.contains('}'))
+                .collect::<HashSet<&str>>()
+        })
+        .filter(|classes| classes.len() > 1)
+        .collect()
+}
+
+/* ============================================================================================== */
+pub fn script_applied_classes(content: &str) -> HashSet<&str> {
+    SCRIPT_APPLIED
+        .captures_iter(content)
+        .flat_map(|captures| STRING_LITERAL.captures_iter(captures.get(1).unwrap().as_str()).collect::<Vec<_>>())
+        .flat_map(|captures| captures.get(1).unwrap().as_str().split_whitespace())
+        .collect()
+}
@@ -1,11 +1,28 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
-use crate::has_extension;
+use crate::scan_filter::ScanFilter;
+use crate::exit_status::ConfigError;
+use crate::file_walker::DEFAULT_MAX_FILES;
+use crate::output_file::write_output;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
     pub scan: ScanConfig,
+    #[serde(default)]
+    pub analysis: AnalysisConfig,
+    #[serde(default)]
+    pub gui: GuiConfig,
+    #[serde(default)]
+    pub notify: NotifyConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub rules: RulesConfig,
+    /// Team or component owning the stylesheets matched by each glob, e.g. `"src/components/Button/**" = "design-system"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub owners: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -16,10 +33,285 @@ pub struct ScanConfig {
     pub include_extensions: Vec<String>,
     #[serde(default = "default_css_extensions")]
     pub css_extensions: Vec<String>,
+    /// Components with their own <style> blocks (Astro, MDX): definitions and usage in one file
+    #[serde(default = "default_component_extensions")]
+    pub component_extensions: Vec<String>,
+    /// JSON/YAML files (CMS content, i18n, storybook args) whose string values count as usage
+    #[serde(default)]
+    pub data_extensions: Vec<String>,
+    /// Directory levels below the scanned directory to descend into (unlimited when unset)
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// Stop collecting files after this many, 0 for no limit
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+    /// Fail when a directory or file can't be read during the walk, instead of warning and skipping it
+    #[serde(default)]
+    pub strict_walk: bool,
+    /// Threads reading files; worth raising well above the core count on slow network drives
+    #[serde(default)]
+    pub io_threads: Option<usize>,
+    /// Threads parsing and matching file contents (defaults to the number of cores)
+    #[serde(default)]
+    pub cpu_threads: Option<usize>,
+    /// Named groups of usage files, checked in order; the first match wins
+    #[serde(default = "default_categories")]
+    pub categories: Vec<FileCategoryConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FileCategoryConfig {
+    pub name: String,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Directory names (or "a/b" paths) below the scanned directory
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// File name endings before the extension, like ".test" in `Button.test.tsx`
+    #[serde(default)]
+    pub stem_suffixes: Vec<String>,
+    #[serde(default)]
+    pub policy: UsagePolicy,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UsagePolicy {
+    /// Counts as usage
+    #[default]
+    Usage,
+    /// Counts as usage, but classes used only here are reported as "used only in <category>"
+    Weak,
+    /// Never counts as usage
+    Ignore,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AnalysisConfig {
+    #[serde(default)]
+    pub naming_convention: NamingConvention,
+    /// Regex used to extract class definitions, capture group 1 is the class name
+    #[serde(default = "default_class_pattern")]
+    pub class_pattern: String,
+    #[serde(default = "default_min_class_length")]
+    pub min_class_length: usize,
+    /// Skip purely numeric names like `.5` picked up from decimals
+    #[serde(default = "default_ignore_numeric")]
+    pub ignore_numeric: bool,
+    /// Generic names (row, col, btn) whose unused findings are reported as low confidence
+    #[serde(default)]
+    pub stop_words: Vec<String>,
+    /// Cascade layers left out of the analysis, sublayers included (`utilities` covers `utilities.spacing`)
+    #[serde(default)]
+    pub exclude_layers: Vec<String>,
+    /// Expand SCSS with the embedded Sass compiler before extracting classes, so `@each` loops
+    /// and mixins contribute the selectors they generate
+    #[serde(default)]
+    pub compile_scss: bool,
+    /// Extra directories for `@use`/`@import` while compiling, relative to the project directory
+    #[serde(default)]
+    pub scss_load_paths: Vec<String>,
+    /// Named sets of framework-provided or runtime-generated class families, e.g. `["bootstrap5"]`
+    #[serde(default)]
+    pub presets: Vec<String>,
+    /// Directories with `<name>.toml` presets that replace or add to the built-in ones, relative to the project directory
+    #[serde(default)]
+    pub preset_dirs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NamingConvention {
+    /// `block__element--modifier`
+    Bem,
+    /// `ComponentName-descendent--modifier` plus `is-*` state classes
+    Suit,
+    /// Generic prefix/suffix grouping
+    #[default]
+    None,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GuiConfig {
+    #[serde(default)]
+    pub theme: Theme,
+    /// Start analysis right away when a folder is dropped onto the window
+    #[serde(default)]
+    pub analyze_on_drop: bool,
+    #[serde(default)]
+    pub shortcuts: ShortcutsConfig,
+    /// Command that opens a class definition; `{file}` and `{line}` are filled in
+    #[serde(default = "default_editor_command")]
+    pub editor: String,
+    /// Keep running in the system tray when the window is closed
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+    /// Projects re-scanned in the background, with a notification when their unused count changes
+    #[serde(default)]
+    pub pinned_projects: Vec<String>,
+    /// Minutes between background scans of a pinned project; 0 turns them off
+    #[serde(default = "default_rescan_interval_minutes")]
+    pub rescan_interval_minutes: u64,
+}
+
+/// Key bindings of the desktop app, in Tauri accelerator syntax (`CmdOrCtrl+Shift+E`)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ShortcutsConfig {
+    pub focus_search: String,
+    pub run_analysis: String,
+    pub expand_all: String,
+    pub collapse_all: String,
+    pub open_in_editor: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Follow the OS dark mode setting
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NotifyConfig {
+    /// Notify when more than this many classes are unused
+    pub max_unused: Option<usize>,
+    /// Notify when the unused count grew by more than this since the previous run
+    pub max_increase: Option<usize>,
+    /// Also send the markdown report in the payload
+    #[serde(default)]
+    pub include_markdown: bool,
+    /// Where the previous run's unused count is remembered
+    #[serde(default = "default_notify_state_file")]
+    pub state_file: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OutputConfig {
+    /// Replace emoji and box characters with plain ASCII
+    #[serde(default)]
+    pub ascii: bool,
+    /// Width of header separator lines
+    #[serde(default = "default_output_width")]
+    pub width: usize,
+    /// Message language ("en", "nl"); falls back to TAG_FINDER_LANG, then LANG
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Pager for long reports on a terminal (falls back to $PAGER, then `less -FRX`); "" turns paging off
+    #[serde(default)]
+    pub pager: Option<String>,
+    /// Digit grouping and decimal mark for counts and percentages ("de", "fr-CH", "plain");
+    /// follows the message language when unset
+    #[serde(default)]
+    pub number_locale: Option<String>,
+    /// Order of files and classes in detailed, by-file and JSON reports
+    #[serde(default)]
+    pub sort: ReportSort,
+    /// Declarations shown for each unused class in detailed and JSON reports; 0 leaves them out
+    #[serde(default = "default_preview_declarations")]
+    pub preview_declarations: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportSort {
+    /// Files with the most unused classes first
+    Count,
+    /// Classes alphabetically
+    Name,
+    /// Files alphabetically, classes by line
+    #[default]
+    File,
+    /// Files with the highest share of unused classes first
+    Percentage,
+}
+
+/// How seriously each kind of finding is taken; any finding of an `error` rule fails the run
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct RulesConfig {
+    /// Classes defined in a stylesheet but never used
+    #[serde(default = "default_warn_severity")]
+    pub unused_class: Severity,
+    /// Classes only used from script modules the bundler dropped
+    #[serde(default = "default_warn_severity")]
+    pub dead_code_usage: Severity,
+    /// Classes only used in weak categories such as tests or docs
+    #[serde(default = "default_info_severity")]
+    pub used_only_in_category: Severity,
+    /// Compound selectors whose classes never appear on one element
+    #[serde(default = "default_info_severity")]
+    pub dead_combination: Severity,
+    /// `[data-*]` selectors whose attribute never appears in markup or scripts
+    #[serde(default = "default_warn_severity")]
+    pub unused_data_attribute: Severity,
+    /// `@font-face` families never named in a font declaration
+    #[serde(default = "default_warn_severity")]
+    pub unused_font_face: Severity,
+    /// Files referenced only from rules that can't match
+    #[serde(default = "default_info_severity")]
+    pub unused_asset: Severity,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Not reported as a rule finding
+    Off,
+    Info,
+    Warn,
+    /// Findings make `unused-classes` exit with status 1
+    Error,
 }
 
 /* =================================== Default value functions ================================== */
 
+// Escapes are part of the name, so Tailwind's `.md\:flex` and `.\32xl\:p-4` come out whole
+pub const DEFAULT_CLASS_PATTERN: &str = r"\.((?:[a-zA-Z]|\\[0-9a-fA-F]{1,6} ?|\\[^0-9a-fA-F\r\n])(?:[a-zA-Z0-9_-]|\\[0-9a-fA-F]{1,6} ?|\\[^0-9a-fA-F\r\n])*)";
+
+fn default_class_pattern() -> String {
+    DEFAULT_CLASS_PATTERN.to_string()
+}
+
+fn default_min_class_length() -> usize {
+    2
+}
+
+fn default_ignore_numeric() -> bool {
+    true
+}
+
+fn default_notify_state_file() -> String {
+    ".tag-finder-notify.json".to_string()
+}
+
+fn default_output_width() -> usize {
+    50
+}
+
+fn default_preview_declarations() -> usize {
+    3
+}
+
+fn default_editor_command() -> String {
+    "code --goto {file}:{line}".to_string()
+}
+
+fn default_rescan_interval_minutes() -> u64 {
+    60
+}
+
+fn default_warn_severity() -> Severity {
+    Severity::Warn
+}
+
+fn default_info_severity() -> Severity {
+    Severity::Info
+}
+
 fn default_exclude_dirs() -> Vec<String> {
     vec![
         "node_modules".to_string(),
@@ -41,6 +333,9 @@ fn default_include_extensions() -> Vec<String> {
         "tsx".to_string(),
         "php".to_string(),
         "rs".to_string(),
+        "pug".to_string(),
+        "slim".to_string(),
+        "haml".to_string(),
     ]
 }
 
@@ -51,6 +346,131 @@ fn default_css_extensions() -> Vec<String> {
     ]
 }
 
+fn default_max_files() -> usize {
+    DEFAULT_MAX_FILES
+}
+
+fn default_component_extensions() -> Vec<String> {
+    vec![
+        "astro".to_string(),
+        "mdx".to_string(),
+        "mjml".to_string(),
+    ]
+}
+
+fn default_categories() -> Vec<FileCategoryConfig> {
+    let category = |name: &str, extensions: &[&str], paths: &[&str], stem_suffixes: &[&str], policy| FileCategoryConfig {
+        name: name.to_string(),
+        extensions: extensions.iter().map(|s| s.to_string()).collect(),
+        paths: paths.iter().map(|s| s.to_string()).collect(),
+        stem_suffixes: stem_suffixes.iter().map(|s| s.to_string()).collect(),
+        policy,
+    };
+
+    vec![
+        category("tests", &[], &["test", "tests", "__tests__", "spec"], &[".test", ".spec"], UsagePolicy::Weak),
+        category("docs", &["md"], &["docs", "stories"], &[".stories"], UsagePolicy::Weak),
+        category("markup", &["html", "htm", "php", "vue", "svelte", "astro", "mdx", "pug", "jade", "slim", "haml"], &[], &[], UsagePolicy::Usage),
+        category("scripts", &["js", "jsx", "ts", "tsx", "mjs", "cjs"], &[], &[], UsagePolicy::Usage),
+    ]
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            naming_convention: NamingConvention::default(),
+            class_pattern: default_class_pattern(),
+            min_class_length: default_min_class_length(),
+            ignore_numeric: default_ignore_numeric(),
+            stop_words: Vec::new(),
+            exclude_layers: Vec::new(),
+            compile_scss: false,
+            scss_load_paths: Vec::new(),
+            presets: Vec::new(),
+            preset_dirs: Vec::new(),
+        }
+    }
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            max_unused: None,
+            max_increase: None,
+            include_markdown: false,
+            state_file: default_notify_state_file(),
+        }
+    }
+}
+
+impl Default for GuiConfig {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            analyze_on_drop: false,
+            shortcuts: ShortcutsConfig::default(),
+            editor: default_editor_command(),
+            minimize_to_tray: false,
+            pinned_projects: Vec::new(),
+            rescan_interval_minutes: default_rescan_interval_minutes(),
+        }
+    }
+}
+
+impl Default for ShortcutsConfig {
+    fn default() -> Self {
+        Self {
+            focus_search: "CmdOrCtrl+F".to_string(),
+            run_analysis: "CmdOrCtrl+Enter".to_string(),
+            expand_all: "CmdOrCtrl+Shift+E".to_string(),
+            collapse_all: "CmdOrCtrl+Shift+C".to_string(),
+            open_in_editor: "CmdOrCtrl+O".to_string(),
+        }
+    }
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        Self {
+            unused_class: default_warn_severity(),
+            dead_code_usage: default_warn_severity(),
+            used_only_in_category: default_info_severity(),
+            dead_combination: default_info_severity(),
+            unused_data_attribute: default_warn_severity(),
+            unused_font_face: default_warn_severity(),
+            unused_asset: default_info_severity(),
+        }
+    }
+}
+
+impl ReportSort {
+    pub const NAMES: &[&str] = &["count", "name", "file", "percentage"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "count" => Some(ReportSort::Count),
+            "name" => Some(ReportSort::Name),
+            "file" => Some(ReportSort::File),
+            "percentage" => Some(ReportSort::Percentage),
+            _ => None,
+        }
+    }
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            ascii: false,
+            width: default_output_width(),
+            locale: None,
+            pager: None,
+            number_locale: None,
+            sort: ReportSort::default(),
+            preview_declarations: default_preview_declarations(),
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -58,37 +478,123 @@ impl Default for Config {
                 exclude_dirs: default_exclude_dirs(),
                 include_extensions: default_include_extensions(),
                 css_extensions: default_css_extensions(),
+                component_extensions: default_component_extensions(),
+                data_extensions: Vec::new(),
+                max_depth: None,
+                max_files: default_max_files(),
+                strict_walk: false,
+                io_threads: None,
+                cpu_threads: None,
+                categories: default_categories(),
             },
+            analysis: AnalysisConfig::default(),
+            gui: GuiConfig::default(),
+            notify: NotifyConfig::default(),
+            output: OutputConfig::default(),
+            rules: RulesConfig::default(),
+            owners: BTreeMap::new(),
         }
     }
 }
 
 /* ==================================== Config implementation =================================== */
+/// `[profile.ci]`, `[profile.local]`: named overrides for the rest of the file
+pub const PROFILE_TABLE: &str = "profile";
+
+/// `TAG_FINDER_SCAN__EXCLUDE_DIRS=node_modules,dist` sets `exclude_dirs` in `[scan]`
+pub const ENV_PREFIX: &str = "TAG_FINDER_";
+const ENV_SEPARATOR: &str = "__";
+
 
 impl Config {
     /* =================================== Load from file path ================================== */
     pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_file_with_profile(path, None)
+    }
+
+    /* ========================================================================================== */
+    /// Settings from `[profile.<name>]` are laid over the rest of the file; without a profile
+    /// those tables are ignored
+    pub fn from_file_with_profile(path: &str, profile: Option<&str>) -> Result<Self, Box<dyn std::error::Error>> {
         let content = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
-        Ok(config)
+        let mut document: toml::Table = toml::from_str(&content)?;
+        let mut profiles = match document.remove(PROFILE_TABLE) {
+            Some(toml::Value::Table(profiles)) => profiles,
+            Some(_) => return Err(ConfigError::new(format!("'{}' in {} must be a table of profiles", PROFILE_TABLE, path)).into()),
+            None => toml::Table::new(),
+        };
+
+        let mut document = toml::Value::Table(document);
+        if let Some(profile) = profile {
+            let overrides = profiles.remove(profile).ok_or_else(|| {
+                let available: Vec<&str> = profiles.keys().map(String::as_str).collect();
+                ConfigError::new(format!("No profile '{}' in {} (available: {})", profile, path,
+                    if available.is_empty() { "none".to_string() } else { available.join(", ") }))
+            })?;
+            merge_toml(&mut document, overrides);
+        }
+
+        Ok(document.try_into()?)
     }
 
     /* ========================================================================================== */
-    pub fn from_file_or_default(path: &str) -> Self {
-        match Self::from_file(path) {
-            Ok(config) => {
-                println!("Loaded configuration from {}", path);
-                config
-            },
-            Err(_) => {
-                println!("Using default configurating (no config file found)");
-                Self::default()
-            }
+    /// Stores the desktop app's `[gui]` settings in the config file at `path`. Only the settings
+    /// that differ from what the file gives now are written; the rest of the file, comments
+    /// included, stays as it was
+    pub fn save_gui_settings(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let invalid = |e: &dyn std::fmt::Display| ConfigError::new(format!("Invalid config file {}: {}", path, e));
+        let mut document: toml_edit::DocumentMut = content.parse().map_err(|e| invalid(&e))?;
+        let current: GuiConfig = toml::from_str::<toml::Table>(&content).map_err(|e| invalid(&e))?
+            .remove("gui")
+            .map(toml::Value::try_into)
+            .transpose()
+            .map_err(|e| invalid(&e))?
+            .unwrap_or_default();
+
+        let as_table = |gui: &GuiConfig| -> Result<toml_edit::DocumentMut, Box<dyn std::error::Error>> {
+            Ok(toml::to_string(gui)?.parse()?)
+        };
+        let gui = document.entry("gui").or_insert_with(|| {
+            // Stays out of the file unless a setting is written into it
+            let mut table = toml_edit::Table::new();
+            table.set_implicit(true);
+            toml_edit::Item::Table(table)
+        });
+        let gui = gui.as_table_mut().ok_or_else(|| ConfigError::new(format!("'gui' in {} must be a table", path)))?;
+        write_changed_settings(gui, as_table(&self.gui)?.as_table(), as_table(&current)?.as_table());
+
+        write_output(path, document.to_string().as_bytes())?;
+        Ok(())
+    }
+
+    /* ========================================================================================== */
+    pub fn from_file_or_default(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_file_with_profile_or_default(path, None)
+    }
+
+    /* ========================================================================================== */
+    /// The defaults when `path` doesn't exist. A file that exists but can't be read or parsed is a
+    /// `ConfigError`, since running with settings nobody asked for would pass CI for the wrong reasons
+    pub fn from_file_with_profile_or_default(path: &str, profile: Option<&str>) -> Result<Self, Box<dyn std::error::Error>> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
         }
+        Self::from_file_with_profile(path, profile)
+            .map_err(|e| ConfigError::new(format!("Invalid config file {}: {}", path, e)).into())
     }
 
     /* =========================== Automatically find configs and load ========================== */
     pub fn find_config_file() -> Option<String> {
+        Self::find_config_file_in(".")
+    }
+
+    /* ========================================================================================== */
+    pub fn find_config_file_in(directory: &str) -> Option<String> {
         let possible_paths = [
             "tag-finder.toml",
             ".tag-finder.toml",
@@ -96,8 +602,9 @@ impl Config {
         ];
 
         for path in &possible_paths {
-            if Path::new(path).exists() {
-                return Some(path.to_string());
+            let candidate = Path::new(directory).join(path);
+            if candidate.exists() {
+                return Some(candidate.to_string_lossy().to_string());
             }
         }
 
@@ -105,30 +612,297 @@ impl Config {
     }
 
     /* ========================================================================================== */
-    pub fn load_or_default() -> Self {
-        if let Some(config_path) = Self::find_config_file() {
-            Self::from_file_or_default(&config_path)
-        } else {
-            println!("No config file found, using defaults");
-            Self::default()
+    pub fn load_for_directory(directory: &str) -> Self {
+        // Used by library callers, so nothing is printed; a broken file falls back to the defaults
+        Self::find_config_file_in(directory)
+            .and_then(|config_path| Self::from_file(&config_path).ok())
+            .unwrap_or_default()
+    }
+
+    /* ========================================================================================== */
+    pub fn load_or_default() -> Result<Self, Box<dyn std::error::Error>> {
+        match Self::find_config_file() {
+            Some(config_path) => Self::from_file_or_default(&config_path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /* ================================== Environment overrides ================================= */
+    pub fn with_env_overrides(self) -> Result<Self, Box<dyn std::error::Error>> {
+        self.with_overrides(std::env::vars())
+    }
+
+    /* ========================================================================================== */
+    /// Applies `TAG_FINDER_<SECTION>__<KEY>` variables over this config. Names without `__`
+    /// (like `TAG_FINDER_LANG`) are not config keys and are left alone.
+    pub fn with_overrides<I>(self, variables: I) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut document = toml::Value::try_from(&self)?;
+        let mut applied = Vec::new();
+
+        for (name, value) in variables {
+            let Some(path) = name.strip_prefix(ENV_PREFIX).filter(|path| path.contains(ENV_SEPARATOR)) else {
+                continue;
+            };
+            let keys: Vec<String> = path.split(ENV_SEPARATOR).map(str::to_lowercase).collect();
+            let keys = set_override(&mut document, &keys, &value).map_err(|e| ConfigError::new(format!("{}: {}", name, e)))?;
+            applied.push((name, keys));
+        }
+
+        if applied.is_empty() {
+            return Ok(self);
+        }
+        let names: Vec<&str> = applied.iter().map(|(name, _)| name.as_str()).collect();
+        let config: Self = document.try_into()
+            .map_err(|e| ConfigError::new(format!("Invalid environment override ({}): {}", names.join(", "), e)))?;
+
+        // Deserializing drops keys no field reads, so a typo would otherwise change nothing silently
+        let result = toml::Value::try_from(&config)?;
+        for (name, keys) in &applied {
+            if keys.iter().try_fold(&result, |value, key| value.get(key)).is_none() {
+                return Err(ConfigError::new(format!("{}: unknown config key '{}'", name, keys.join("."))).into());
+            }
         }
+        Ok(config)
     }
 
     /* =================================== Scanning functions =================================== */
-    // ?Should these be in the FileWalker?
-    pub fn should_exclude_dir(&self, dir_name: &str) -> bool {
-        self.scan.exclude_dirs.iter().any(|excluded| {
-            dir_name == excluded || dir_name.starts_with(&format!("{}/", excluded))
-        })
+    pub fn scan_filter(&self) -> ScanFilter {
+        ScanFilter::from_config(self)
     }
 
     /* ========================================================================================== */
-    pub fn should_include_file(&self, file_path: &Path) -> bool {
-        has_extension(file_path, &self.scan.include_extensions.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+    pub fn is_stop_word(&self, class_name: &str) -> bool {
+        self.analysis.stop_words.iter().any(|word| word.eq_ignore_ascii_case(class_name))
     }
 
     /* ========================================================================================== */
-    pub fn is_css_file(&self, file_path: &Path) -> bool {
-        has_extension(file_path, &self.scan.css_extensions.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+    pub fn is_excluded_layer(&self, layer: &str) -> bool {
+        self.analysis.exclude_layers.iter().any(|excluded| {
+            layer == excluded || layer.strip_prefix(excluded.as_str()).is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+}
+
+/* ============================================================================================== */
+fn write_changed_settings(target: &mut toml_edit::Table, wanted: &toml_edit::Table, current: &toml_edit::Table) {
+    for (key, item) in wanted.iter() {
+        let current_item = current.get(key);
+        if let (Some(wanted), Some(current)) = (item.as_table(), current_item.and_then(toml_edit::Item::as_table)) {
+            let mut table = toml_edit::Table::new();
+            table.set_implicit(true);
+            if let Some(target) = target.entry(key).or_insert(toml_edit::Item::Table(table)).as_table_mut() {
+                write_changed_settings(target, wanted, current);
+            }
+            continue;
+        }
+        if current_item.map(toml_edit::Item::to_string) == Some(item.to_string()) {
+            continue;
+        }
+
+        // A comment after the old value stays with the new one
+        let mut item = item.clone();
+        if let (Some(value), Some(old)) = (item.as_value_mut(), target.get(key).and_then(toml_edit::Item::as_value)) {
+            *value.decor_mut() = old.decor().clone();
+        }
+        target.insert(key, item);
+    }
+}
+
+/* ============================================================================================== */
+/// Tables merge key by key, anything else (including arrays) is replaced by the overlay
+pub fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/* ============================================================================================== */
+/// Returns the path of the key that was set, as spelled in the config
+fn set_override(document: &mut toml::Value, keys: &[String], raw: &str) -> Result<Vec<String>, String> {
+    let Some((last, sections)) = keys.split_last() else {
+        return Err("missing key".to_string());
+    };
+
+    let mut table = document.as_table_mut().ok_or("config is not a table")?;
+    let mut path = Vec::with_capacity(keys.len());
+    for section in sections {
+        let section = matching_key(table, section);
+        path.push(section.clone());
+        table = table.entry(section.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| format!("'{}' is a value, not a section", section))?;
+    }
+
+    let key = matching_key(table, last);
+    let value = parse_override(raw, table.get(&key))?;
+    table.insert(key.clone(), value);
+    path.push(key);
+    Ok(path)
+}
+
+/* ============================================================================================== */
+fn matching_key(table: &toml::Table, key: &str) -> String {
+    // Variable names can't hold '-', so `UNUSED_CLASS` also finds `unused-class`
+    table.keys()
+        .find(|existing| existing.replace('-', "_") == key)
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/* ============================================================================================== */
+fn parse_override(raw: &str, current: Option<&toml::Value>) -> Result<toml::Value, String> {
+    // The value being replaced tells the type; lists are comma-separated
+    match current {
+        Some(toml::Value::Array(items)) if items.iter().any(|item| !item.is_str()) => {
+            Err("lists of tables can't be set from the environment".to_string())
+        }
+        Some(toml::Value::Array(_)) => Ok(toml::Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| toml::Value::String(item.to_string()))
+                .collect(),
+        )),
+        Some(toml::Value::Integer(_)) => raw.trim().parse().map(toml::Value::Integer)
+            .map_err(|_| format!("expected a number, got '{}'", raw)),
+        Some(toml::Value::Boolean(_)) => raw.trim().parse().map(toml::Value::Boolean)
+            .map_err(|_| format!("expected true or false, got '{}'", raw)),
+        Some(toml::Value::Table(_)) => Err("is a section, set its keys instead".to_string()),
+        Some(_) => Ok(toml::Value::String(raw.to_string())),
+        // Unset optional keys have nothing to go by; numbers and booleans are taken as such
+        None => Ok(raw.trim().parse().map(toml::Value::Integer)
+            .or_else(|_| raw.trim().parse().map(toml::Value::Boolean))
+            .unwrap_or_else(|_| toml::Value::String(raw.to_string()))),
+    }
+}
+
+/* ============================================================================================== */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overridden(variables: &[(&str, &str)]) -> Result<Config, Box<dyn std::error::Error>> {
+        Config::default().with_overrides(variables.iter().map(|(name, value)| (name.to_string(), value.to_string())))
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn overrides_take_the_type_of_the_current_value() {
+        let config = overridden(&[
+            ("TAG_FINDER_SCAN__MAX_FILES", "250"),
+            ("TAG_FINDER_SCAN__STRICT_WALK", "true"),
+            ("TAG_FINDER_OUTPUT__PAGER", "less -R"),
+            ("TAG_FINDER_SCAN__MAX_DEPTH", "3"),
+        ]).unwrap();
+        assert_eq!(config.scan.max_files, 250);
+        assert!(config.scan.strict_walk);
+        assert_eq!(config.output.pager.as_deref(), Some("less -R"));
+        assert_eq!(config.scan.max_depth, Some(3));
+
+        let error = overridden(&[("TAG_FINDER_SCAN__MAX_FILES", "many")]).unwrap_err();
+        assert!(error.to_string().contains("expected a number"), "{}", error);
+    }
+
+    #[test]
+    fn override_names_match_dashed_keys() {
+        let config = overridden(&[("TAG_FINDER_RULES__UNUSED_CLASS", "error")]).unwrap();
+        assert_eq!(config.rules.unused_class, Severity::Error);
+    }
+
+    #[test]
+    fn list_overrides_are_comma_separated() {
+        let config = overridden(&[("TAG_FINDER_ANALYSIS__STOP_WORDS", "active, hidden,,js-")]).unwrap();
+        assert_eq!(config.analysis.stop_words, vec!["active", "hidden", "js-"]);
+    }
+
+    #[test]
+    fn lists_of_tables_cannot_be_overridden() {
+        let error = overridden(&[("TAG_FINDER_SCAN__CATEGORIES", "tests")]).unwrap_err();
+        assert!(error.to_string().contains("lists of tables"), "{}", error);
+    }
+
+    #[test]
+    fn unknown_override_keys_are_rejected() {
+        let error = overridden(&[("TAG_FINDER_SCAN__TYPO", "1")]).unwrap_err();
+        assert!(error.is::<ConfigError>());
+        assert!(error.to_string().contains("unknown config key 'scan.typo'"), "{}", error);
+        assert!(overridden(&[("TAG_FINDER_TYPO__KEY", "1")]).is_err());
+    }
+
+    #[test]
+    fn merged_tables_keep_keys_the_overlay_leaves_out() {
+        let mut base: toml::Value = toml::from_str("[scan]\nmax_files = 10\nexclude_dirs = [\"dist\", \"build\"]\n").unwrap();
+        let overlay: toml::Value = toml::from_str("[scan]\nexclude_dirs = [\"vendor\"]\n[output]\nascii = true\n").unwrap();
+        merge_toml(&mut base, overlay);
+        assert_eq!(base["scan"]["max_files"].as_integer(), Some(10));
+        // Lists are replaced, not appended to
+        assert_eq!(base["scan"]["exclude_dirs"].as_array().unwrap().len(), 1);
+        assert_eq!(base["output"]["ascii"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn profiles_are_laid_over_the_file() {
+        let path = std::env::temp_dir().join(format!("tag-finder-profile-{}.toml", std::process::id()));
+        fs::write(&path, "[scan]\nmax_files = 10\nstrict_walk = true\n\n[profile.ci.scan]\nmax_files = 99\n").unwrap();
+        let path_text = path.to_string_lossy().to_string();
+
+        let plain = Config::from_file_with_profile(&path_text, None).unwrap();
+        let ci = Config::from_file_with_profile(&path_text, Some("ci")).unwrap();
+        let missing = Config::from_file_with_profile(&path_text, Some("nightly")).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(plain.scan.max_files, 10);
+        assert_eq!(ci.scan.max_files, 99);
+        assert!(ci.scan.strict_walk);
+        assert!(missing.is::<ConfigError>());
+        assert!(missing.to_string().contains("available: ci"), "{}", missing);
+    }
+
+    #[test]
+    fn gui_settings_are_saved_without_touching_the_rest() {
+        let path = std::env::temp_dir().join(format!("tag-finder-gui-{}.toml", std::process::id()));
+        let path_text = path.to_string_lossy().to_string();
+        let original = "# Shared with CI, keep max_files low\n[scan]\nmax_files = 10 # enough for the fixtures\n\n[gui]\ntheme = \"light\" # the team's choice\n";
+        fs::write(&path, original).unwrap();
+
+        let mut config = Config::from_file(&path_text).unwrap();
+        config.gui.theme = Theme::Dark;
+        config.gui.shortcuts.focus_search = "CmdOrCtrl+K".to_string();
+        config.save_gui_settings(&path_text).unwrap();
+        let saved = fs::read_to_string(&path).unwrap();
+        // Saving settings the file already has changes nothing
+        Config::from_file(&path_text).unwrap().save_gui_settings(&path_text).unwrap();
+        let saved_again = fs::read_to_string(&path).unwrap();
+        let reloaded = Config::from_file(&path_text).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(saved.starts_with("# Shared with CI, keep max_files low\n[scan]\nmax_files = 10 # enough for the fixtures\n"), "{}", saved);
+        assert!(saved.contains("theme = \"dark\" # the team's choice"), "{}", saved);
+        assert!(saved.contains("[gui.shortcuts]\nfocus_search = \"CmdOrCtrl+K\""), "{}", saved);
+        // Defaults the file leaves out stay out
+        assert!(!saved.contains("editor") && !saved.contains("exclude_dirs"), "{}", saved);
+        assert_eq!(saved_again, saved);
+        assert_eq!(reloaded.gui.theme, Theme::Dark);
+        assert_eq!(reloaded.gui.shortcuts.focus_search, "CmdOrCtrl+K");
+        assert_eq!(reloaded.scan.max_files, 10);
+    }
+
+    #[test]
+    fn variables_without_a_section_are_ignored() {
+        let config = overridden(&[("TAG_FINDER_LANG", "nl"), ("PATH", "/usr/bin")]).unwrap();
+        assert_eq!(config.scan.max_files, Config::default().scan.max_files);
+    }
+}
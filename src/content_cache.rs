@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Results per distinct file content, shared by analyzer threads, so byte-identical files
+/// (vendored copies, generated duplicates) are scanned once and the result reused for every path
+pub struct ContentCache<T> {
+    entries: Mutex<HashMap<ContentKey, Arc<T>>>,
+    reused: AtomicUsize,
+}
+
+// Length next to the hash, so a collision would have to agree on both
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ContentKey {
+    hash: u64,
+    length: usize,
+}
+
+impl<T> Default for ContentCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ContentCache<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            reused: AtomicUsize::new(0),
+        }
+    }
+
+    /* ========================================================================================== */
+    /// The cached result for `content`, or `compute`'s, which is stored for the next file with the same content
+    pub fn get_or_compute(&self, content: &str, compute: impl FnOnce() -> T) -> Arc<T> {
+        let key = ContentKey::of(content);
+        if let Some(result) = self.entries.lock().unwrap().get(&key) {
+            self.reused.fetch_add(1, Ordering::Relaxed);
+            return Arc::clone(result);
+        }
+
+        // Not holding the lock while scanning; two threads racing on the same content both compute it once
+        let result = Arc::new(compute());
+        Arc::clone(self.entries.lock().unwrap().entry(key).or_insert(result))
+    }
+
+    /* ========================================================================================== */
+    /// Files answered from the cache instead of being scanned
+    pub fn reused(&self) -> usize {
+        self.reused.load(Ordering::Relaxed)
+    }
+}
+
+impl ContentKey {
+    fn of(content: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        Self { hash: hasher.finish(), length: content.len() }
+    }
+}
@@ -2,13 +2,24 @@ use std::collections::HashSet;
 use crate::text_processor::{TextProcessor};
 use crate::parallel_processor::ParallelProcessor;
 use crate::ProcessorBuilder;
-use crate::traits::ThreadCountConfigurable;
+use crate::traits::{ThreadCountConfigurable, ConfigConfigurable, ProgressConfigurable, ObserverConfigurable};
+use crate::observer::{AnalysisObserver, SilentObserver};
+use crate::config::{Config, DEFAULT_CLASS_PATTERN};
+use crate::css_rules::unescape_identifier;
+use crate::exit_status::ConfigError;
+use crate::output_style::OutputStyle;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc};
 use std::path::PathBuf;
 
 pub struct CssParser {
     thread_count: Option<usize>,
+    class_pattern: String,
+    min_class_length: usize,
+    ignore_numeric: bool,
+    show_progress: bool,
+    observer: Arc<dyn AnalysisObserver>,
+    style: OutputStyle,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,12 +27,42 @@ pub struct CssClass {
     pub name: String,
     pub file: String,
     pub line: usize,
+    /// 1-based, in characters
+    #[serde(default)]
+    pub column: usize,
+    /// Bytes from the start of the file, for editors that address text by offset
+    #[serde(default)]
+    pub offset: usize,
+    /// Cascade layer of the definition, e.g. `utilities` or `framework.utilities`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<String>,
+    /// Limits of the enclosing `@scope` block, e.g. `(.card) to (.card-body)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// First declarations of the defining rule, e.g. `color: red; padding: 4px…`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub declarations: Option<String>,
+    /// From an `@doc` comment above the defining rule, e.g. `Button primary state`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+impl Default for CssParser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CssParser {
     pub fn new() -> Self {
         Self { 
             thread_count: None,
+            class_pattern: DEFAULT_CLASS_PATTERN.to_string(),
+            min_class_length: 2,
+            ignore_numeric: true,
+            show_progress: true,
+            observer: Arc::new(SilentObserver),
+            style: OutputStyle::default(),
         }
     }
 
@@ -35,10 +76,15 @@ impl CssParser {
     pub fn extract_classes_parallel(&self, files_with_content: Vec<(PathBuf, String)>) -> Result<Vec<CssClass>, Box<dyn std::error::Error>> {
         let processor_arc = Arc::new(
             TextProcessor::new()
-                .add_pattern("css_class", r"\.([a-zA-Z][a-zA-Z0-9_-]*)")?
+                .add_pattern("css_class", &self.class_pattern)
+                .map_err(|e| ConfigError::new(format!("Invalid class_pattern '{}': {}", self.class_pattern, e)))?
         );
 
-        let parallel_processor = ParallelProcessor::new().configure_threads(self.thread_count);
+        let parallel_processor = ParallelProcessor::new()
+            .configure_threads(self.thread_count)
+            .with_progress(self.show_progress)
+            .with_observer(Arc::clone(&self.observer))
+            .with_output_style(self.style.clone());
         
         let all_classes = parallel_processor.process_flat_map(
             files_with_content,
@@ -48,18 +94,24 @@ impl CssParser {
                 
                 matches
                     .into_iter()
-                    .filter(|text_match| {
-                        text_match.pattern_name == "css_class" 
-                            && self.is_valid_class_name(&text_match.matched_text)
-                    })
-                    .map(|text_match| CssClass {
-                        name: text_match.matched_text,
+                    .filter(|text_match| text_match.pattern_name == "css_class")
+                    // Markup writes `md\:flex` as `md:flex`
+                    .map(|text_match| (unescape_identifier(&text_match.matched_text).into_owned(), text_match))
+                    .filter(|(name, _)| self.is_valid_class_name(name))
+                    .map(|(name, text_match)| CssClass {
+                        name,
                         file: file_path_str.clone(),
                         line: text_match.line,
+                        column: text_match.column,
+                        offset: text_match.offset,
+                        layer: None,
+                        scope: None,
+                        declarations: None,
+                        doc: None,
                     })
                     .collect::<Vec<_>>()
             },
-            "Processing files for CSS classes"
+            "progress.processing_css_files"
         )?;
         
         let mut classes = all_classes;
@@ -69,7 +121,8 @@ impl CssParser {
 
     /* ========================================================================================== */
     fn is_valid_class_name(&self, name: &str) -> bool {
-        name.len() >= 2 && !name.chars().all(|c| c.is_ascii_digit())
+        let is_numeric = name.chars().all(|c| c.is_ascii_digit());
+        name.len() >= self.min_class_length && !(self.ignore_numeric && is_numeric)
     }
 
     /* ========================================================================================== */
@@ -82,9 +135,33 @@ impl CssParser {
     }
 }
 
+impl ObserverConfigurable for CssParser {
+    fn with_observer(mut self, observer: Arc<dyn AnalysisObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+}
+
+impl ProgressConfigurable for CssParser {
+    fn with_progress(mut self, show_progress: bool) -> Self {
+        self.show_progress = show_progress;
+        self
+    }
+}
+
 impl ThreadCountConfigurable for CssParser {
     fn with_thread_count(mut self, count: usize) -> Self {
         self.thread_count = Some(count);
         self
     }
-}
\ No newline at end of file
+}
+
+impl ConfigConfigurable for CssParser {
+    fn with_config(mut self, config: Config) -> Self {
+        self.class_pattern = config.analysis.class_pattern;
+        self.min_class_length = config.analysis.min_class_length;
+        self.ignore_numeric = config.analysis.ignore_numeric;
+        self.style = OutputStyle::from_config(&config.output);
+        self
+    }
+}
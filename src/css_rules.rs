@@ -0,0 +1,589 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::path::Path;
+
+// Blocks of these at-rules hold descriptors or keyframe steps, not style rules
+const OPAQUE_AT_RULES: &[&str] = &[
+    "keyframes", "-webkit-keyframes", "-moz-keyframes", "font-face", "page", "counter-style",
+    "property", "font-feature-values", "mixin", "function",
+];
+
+/// Name given to an `@layer { }` block without one
+pub const ANONYMOUS_LAYER: &str = "<anonymous>";
+// Longer values are cut in previews, so one gradient or grid template doesn't fill the line
+const PREVIEW_VALUE_LENGTH: usize = 40;
+const PREVIEW_ELLIPSIS: &str = "…";
+// Tag of a comment documenting the rule below it: `/* @doc Button primary state */`
+const DOC_TAG: &str = "@doc";
+
+/// One style rule block with the selectors it applies to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CssRule {
+    /// Full selector list as written, with SCSS nesting resolved, e.g. `.nav .item, .nav .link`
+    pub selector: String,
+    pub selectors: Vec<String>,
+    /// Declarations directly in this block, in source order
+    pub declarations: Vec<Declaration>,
+    /// Enclosing at-rule preludes, outermost first, e.g. `@media (max-width: 600px)`
+    pub at_rules: Vec<String>,
+    pub file: String,
+    pub line: usize,
+    /// Text of an `@doc` comment right above the rule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Declaration {
+    /// Property name, or the at-keyword for statements like `@include` and `@extend`
+    pub property: String,
+    pub value: String,
+    #[serde(default)]
+    pub important: bool,
+}
+
+/// (ids, classes/attributes/pseudo-classes, elements/pseudo-elements)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Specificity(pub u32, pub u32, pub u32);
+
+struct Frame {
+    /// Selectors declarations in this block apply to; `None` outside style rules
+    selectors: Option<Vec<String>>,
+    at_rules: Vec<String>,
+    /// Index in the output of this block's rule, created on its first declaration
+    rule: Option<usize>,
+    line: usize,
+    opaque: bool,
+    doc: Option<String>,
+}
+
+impl Declaration {
+    /// A real property, as opposed to SCSS variables and at-statements
+    pub fn is_property(&self) -> bool {
+        !self.property.is_empty() && !self.property.starts_with(['$', '@', '+', '=', '%'])
+    }
+}
+
+impl fmt::Display for Declaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self.value.char_indices().nth(PREVIEW_VALUE_LENGTH) {
+            Some((end, _)) => format!("{}{}", &self.value[..end], PREVIEW_ELLIPSIS),
+            None => self.value.clone(),
+        };
+        let important = if self.important { " !important" } else { "" };
+        if self.property.starts_with('@') {
+            write!(f, "{} {}{}", self.property, value, important)
+        } else {
+            write!(f, "{}: {}{}", self.property, value, important)
+        }
+    }
+}
+
+impl CssRule {
+    /// Class names in the selectors, without the dot and unescaped
+    pub fn classes(&self) -> Vec<Cow<'_, str>> {
+        let mut classes: Vec<Cow<'_, str>> = self.selectors.iter().flat_map(|selector| selector_classes(selector)).collect();
+        classes.sort_unstable();
+        classes.dedup();
+        classes
+    }
+
+    /* ========================================================================================== */
+    pub fn specificity(&self) -> Specificity {
+        self.selectors.iter().map(|selector| specificity(selector)).max().unwrap_or_default()
+    }
+
+    /* ========================================================================================== */
+    /// Cascade layer of the rule, nested layers joined with dots as in `@layer framework.utilities`
+    pub fn layer(&self) -> Option<String> {
+        let names: Vec<&str> = self.at_rules.iter()
+            .filter_map(|at_rule| at_rule_params(at_rule, "layer"))
+            .map(|name| if name.is_empty() { ANONYMOUS_LAYER } else { name })
+            .collect();
+        (!names.is_empty()).then(|| names.join("."))
+    }
+
+    /* ========================================================================================== */
+    /// Scoping limits of the innermost `@scope`, e.g. `(.card) to (.card-body)`
+    pub fn scope(&self) -> Option<String> {
+        self.at_rules.iter().rev()
+            .find_map(|at_rule| at_rule_params(at_rule, "scope"))
+            .map(str::to_string)
+    }
+
+    /* ========================================================================================== */
+    /// The first `count` declarations, e.g. `color: red; padding: 4px…`, with long values shortened
+    pub fn preview(&self, count: usize) -> Option<String> {
+        if count == 0 || self.declarations.is_empty() {
+            return None;
+        }
+        let shown: Vec<String> = self.declarations.iter().take(count).map(Declaration::to_string).collect();
+        let more = if self.declarations.len() > count { PREVIEW_ELLIPSIS } else { "" };
+        Some(format!("{}{}", shown.join("; "), more))
+    }
+}
+
+impl fmt::Display for Specificity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.0, self.1, self.2)
+    }
+}
+
+/* ============================================================================================== */
+/*                  Style rules of a stylesheet, with their at-rule context                       */
+/* ============================================================================================== */
+pub fn extract_rules(path: &Path, content: &str) -> Vec<CssRule> {
+    let file = path.to_string_lossy().to_string();
+    let chars: Vec<char> = content.chars().collect();
+    let mut rules: Vec<CssRule> = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut buffer = String::new();
+    let mut buffer_line = 1;
+    let mut line = 1;
+    let mut index = 0;
+    // An `@doc` comment waiting for the rule it documents
+    let mut doc: Option<String> = None;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let next = chars.get(index + 1).copied();
+        match c {
+            '/' if next == Some('*') => {
+                let end = find_sequence(&chars, index + 2, &['*', '/']).map_or(chars.len(), |end| end + 2);
+                let comment: String = chars[index + 2..end.saturating_sub(2).max(index + 2)].iter().collect();
+                doc = doc_comment(&comment).or(doc);
+                line += chars[index..end].iter().filter(|c| **c == '\n').count();
+                buffer.push(' ');
+                index = end;
+                continue;
+            }
+            // SCSS line comments; `//` right after a non-space is part of a value like a URL
+            '/' if next == Some('/') && index.checked_sub(1).is_none_or(|prev| chars[prev].is_whitespace() || "{};".contains(chars[prev])) => {
+                let start = index + 2;
+                while index < chars.len() && chars[index] != '\n' {
+                    index += 1;
+                }
+                let comment: String = chars[start..index].iter().collect();
+                doc = doc_comment(&comment).or(doc);
+                continue;
+            }
+            '"' | '\'' => {
+                let end = string_end(&chars, index);
+                if buffer.trim().is_empty() {
+                    buffer_line = line;
+                }
+                line += chars[index..end].iter().filter(|c| **c == '\n').count();
+                buffer.extend(&chars[index..end]);
+                index = end;
+                continue;
+            }
+            // `#{$var}` interpolation belongs to the surrounding selector or value
+            '#' if next == Some('{') => {
+                let end = block_end(&chars, index + 1);
+                if buffer.trim().is_empty() {
+                    buffer_line = line;
+                }
+                buffer.extend(&chars[index..end]);
+                index = end;
+                continue;
+            }
+            '{' => {
+                let mut frame = open_frame(frames.last(), &buffer, buffer_line);
+                // `@media` and other at-rule blocks aren't documented, the rules inside them are
+                if frame.selectors.is_some() && !buffer.trim_start().starts_with('@') {
+                    frame.doc = doc.take();
+                }
+                frames.push(frame);
+                buffer.clear();
+            }
+            ';' | '}' => {
+                let statement = normalize_whitespace(&buffer);
+                buffer.clear();
+                doc = None;
+                if let (Some(frame), Some(declaration)) = (frames.last_mut(), parse_declaration(&statement)) {
+                    add_declaration(&mut rules, frame, &file, declaration);
+                }
+                if c == '}' {
+                    frames.pop();
+                }
+            }
+            _ => {
+                if c == '\n' {
+                    line += 1;
+                } else if !c.is_whitespace() && buffer.trim().is_empty() {
+                    buffer_line = line;
+                }
+                buffer.push(c);
+            }
+        }
+        index += 1;
+    }
+
+    // Nested blocks get their rule on their first declaration, which may come after a parent's
+    rules.sort_by_key(|rule| rule.line);
+    rules
+}
+
+/* ============================================================================================== */
+pub fn specificity(selector: &str) -> Specificity {
+    let chars: Vec<char> = selector.chars().collect();
+    let mut result = Specificity::default();
+    let mut index = 0;
+
+    while index < chars.len() {
+        match chars[index] {
+            // SCSS interpolation is part of a name, not an id
+            '#' if chars.get(index + 1) == Some(&'{') => {
+                index = block_end(&chars, index + 1);
+            }
+            '#' => {
+                result.0 += 1;
+                index = name_end(&chars, index + 1);
+            }
+            '.' => {
+                result.1 += 1;
+                index = name_end(&chars, index + 1);
+            }
+            '[' => {
+                result.1 += 1;
+                index = chars[index..].iter().position(|c| *c == ']').map_or(chars.len(), |end| index + end + 1);
+            }
+            ':' => {
+                let element = chars.get(index + 1) == Some(&':');
+                let start = if element { index + 2 } else { index + 1 };
+                let end = name_end(&chars, start);
+                let name: String = chars[start..end].iter().collect::<String>().to_lowercase();
+                index = end;
+
+                let arguments = (chars.get(end) == Some(&'(')).then(|| {
+                    let close = paren_end(&chars, end);
+                    index = close;
+                    chars[end + 1..close.saturating_sub(1).max(end + 1)].iter().collect::<String>()
+                });
+
+                match (name.as_str(), arguments) {
+                    // Zero specificity by definition
+                    ("where", _) => {}
+                    // Take the most specific argument
+                    ("is" | "not" | "has" | "matches", Some(arguments)) => {
+                        let most_specific = split_selector_list(&arguments).iter().map(|argument| specificity(argument)).max().unwrap_or_default();
+                        result.0 += most_specific.0;
+                        result.1 += most_specific.1;
+                        result.2 += most_specific.2;
+                    }
+                    ("before" | "after" | "first-line" | "first-letter", _) => result.2 += 1,
+                    _ if element => result.2 += 1,
+                    _ => result.1 += 1,
+                }
+            }
+            c if c.is_alphabetic() => {
+                result.2 += 1;
+                index = name_end(&chars, index);
+            }
+            _ => index += 1,
+        }
+    }
+
+    result
+}
+
+/* ============================================================================================== */
+pub fn split_selector_list(selectors: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+
+    for c in selectors.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(normalize_whitespace(&current));
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(normalize_whitespace(&current));
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/* ============================================================================================== */
+pub fn selector_classes(selector: &str) -> Vec<Cow<'_, str>> {
+    selector.match_indices('.')
+        // `\.` is part of a name like `w-1\.5`
+        .filter(|(dot, _)| !selector[..*dot].ends_with('\\'))
+        .filter_map(|(dot, _)| {
+            let rest = &selector[dot + 1..];
+            let end = identifier_end(rest);
+            // `.5s` in a value or a lone dot is not a class
+            (end > 0 && !rest.starts_with(|c: char| c.is_ascii_digit())).then(|| unescape_identifier(&rest[..end]))
+        })
+        .collect()
+}
+
+/* ============================================================================================== */
+/// A CSS identifier as markup writes it: `md\:flex` is `md:flex`, `\32xl` is `2xl`
+pub fn unescape_identifier(identifier: &str) -> Cow<'_, str> {
+    if !identifier.contains('\\') {
+        return Cow::Borrowed(identifier);
+    }
+
+    let mut result = String::with_capacity(identifier.len());
+    let mut chars = identifier.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let mut hex = String::new();
+        while hex.len() < 6 && let Some(digit) = chars.next_if(char::is_ascii_hexdigit) {
+            hex.push(digit);
+        }
+        if hex.is_empty() {
+            // Any other character stands for itself
+            result.extend(chars.next());
+        } else {
+            // One space ends a code point, as in `\32 xl`
+            chars.next_if_eq(&' ');
+            result.push(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+    }
+    Cow::Owned(result)
+}
+
+/* ============================================================================================== */
+/// What follows the keyword of an `@name ...` prelude, or `None` for other at-rules
+fn at_rule_params<'a>(at_rule: &'a str, name: &str) -> Option<&'a str> {
+    let rest = at_rule.strip_prefix('@')?;
+    let keyword = rest.get(..name.len()).filter(|keyword| keyword.eq_ignore_ascii_case(name))?;
+    let params = &rest[keyword.len()..];
+    // `@layers` or `@scoped` are different at-rules
+    match params.chars().next() {
+        None => Some(""),
+        Some(c) if c.is_whitespace() || c == '(' => Some(params.trim()),
+        Some(_) => None,
+    }
+}
+
+/* ============================================================================================== */
+fn open_frame(parent: Option<&Frame>, prelude: &str, line: usize) -> Frame {
+    let prelude = normalize_whitespace(prelude);
+    let parent_selectors = parent.and_then(|parent| parent.selectors.clone());
+    let mut at_rules = parent.map(|parent| parent.at_rules.clone()).unwrap_or_default();
+    let parent_opaque = parent.is_some_and(|parent| parent.opaque);
+
+    if let Some(at_rule) = prelude.strip_prefix('@') {
+        let name = at_rule.split(|c: char| c.is_whitespace() || c == '(').next().unwrap_or("").to_lowercase();
+        at_rules.push(prelude.clone());
+        // A nested `@media` inside `.card { }` still styles `.card`
+        return Frame {
+            selectors: parent_selectors,
+            at_rules,
+            rule: None,
+            line,
+            opaque: parent_opaque || OPAQUE_AT_RULES.contains(&name.as_str()),
+            doc: None,
+        };
+    }
+
+    let selectors = split_selector_list(&prelude);
+    let selectors = match parent_selectors {
+        Some(parents) => parents.iter()
+            .flat_map(|parent| selectors.iter().map(move |child| {
+                if child.contains('&') {
+                    child.replace('&', parent)
+                } else {
+                    format!("{} {}", parent, child)
+                }
+            }))
+            .collect(),
+        None => selectors,
+    };
+
+    Frame { selectors: Some(selectors), at_rules, rule: None, line, opaque: parent_opaque, doc: None }
+}
+
+/* ============================================================================================== */
+fn add_declaration(rules: &mut Vec<CssRule>, frame: &mut Frame, file: &str, declaration: Declaration) {
+    let Some(selectors) = frame.selectors.as_ref().filter(|_| !frame.opaque) else {
+        return;
+    };
+
+    let rule_index = *frame.rule.get_or_insert_with(|| {
+        rules.push(CssRule {
+            selector: selectors.join(", "),
+            selectors: selectors.clone(),
+            declarations: Vec::new(),
+            at_rules: frame.at_rules.clone(),
+            file: file.to_string(),
+            line: frame.line,
+            doc: frame.doc.clone(),
+        });
+        rules.len() - 1
+    });
+    rules[rule_index].declarations.push(declaration);
+}
+
+/* ============================================================================================== */
+fn parse_declaration(statement: &str) -> Option<Declaration> {
+    if statement.is_empty() {
+        return None;
+    }
+
+    let (property, value) = if statement.starts_with('@') {
+        statement.split_once(char::is_whitespace).unwrap_or((statement, ""))
+    } else {
+        statement.split_once(':').unwrap_or((statement, ""))
+    };
+
+    let value = value.trim();
+    let lowercase = value.to_lowercase();
+    let important_at = lowercase.rfind('!').filter(|bang| lowercase[bang + 1..].trim() == "important");
+    let (value, important) = match important_at {
+        Some(bang) => (value[..bang].trim(), true),
+        None => (value, false),
+    };
+
+    Some(Declaration { property: property.trim().to_string(), value: value.to_string(), important })
+}
+
+/* ============================================================================================== */
+/// What follows `@doc` in a comment, with the `*` of block comment lines dropped
+fn doc_comment(comment: &str) -> Option<String> {
+    // `@docs` or `@doctype` in a comment are something else
+    let (start, _) = comment.match_indices(DOC_TAG)
+        .find(|(start, _)| comment[start + DOC_TAG.len()..].chars().next().is_none_or(char::is_whitespace))?;
+    let text = comment[start + DOC_TAG.len()..].lines()
+        .map(|line| line.trim().trim_start_matches('*').trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = normalize_whitespace(&text);
+    (!text.is_empty()).then_some(text)
+}
+
+/* ============================================================================================== */
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/* ============================================================================================== */
+fn find_sequence(chars: &[char], from: usize, sequence: &[char]) -> Option<usize> {
+    (from..chars.len()).find(|start| chars[*start..].starts_with(sequence))
+}
+
+/* ============================================================================================== */
+fn string_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut index = start + 1;
+    while index < chars.len() {
+        match chars[index] {
+            '\\' => index += 2,
+            c if c == quote => return index + 1,
+            '\n' => return index,
+            _ => index += 1,
+        }
+    }
+    chars.len()
+}
+
+/* ============================================================================================== */
+fn block_end(chars: &[char], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (index, c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    chars.len()
+}
+
+/* ============================================================================================== */
+fn paren_end(chars: &[char], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (index, c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    chars.len()
+}
+
+/* ============================================================================================== */
+// Byte length of the identifier `text` starts with, escapes included
+fn identifier_end(text: &str) -> usize {
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some((_, escaped)) if escaped.is_ascii_hexdigit() => {
+                    for _ in 0..5 {
+                        chars.next_if(|(_, c)| c.is_ascii_hexdigit());
+                    }
+                    chars.next_if(|(_, c)| *c == ' ');
+                }
+                Some(_) => {}
+                None => return index,
+            }
+        } else if !(c.is_alphanumeric() || c == '-' || c == '_') {
+            return index;
+        }
+    }
+    text.len()
+}
+
+/* ============================================================================================== */
+fn name_end(chars: &[char], start: usize) -> usize {
+    (start..chars.len())
+        .find(|index| !(chars[*index].is_alphanumeric() || chars[*index] == '-' || chars[*index] == '_' || chars[*index] == '\\'))
+        .unwrap_or(chars.len())
+}
+
+/* ============================================================================================== */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescapes_tailwind_identifiers() {
+        assert_eq!(unescape_identifier(r"md\:flex"), "md:flex");
+        assert_eq!(unescape_identifier(r"w-1\.5"), "w-1.5");
+        assert_eq!(unescape_identifier(r"w-\[10px\]"), "w-[10px]");
+        assert_eq!(unescape_identifier(r"\32 xl\:p-4"), "2xl:p-4");
+        assert!(matches!(unescape_identifier("plain"), Cow::Borrowed("plain")));
+    }
+
+    #[test]
+    fn identifier_end_includes_escapes() {
+        assert_eq!(identifier_end(r"md\:flex:hover"), r"md\:flex".len());
+        assert_eq!(identifier_end(r"\32 xl\:p-4 > a"), r"\32 xl\:p-4".len());
+        assert_eq!(identifier_end(r"w-1\.5{"), r"w-1\.5".len());
+        assert_eq!(identifier_end("card.active"), "card".len());
+    }
+
+    #[test]
+    fn selector_classes_keeps_escaped_dots() {
+        assert_eq!(selector_classes(r".w-1\.5"), vec!["w-1.5"]);
+        assert_eq!(selector_classes(r".md\:flex.card > .\32 xl\:p-4"), vec!["md:flex", "card", "2xl:p-4"]);
+        assert_eq!(selector_classes(r".hover\:underline:hover"), vec!["hover:underline"]);
+        assert!(selector_classes("a { transition: .5s }").is_empty());
+    }
+}
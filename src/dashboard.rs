@@ -0,0 +1,192 @@
+use crate::history::{FileSnapshot, HistoryEntry};
+use crate::number_format::NumberFormat;
+use crate::output_file::write_output;
+use crate::utils::{escape_html, format_unix_date};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 240.0;
+const CHART_PADDING: f64 = 30.0;
+const TOP_OFFENDERS: usize = 10;
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:900px;color:#222}\
+table{border-collapse:collapse;width:100%}td,th{border-bottom:1px solid #ddd;padding:.4rem;text-align:left}\
+.num{text-align:right}svg{background:#fafafa;border:1px solid #eee}code{background:#f2f2f2;padding:0 .2rem}";
+
+pub struct DashboardGenerator {
+    entries: Vec<HistoryEntry>,
+    numbers: NumberFormat,
+}
+
+impl DashboardGenerator {
+    pub fn new(entries: Vec<HistoryEntry>) -> Self {
+        Self { entries, numbers: NumberFormat::default() }
+    }
+
+    /* ========================================================================================== */
+    pub fn with_number_format(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    /* ========================================================================================== */
+    pub fn write(&self, out_dir: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let latest = self.entries.last().ok_or("History is empty, run unused-classes --history first")?;
+
+        let files_dir = Path::new(out_dir).join("files");
+        fs::create_dir_all(&files_dir)?;
+
+        let index_path = Path::new(out_dir).join("index.html");
+        write_output(&index_path.to_string_lossy(), self.render_index(latest).as_bytes())?;
+
+        for (index, snapshot) in latest.files.iter().enumerate() {
+            let page_path = files_dir.join(format!("{}.html", index));
+            write_output(&page_path.to_string_lossy(), self.render_file_page(snapshot).as_bytes())?;
+        }
+
+        Ok(latest.files.len() + 1)
+    }
+
+    /* ========================================================================================== */
+    fn render_index(&self, latest: &HistoryEntry) -> String {
+        let percentage = if latest.total_classes > 0 {
+            latest.unused_classes as f64 / latest.total_classes as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        let mut body = format!(
+            "<h1>Unused CSS dashboard</h1>\n<p>{} &middot; last run {}</p>\n\
+             <p><strong>{}</strong> of {} classes unused ({}%) across {} runs.</p>\n",
+            escape_html(&latest.directory),
+            format_unix_date(latest.timestamp),
+            self.numbers.count(latest.unused_classes),
+            self.numbers.count(latest.total_classes),
+            self.numbers.percentage(percentage),
+            self.numbers.count(self.entries.len()),
+        );
+
+        body.push_str("<h2>Trend</h2>\n");
+        body.push_str(&self.render_trend_chart());
+
+        body.push_str("<h2>Top offenders</h2>\n<table><tr><th>File</th><th class=\"num\">Unused</th><th class=\"num\">Total</th></tr>\n");
+        let mut offenders: Vec<(usize, &FileSnapshot)> = latest.files.iter()
+            .enumerate()
+            .filter(|(_, snapshot)| !snapshot.unused_classes.is_empty())
+            .collect();
+        offenders.sort_by_key(|(_, snapshot)| std::cmp::Reverse(snapshot.unused_classes.len()));
+
+        for (index, snapshot) in offenders.iter().take(TOP_OFFENDERS) {
+            body.push_str(&format!(
+                "<tr><td><a href=\"files/{}.html\">{}</a></td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                index, escape_html(&snapshot.file), self.numbers.count(snapshot.unused_classes.len()), self.numbers.count(snapshot.total_classes)
+            ));
+        }
+        body.push_str("</table>\n");
+        body.push_str(&self.render_owner_table(latest));
+
+        render_page("Unused CSS dashboard", &body)
+    }
+
+    /* ========================================================================================== */
+    fn render_trend_chart(&self) -> String {
+        let max_unused = self.entries.iter().map(|e| e.unused_classes).max().unwrap_or(0).max(1) as f64;
+        let steps = (self.entries.len().max(2) - 1) as f64;
+        let plot_width = CHART_WIDTH - 2.0 * CHART_PADDING;
+        let plot_height = CHART_HEIGHT - 2.0 * CHART_PADDING;
+
+        let points: Vec<String> = self.entries.iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let x = CHART_PADDING + plot_width * i as f64 / steps;
+                let y = CHART_HEIGHT - CHART_PADDING - plot_height * entry.unused_classes as f64 / max_unused;
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+
+        let first = self.entries.first().map(|e| format_unix_date(e.timestamp)).unwrap_or_default();
+        let last = self.entries.last().map(|e| format_unix_date(e.timestamp)).unwrap_or_default();
+
+        format!(
+            "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\
+             <polyline fill=\"none\" stroke=\"#d33\" stroke-width=\"2\" points=\"{points}\"/>\
+             <text x=\"{p}\" y=\"{ty}\" font-size=\"12\">{first}</text>\
+             <text x=\"{lx}\" y=\"{ty}\" font-size=\"12\" text-anchor=\"end\">{last}</text>\
+             <text x=\"{p}\" y=\"{my}\" font-size=\"12\">max {max}</text></svg>\n",
+            w = CHART_WIDTH, h = CHART_HEIGHT, p = CHART_PADDING,
+            ty = CHART_HEIGHT - 8.0, lx = CHART_WIDTH - CHART_PADDING, my = CHART_PADDING - 10.0,
+            points = points.join(" "), first = first, last = last, max = self.numbers.count(max_unused as usize),
+        )
+    }
+
+    /* ========================================================================================== */
+    fn render_owner_table(&self, latest: &HistoryEntry) -> String {
+        // owner -> (unused, total)
+        let mut owners: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for snapshot in &latest.files {
+            if let Some(owner) = &snapshot.owner {
+                let totals = owners.entry(owner).or_default();
+                totals.0 += snapshot.unused_classes.len();
+                totals.1 += snapshot.total_classes;
+            }
+        }
+        if owners.is_empty() {
+            return String::new();
+        }
+
+        let mut owners: Vec<(&str, (usize, usize))> = owners.into_iter().collect();
+        owners.sort_by_key(|(_, (unused, _))| std::cmp::Reverse(*unused));
+        let mut table = String::from("<h2>By owner</h2>\n<table><tr><th>Owner</th><th class=\"num\">Unused</th><th class=\"num\">Total</th></tr>\n");
+        for (owner, (unused, total)) in owners {
+            table.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                escape_html(owner), self.numbers.count(unused), self.numbers.count(total)
+            ));
+        }
+        table.push_str("</table>\n");
+        table
+    }
+
+    /* ========================================================================================== */
+    fn render_file_page(&self, snapshot: &FileSnapshot) -> String {
+        let mut body = format!(
+            "<p><a href=\"../index.html\">&larr; Dashboard</a></p>\n<h1>{}</h1>\n<p>{} of {} classes unused.</p>\n",
+            escape_html(&snapshot.file), self.numbers.count(snapshot.unused_classes.len()), self.numbers.count(snapshot.total_classes)
+        );
+        if let Some(owner) = &snapshot.owner {
+            body.push_str(&format!("<p>Owner: {}</p>\n", escape_html(owner)));
+        }
+
+        body.push_str("<h2>Unused classes</h2>\n<ul>\n");
+        for class in &snapshot.unused_classes {
+            match snapshot.docs.get(class) {
+                Some(doc) => body.push_str(&format!("<li><code>.{}</code> &mdash; {}</li>\n", escape_html(class), escape_html(doc))),
+                None => body.push_str(&format!("<li><code>.{}</code></li>\n", escape_html(class))),
+            }
+        }
+        body.push_str("</ul>\n");
+
+        body.push_str("<h2>History</h2>\n<table><tr><th>Run</th><th class=\"num\">Unused</th></tr>\n");
+        for entry in &self.entries {
+            if let Some(past) = entry.files.iter().find(|f| f.file == snapshot.file) {
+                body.push_str(&format!(
+                    "<tr><td>{}</td><td class=\"num\">{}</td></tr>\n",
+                    format_unix_date(entry.timestamp), self.numbers.count(past.unused_classes.len())
+                ));
+            }
+        }
+        body.push_str("</table>\n");
+
+        render_page(&snapshot.file, &body)
+    }
+}
+
+/* ============================================================================================== */
+pub(crate) fn render_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title><style>{}</style></head>\n<body>\n{}</body></html>\n",
+        escape_html(title), STYLE, body
+    )
+}
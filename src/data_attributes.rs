@@ -0,0 +1,82 @@
+use crate::css_rules::CssRule;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+// `[data-state]`, `[data-state="open"]`, `[ data-theme ~= 'dark' ]`
+const SELECTOR_ATTRIBUTE_PATTERN: &str = r"\[\s*(data-[a-zA-Z0-9_-]+)";
+// The attribute name anywhere in markup or scripts: `data-state="…"`, `setAttribute('data-state', …)`,
+// `querySelector('[data-state]')`; a hyphen or word character right before it means a longer name
+const USAGE_ATTRIBUTE_PATTERN: &str = r"(?:^|[^\w-])(data-[a-zA-Z0-9_-]+)";
+// `el.dataset.userRole` and `el.dataset['userRole']` read or write `data-user-role`
+const DATASET_PATTERN: &str = r#"\bdataset(?:\.([a-zA-Z_$][\w$]*)|\[\s*["'`]([^"'`]+)["'`]\s*\])"#;
+
+// Compiled once, `used_data_attributes` runs for every usage file
+static SELECTOR_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(SELECTOR_ATTRIBUTE_PATTERN).unwrap());
+static USAGE_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(USAGE_ATTRIBUTE_PATTERN).unwrap());
+static DATASET: LazyLock<Regex> = LazyLock::new(|| Regex::new(DATASET_PATTERN).unwrap());
+
+/// A rule styling elements by a `data-*` attribute
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataAttributeSelector {
+    /// Lowercased attribute name, e.g. `data-state`
+    pub attribute: String,
+    pub selector: String,
+    pub file: String,
+    pub line: usize,
+}
+
+/* ============================================================================================== */
+/*                 Attribute selectors in stylesheets, attribute names in usage files             */
+/* ============================================================================================== */
+pub fn data_attribute_selectors(rules: &[CssRule]) -> Vec<DataAttributeSelector> {
+    let mut selectors = Vec::new();
+
+    for rule in rules {
+        for selector in &rule.selectors {
+            // One finding per attribute and selector, however often the selector repeats it
+            let mut seen = HashSet::new();
+            for captures in SELECTOR_ATTRIBUTE.captures_iter(selector) {
+                let name = captures[1].to_lowercase();
+                if seen.insert(name.clone()) {
+                    selectors.push(DataAttributeSelector {
+                        attribute: name,
+                        selector: selector.clone(),
+                        file: rule.file.clone(),
+                        line: rule.line,
+                    });
+                }
+            }
+        }
+    }
+
+    selectors
+}
+
+/* ============================================================================================== */
+/// Lowercased `data-*` attribute names a usage file sets, reads or queries
+pub fn used_data_attributes(content: &str) -> HashSet<String> {
+    let mut names: HashSet<String> = USAGE_ATTRIBUTE.captures_iter(content)
+        .map(|captures| captures[1].to_lowercase())
+        .collect();
+    names.extend(DATASET.captures_iter(content)
+        .filter_map(|captures| captures.get(1).or(captures.get(2)))
+        .map(|property| format!("data-{}", kebab_case(property.as_str()))));
+    names
+}
+
+/* ============================================================================================== */
+/// `userRole` -> `user-role`, the attribute name behind a `dataset` property
+fn kebab_case(property: &str) -> String {
+    let mut name = String::with_capacity(property.len() + 4);
+    for c in property.chars() {
+        if c.is_ascii_uppercase() {
+            name.push('-');
+            name.push(c.to_ascii_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
@@ -0,0 +1,29 @@
+use crate::utils::get_file_extension;
+use serde_json::Value;
+use std::path::Path;
+
+/* ============================================================================================== */
+/*                       String values of JSON/YAML data files, one per line                      */
+/* ============================================================================================== */
+pub fn data_file_text(path: &Path, content: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    // Keys and structure are dropped so `{"title": ...}` doesn't count as usage of `.title`,
+    // while class lists like `"classes": "card card--wide"` keep their formatting
+    let value: Value = match get_file_extension(path) {
+        Some("yaml") | Some("yml") => serde_yaml::from_str(content)?,
+        _ => serde_json::from_str(content)?,
+    };
+
+    let mut values = Vec::new();
+    collect_strings(&value, &mut values);
+    Ok(values.join("\n"))
+}
+
+/* ============================================================================================== */
+fn collect_strings<'a>(value: &'a Value, values: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) => values.push(text),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, values)),
+        Value::Object(map) => map.values().for_each(|item| collect_strings(item, values)),
+        _ => {}
+    }
+}
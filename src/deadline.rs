@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A limit on the wall-clock time of one analysis, so a pathological project can't hang a CI job
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+    limit: Duration,
+    /// `None` when the limit is too far out to represent, so it never passes
+    end: Option<Instant>,
+}
+
+/// Whether every usage file was analyzed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    #[default]
+    Complete,
+    /// The time limit passed; classes without usage in the files read so far are `not_analyzed`
+    TimedOut,
+}
+
+/// How far a timed out analysis got
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeoutInfo {
+    pub limit_seconds: u64,
+    pub scanned_files: usize,
+    pub total_files: usize,
+}
+
+impl Deadline {
+    pub fn starting_now(limit: Duration) -> Self {
+        Self { limit, end: Instant::now().checked_add(limit) }
+    }
+
+    /* ========================================================================================== */
+    pub fn is_past(&self) -> bool {
+        self.end.is_some_and(|end| Instant::now() >= end)
+    }
+
+    /* ========================================================================================== */
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+}
+
+impl TimeoutInfo {
+    /// The limit the way it's usually written, e.g. `10m` or `90s`
+    pub fn limit_text(&self) -> String {
+        format_limit(self.limit_seconds)
+    }
+}
+
+impl fmt::Display for Deadline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_limit(self.limit.as_secs()))
+    }
+}
+
+/* ============================================================================================== */
+fn format_limit(seconds: u64) -> String {
+    match seconds {
+        0 => "0s".to_string(),
+        _ if seconds.is_multiple_of(3600) => format!("{}h", seconds / 3600),
+        _ if seconds.is_multiple_of(60) => format!("{}m", seconds / 60),
+        _ => format!("{}s", seconds),
+    }
+}
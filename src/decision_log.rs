@@ -0,0 +1,163 @@
+use crate::css_parser::CssClass;
+use serde::{Deserialize, Serialize};
+use crate::output_file::write_output_with;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionStatus {
+    Used,
+    UsedViaPattern,
+    Unused,
+    /// Unused, but the name is a configured stop word
+    LowConfidence,
+    /// Only referenced from script modules the bundler never included
+    DeadCodeOnly,
+    /// Unused, but the definition is younger than `--min-age`
+    Recent,
+    /// Unused, but the definition wasn't added since `--diff-base`
+    Legacy,
+    /// No usage in the files scanned before `--timeout` stopped the analysis
+    NotAnalyzed,
+}
+
+/// A column of the decision log, for exports that only need some of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecisionField {
+    Class,
+    File,
+    Line,
+    Column,
+    Offset,
+    Status,
+    Evidence,
+    UsedOnlyIn,
+    ElapsedUs,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionEvidence {
+    pub file: String,
+    /// Dynamic pattern that matched in `file`, absent for exact matches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassDecision {
+    pub class: String,
+    pub file: String,
+    pub line: usize,
+    #[serde(default)]
+    pub column: usize,
+    #[serde(default)]
+    pub offset: usize,
+    pub status: DecisionStatus,
+    pub evidence: Vec<DecisionEvidence>,
+    /// Categories of the usage files when every one of them has a weak usage policy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub used_only_in: Vec<String>,
+    /// Time spent matching the dynamic patterns that cover this class
+    pub elapsed_us: u64,
+}
+
+impl DecisionField {
+    pub const ALL: &'static [DecisionField] = &[
+        DecisionField::Class,
+        DecisionField::File,
+        DecisionField::Line,
+        DecisionField::Column,
+        DecisionField::Offset,
+        DecisionField::Status,
+        DecisionField::Evidence,
+        DecisionField::UsedOnlyIn,
+        DecisionField::ElapsedUs,
+    ];
+
+    /* ========================================================================================== */
+    /// Key of this field in a decision log line
+    pub fn key(self) -> &'static str {
+        match self {
+            DecisionField::Class => "class",
+            DecisionField::File => "file",
+            DecisionField::Line => "line",
+            DecisionField::Column => "column",
+            DecisionField::Offset => "offset",
+            DecisionField::Status => "status",
+            DecisionField::Evidence => "evidence",
+            DecisionField::UsedOnlyIn => "used_only_in",
+            DecisionField::ElapsedUs => "elapsed_us",
+        }
+    }
+
+    /* ========================================================================================== */
+    pub fn from_name(name: &str) -> Option<Self> {
+        // "name" reads more naturally on the command line than the log's own "class" key
+        let name = name.trim().replace('-', "_");
+        match name.as_str() {
+            "name" => Some(DecisionField::Class),
+            _ => Self::ALL.iter().copied().find(|field| field.key() == name),
+        }
+    }
+}
+
+impl DecisionEvidence {
+    pub fn exact(file: String) -> Self {
+        Self { file, pattern: None }
+    }
+
+    /* ========================================================================================== */
+    pub fn pattern(file: String, pattern: String) -> Self {
+        Self { file, pattern: Some(pattern) }
+    }
+}
+
+impl ClassDecision {
+    pub fn new(class: &CssClass, status: DecisionStatus, evidence: Vec<DecisionEvidence>, elapsed: Duration) -> Self {
+        Self {
+            class: class.name.clone(),
+            file: class.file.clone(),
+            line: class.line,
+            column: class.column,
+            offset: class.offset,
+            status,
+            evidence,
+            used_only_in: Vec::new(),
+            elapsed_us: elapsed.as_micros() as u64,
+        }
+    }
+
+    /* ========================================================================================== */
+    pub fn add_elapsed(&mut self, elapsed: Duration) {
+        self.elapsed_us += elapsed.as_micros() as u64;
+    }
+}
+
+/* ============================================================================================== */
+pub fn decision_key(class: &CssClass) -> String {
+    // The same name can be defined in several files, so the definition site is part of the key
+    format!("{}:{}:{}", class.file, class.line, class.name)
+}
+
+/* ============================================================================================== */
+/*                                 Decision log: one JSON object per line                         */
+/* ============================================================================================== */
+/// With `fields` set, each line only has those keys, in the log's usual order
+pub fn write_decision_log(path: &str, decisions: &[ClassDecision], fields: Option<&[DecisionField]>) -> Result<(), Box<dyn std::error::Error>> {
+    write_output_with(path, |writer| {
+        for decision in decisions {
+            let Some(fields) = fields else {
+                writeln!(writer, "{}", serde_json::to_string(decision)?)?;
+                continue;
+            };
+
+            let mut line = serde_json::to_value(decision)?;
+            if let Some(object) = line.as_object_mut() {
+                object.retain(|key, _| fields.iter().any(|field| field.key() == key));
+            }
+            writeln!(writer, "{}", serde_json::to_string(&line)?)?;
+        }
+        Ok(())
+    })
+    .map_err(|e| format!("Cannot write decision log '{}': {}", path, e).into())
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// A path the analysis had to leave out, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileIssue {
+    pub path: String,
+    pub error: String,
+}
+
+/// Problems that didn't stop the run but may have left files out of the results
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Diagnostics {
+    /// Directories and files the walk couldn't enter or stat, e.g. permission denied
+    #[serde(default)]
+    pub walk_errors: Vec<FileIssue>,
+    /// Files that were found but still couldn't be read after retrying
+    #[serde(default)]
+    pub read_failures: Vec<FileIssue>,
+}
+
+impl Diagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.walk_errors.is_empty() && self.read_failures.is_empty()
+    }
+}
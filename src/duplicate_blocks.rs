@@ -0,0 +1,60 @@
+use crate::css_rules::CssRule;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Shorter blocks like `display: none` repeat everywhere by design, not by copy-paste
+pub const MIN_DUPLICATE_DECLARATIONS: usize = 3;
+
+/// The same set of declarations written out in several rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateBlock {
+    /// The shared declarations, sorted, as `property: value`
+    pub declarations: Vec<String>,
+    pub locations: Vec<BlockLocation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockLocation {
+    pub selector: String,
+    pub file: String,
+    pub line: usize,
+}
+
+/* ============================================================================================== */
+/*                     Rules repeating another rule's declarations exactly                        */
+/* ============================================================================================== */
+pub fn find_duplicate_blocks(rules: &[CssRule]) -> Vec<DuplicateBlock> {
+    let mut blocks: BTreeMap<Vec<String>, Vec<BlockLocation>> = BTreeMap::new();
+
+    for rule in rules.iter().filter(|rule| rule.declarations.len() >= MIN_DUPLICATE_DECLARATIONS) {
+        // Order doesn't change what a block does when every property is distinct
+        let mut declarations: Vec<String> = rule.declarations.iter()
+            .map(|declaration| match declaration.important {
+                true => format!("{}: {} !important", declaration.property, declaration.value),
+                false => format!("{}: {}", declaration.property, declaration.value),
+            })
+            .collect();
+        declarations.sort();
+        declarations.dedup();
+
+        blocks.entry(declarations).or_default().push(BlockLocation {
+            selector: rule.selector.clone(),
+            file: rule.file.clone(),
+            line: rule.line,
+        });
+    }
+
+    let mut duplicates: Vec<DuplicateBlock> = blocks.into_iter()
+        .filter(|(_, locations)| locations.len() > 1)
+        .map(|(declarations, mut locations)| {
+            locations.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+            DuplicateBlock { declarations, locations }
+        })
+        .collect();
+
+    // Most repeated first, those are the best candidates for a shared class or mixin
+    duplicates.sort_by(|a, b| b.locations.len().cmp(&a.locations.len())
+        .then_with(|| a.locations[0].file.cmp(&b.locations[0].file))
+        .then(a.locations[0].line.cmp(&b.locations[0].line)));
+    duplicates
+}
@@ -0,0 +1,18 @@
+use std::fmt;
+
+/// Failures callers may want to tell apart from other errors, e.g. to ask for another path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagFinderError {
+    /// The directory to analyze doesn't exist, isn't a directory or can't be listed
+    InvalidDirectory { path: String, reason: String },
+}
+
+impl fmt::Display for TagFinderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagFinderError::InvalidDirectory { path, reason } => write!(f, "Cannot analyze '{}': {}", path, reason),
+        }
+    }
+}
+
+impl std::error::Error for TagFinderError {}
@@ -0,0 +1,115 @@
+use crate::cancellation::{is_cancelled, Cancelled};
+use crate::scanner::InvalidWord;
+use std::error::Error;
+use std::fmt;
+
+/// Process exit codes, so scripts can tell findings from a broken setup or an interrupted run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    Clean = 0,
+    /// An `error` rule has findings
+    Findings = 1,
+    /// Invalid config file, profile, environment override, command line or search word
+    ConfigError = 2,
+    /// A file, directory, archive or repository couldn't be read or written
+    IoError = 3,
+    /// Interrupted with Ctrl-C
+    Cancelled = 4,
+    /// `--timeout` stopped the analysis; the partial report was still written
+    TimedOut = 5,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /* ========================================================================================== */
+    pub fn from_passed(passed: bool) -> Self {
+        if passed { ExitStatus::Clean } else { ExitStatus::Findings }
+    }
+
+    /* ========================================================================================== */
+    /// Configuration errors are recognized anywhere in the source chain; anything else failed
+    /// while reading, fetching or writing. After Ctrl-C every error counts as the cancel, since
+    /// some are passed on as text by then
+    pub fn for_error(error: &(dyn Error + 'static)) -> Self {
+        if is_cancelled() || error.is::<Cancelled>() {
+            return ExitStatus::Cancelled;
+        }
+        let mut current = Some(error);
+        while let Some(error) = current {
+            if error.is::<ConfigError>() || error.is::<InvalidWord>() || error.is::<toml::de::Error>() || error.is::<regex::Error>() {
+                return ExitStatus::ConfigError;
+            }
+            current = error.source();
+        }
+        ExitStatus::IoError
+    }
+}
+
+/* ============================================================================================== */
+/// A setting that can't be used as given; fixing it takes an edit, not a retry
+#[derive(Debug)]
+pub struct ConfigError(String);
+
+impl ConfigError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for ConfigError {}
+
+/* ============================================================================================== */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Stands in for the context a caller wraps around an error
+    #[derive(Debug)]
+    struct Wrapped(Box<dyn Error>);
+
+    impl fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "while loading: {}", self.0)
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(self.0.as_ref())
+        }
+    }
+
+    #[test]
+    fn config_errors_exit_with_2() {
+        assert_eq!(ExitStatus::for_error(&ConfigError::new("bad profile")), ExitStatus::ConfigError);
+        let toml_error = toml::from_str::<toml::Table>("scan = [").unwrap_err();
+        assert_eq!(ExitStatus::for_error(&toml_error), ExitStatus::ConfigError);
+        // As a user's `class_pattern` would come in
+        let class_pattern = String::from("(");
+        let regex_error = regex::Regex::new(&class_pattern).unwrap_err();
+        assert_eq!(ExitStatus::for_error(&regex_error), ExitStatus::ConfigError);
+    }
+
+    #[test]
+    fn config_errors_are_found_in_the_source_chain() {
+        let wrapped = Wrapped(Box::new(ConfigError::new("bad profile")));
+        assert_eq!(ExitStatus::for_error(&wrapped), ExitStatus::ConfigError);
+    }
+
+    #[test]
+    fn other_errors_exit_with_3() {
+        let not_found = std::io::Error::new(std::io::ErrorKind::NotFound, "missing.css");
+        assert_eq!(ExitStatus::for_error(&not_found), ExitStatus::IoError);
+        assert_eq!(ExitStatus::for_error(&Wrapped(Box::new(not_found))), ExitStatus::IoError);
+        assert_eq!(ExitStatus::IoError.code(), 3);
+    }
+}
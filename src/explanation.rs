@@ -0,0 +1,139 @@
+use crate::css_parser::CssClass;
+use crate::output_style::OutputStyle;
+use crate::text_processor::TextPosition;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClassVerdict {
+    Used,
+    UsedViaPattern,
+    Unused,
+    /// Only referenced from script modules the bundler never included
+    DeadCodeOnly,
+    NotDefined,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageHit {
+    pub file: String,
+    pub line: usize,
+    /// 1-based, in characters
+    #[serde(default)]
+    pub column: usize,
+    /// Bytes from the start of the file
+    #[serde(default)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternEvidence {
+    pub pattern: String,
+    pub used_in: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassExplanation {
+    pub class_name: String,
+    pub verdict: ClassVerdict,
+    /// Categories of the usage files when every one of them has a weak usage policy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub used_only_in: Vec<String>,
+    pub definitions: Vec<CssClass>,
+    pub usage_hits: Vec<UsageHit>,
+    pub ignored_hits: Vec<UsageHit>,
+    /// Hits in script modules the bundler never included
+    #[serde(default)]
+    pub dead_code_hits: Vec<UsageHit>,
+    pub patterns: Vec<PatternEvidence>,
+    pub rules: Vec<String>,
+}
+
+impl UsageHit {
+    pub fn new(file: String, position: TextPosition) -> Self {
+        Self { file, line: position.line, column: position.column, offset: position.offset }
+    }
+}
+
+impl ClassExplanation {
+    pub fn print(&self, style: &OutputStyle) {
+        println!("\n{} EXPLANATION FOR .{}", style.icon("🔎"), self.class_name);
+        style.print_header_line();
+        self.print_verdict(style);
+        self.print_definitions(style);
+        self.print_usage_hits(style);
+        self.print_patterns(style);
+        self.print_rules(style);
+    }
+
+    /* ========================================================================================== */
+    fn print_verdict(&self, style: &OutputStyle) {
+        let (icon, verdict) = match self.verdict {
+            ClassVerdict::Used => ("✅", "USED (exact match outside CSS files)"),
+            ClassVerdict::UsedViaPattern => ("✅", "USED (via dynamic pattern)"),
+            ClassVerdict::Unused => ("🗑️", " UNUSED (only found in CSS files)"),
+            ClassVerdict::DeadCodeOnly => ("🪦", "DEAD CODE ONLY (only in modules the bundler never included)"),
+            ClassVerdict::NotDefined => ("❌", "NOT DEFINED in any CSS/SCSS file"),
+        };
+        println!("Verdict: {} {}", style.icon(icon), verdict);
+        if !self.used_only_in.is_empty() {
+            println!("Only used in: {}", self.used_only_in.join(", "));
+        }
+    }
+
+    /* ========================================================================================== */
+    fn print_definitions(&self, style: &OutputStyle) {
+        println!("\n{} Definitions ({}):", style.icon("📁"), self.definitions.len());
+        style.print_section_line();
+        for definition in &self.definitions {
+            println!("  {} (line {}, column {})", definition.file, definition.line, definition.column);
+        }
+    }
+
+    /* ========================================================================================== */
+    fn print_usage_hits(&self, style: &OutputStyle) {
+        println!("\n{} Exact usage hits ({}):", style.icon("🎯"), self.usage_hits.len());
+        style.print_section_line();
+        for hit in &self.usage_hits {
+            println!("  {} (line {}, column {})", hit.file, hit.line, hit.column);
+        }
+
+        if !self.ignored_hits.is_empty() {
+            println!("\n{} Ignored hits in style sections ({}):", style.icon("🙈"), self.ignored_hits.len());
+            for hit in &self.ignored_hits {
+                println!("  {} (line {}, column {})", hit.file, hit.line, hit.column);
+            }
+        }
+
+        if !self.dead_code_hits.is_empty() {
+            println!("\n{} Hits in un-bundled modules ({}):", style.icon("🪦"), self.dead_code_hits.len());
+            for hit in &self.dead_code_hits {
+                println!("  {} (line {}, column {})", hit.file, hit.line, hit.column);
+            }
+        }
+    }
+
+    /* ========================================================================================== */
+    fn print_patterns(&self, style: &OutputStyle) {
+        println!("\n{} Covering dynamic patterns ({}):", style.icon("🧩"), self.patterns.len());
+        style.print_section_line();
+        for pattern in &self.patterns {
+            if pattern.used_in.is_empty() {
+                println!("  {} (not used anywhere)", pattern.pattern);
+            } else {
+                println!("  {} used in:", pattern.pattern);
+                for file in &pattern.used_in {
+                    println!("    {} {}", style.icon("•"), file);
+                }
+            }
+        }
+    }
+
+    /* ========================================================================================== */
+    fn print_rules(&self, style: &OutputStyle) {
+        println!("\n{} Rules applied:", style.icon("📏"));
+        style.print_section_line();
+        for rule in &self.rules {
+            println!("  {} {}", style.icon("•"), rule);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::archive::Archive;
+use std::path::{Path, PathBuf};
+
+/// Source of the files a scan looks at.
+/// The disk walker and archives implement it; embedders can implement it over their own
+/// storage (an editor's open buffers, an in-memory VFS, test fixtures) and hand it to
+/// `FileWalker::with_provider` or `UnusedDetector::with_file_provider`.
+pub trait FileProvider: Send + Sync {
+    /// Every file the provider holds. Exclude dirs, extensions and limits are applied by the walker.
+    fn list(&self) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>>;
+
+    /// Text content of a listed file; unreadable files are skipped like on disk
+    fn read(&self, path: &Path) -> std::io::Result<String>;
+}
+
+impl FileProvider for Archive {
+    fn list(&self) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        Ok(self.paths().cloned().collect())
+    }
+
+    /* ========================================================================================== */
+    fn read(&self, path: &Path) -> std::io::Result<String> {
+        Archive::read(self, path)
+            .map(str::to_string)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, format!("{} is not in the archive", path.display())))
+    }
+}
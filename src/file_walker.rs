@@ -1,16 +1,45 @@
 use walkdir::WalkDir;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use std::io::ErrorKind;
 use crate::parallel_processor::ParallelProcessor;
 use crate::utils::{get_thread_count_or_default};
 use crate::config::Config;
-use crate::traits::{ThreadCountConfigurable, ConfigConfigurable};
+use crate::output_style::OutputStyle;
+use crate::scan_filter::ScanFilter;
+use crate::archive::{is_archive_path, Archive};
+use crate::file_provider::FileProvider;
+use crate::diagnostics::{Diagnostics, FileIssue};
+use crate::traits::{ThreadCountConfigurable, ConfigConfigurable, ProgressConfigurable, ObserverConfigurable};
+use crate::observer::{AnalysisObserver, AnalysisStage, SilentObserver};
 use crate::ProcessorBuilder;
 
+/// Enough for large monorepos, small enough to stop a scan that was pointed at `/`
+pub const DEFAULT_MAX_FILES: usize = 100_000;
+
+/// Reads that fail with a possibly transient error are tried this often, waiting twice as long each time
+const READ_ATTEMPTS: u32 = 3;
+const READ_RETRY_DELAY: Duration = Duration::from_millis(50);
+
 pub struct FileWalker {
     directory: String,
     file_filter: Box<dyn Fn(&Path) -> bool + Send + Sync>,
+    scan_filter: Option<ScanFilter>,
     thread_count: Option<usize>,
     config: Option<Config>,
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    /// Explicit files to use instead of walking `directory`
+    file_list: Option<Vec<PathBuf>>,
+    /// Files come from here instead of the disk; set on first use for archive paths
+    provider: OnceLock<Arc<dyn FileProvider>>,
+    show_progress: bool,
+    observer: Arc<dyn AnalysisObserver>,
+    /// Fail the walk on the first unreadable path instead of skipping it
+    strict: bool,
+    /// Filled by the last walk; behind a lock because walking only borrows `self`
+    diagnostics: Mutex<Diagnostics>,
 }
 
 impl FileWalker {
@@ -18,22 +47,24 @@ impl FileWalker {
         Self {
             directory,
             file_filter: Box::new(|_| true),
+            scan_filter: None,
             thread_count: None,
             config: None,
+            max_depth: None,
+            max_files: Some(DEFAULT_MAX_FILES),
+            file_list: None,
+            provider: OnceLock::new(),
+            show_progress: true,
+            observer: Arc::new(SilentObserver),
+            strict: false,
+            diagnostics: Mutex::new(Diagnostics::default()),
         }
     }
 
     /* ========================================================================================== */
     pub fn walk(&self) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
-        let files: Vec<PathBuf> = WalkDir::new(&self.directory)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-            .map(|entry| entry.path().to_path_buf())
-            .filter(|path| (self.file_filter)(path))
-            .collect();
-
-        Ok(files)
+        let provider = self.provider()?;
+        self.collect_files(provider.map(Arc::as_ref))
     }
 
     /* ========================================================================================== */
@@ -42,7 +73,7 @@ impl FileWalker {
         let mut results = Vec::new();
 
         for file in files {
-            if let Ok(content) = std::fs::read_to_string(&file) {
+            if let Ok(content) = self.read(&file) {
                 results.push((file, content));
             }
         }
@@ -53,24 +84,220 @@ impl FileWalker {
     /* ========================================================================================== */
     pub fn walk_with_content_parallel(&self) -> Result<Vec<(PathBuf, String)>, Box<dyn std::error::Error>> {
         let files = self.walk()?;
-        println!("📁 Reading {} files using {} threads...", files.len(), get_thread_count_or_default(self.thread_count));
+        let style = self.output_style();
+        if self.show_progress {
+            self.observer.on_stage_start(AnalysisStage::Reading, &style.message("progress.reading_files", &[&files.len(), &get_thread_count_or_default(self.thread_count)]));
+        }
 
-        let processor = ParallelProcessor::new().configure_threads(self.thread_count);
+        let processor = ParallelProcessor::new()
+            .configure_threads(self.thread_count)
+            .with_progress(self.show_progress)
+            .with_observer(Arc::clone(&self.observer))
+            .with_output_style(self.output_style());
         
         let results = processor.process(
             files,
             |file| -> Result<Option<(PathBuf, String)>, Box<dyn std::error::Error + Send + Sync>> {
-                match std::fs::read_to_string(file) {
+                match self.read(file) {
                     Ok(content) => Ok(Some((file.clone(), content))),
                     Err(_) => Ok(None), // Skip files we can't read
                 }
             },
-            "Reading files"
+            "progress.reading_file_contents"
         )?;
 
         Ok(results.into_iter().flatten().collect())
     }
-    
+
+    /* ========================================================================================== */
+    fn provider(&self) -> Result<Option<&Arc<dyn FileProvider>>, Box<dyn std::error::Error>> {
+        if let Some(provider) = self.provider.get() {
+            return Ok(Some(provider));
+        }
+        if self.file_list.is_some() || !is_archive_path(&self.directory) {
+            return Ok(None);
+        }
+
+        // `--directory site.zip` reads the archive into memory once instead of walking the disk
+        let archive: Arc<dyn FileProvider> = Arc::new(Archive::open(&self.directory)?);
+        Ok(Some(self.provider.get_or_init(|| archive)))
+    }
+
+    /* ========================================================================================== */
+    fn collect_files(&self, provider: Option<&dyn FileProvider>) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let mut files = match (&self.file_list, provider) {
+            (Some(file_list), _) => self.filter_file_list(file_list, provider),
+            (None, Some(provider)) => self.walk_provider(provider)?,
+            (None, None) => self.walk_directory()?,
+        };
+
+        if let Some(max_files) = self.max_files.filter(|max_files| files.len() > *max_files) {
+            files.truncate(max_files);
+            let style = self.output_style();
+            self.observer.on_warning(&style.message("progress.max_files_reached", &[&max_files, &self.directory]));
+        }
+        Ok(files)
+    }
+
+    /* ========================================================================================== */
+    fn walk_directory(&self) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let root = Path::new(&self.directory);
+        let mut walker = WalkDir::new(root);
+        if let Some(max_depth) = self.max_depth {
+            walker = walker.max_depth(max_depth);
+        }
+
+        let mut depth_limited = false;
+        let mut walk_errors = Vec::new();
+        let files: Vec<PathBuf> = walker
+            .into_iter()
+            // Excluded directories are pruned instead of walked and filtered afterwards
+            .filter_entry(|entry| !self.is_excluded(root, entry.path()))
+            // An unreadable subtree is recorded instead of silently vanishing from the results
+            .filter_map(|entry| entry.map_err(|e| walk_errors.push(walk_issue(root, &e))).ok())
+            .inspect(|entry| {
+                if self.max_depth.is_some_and(|max_depth| entry.depth() == max_depth && entry.file_type().is_dir()) {
+                    depth_limited = true;
+                }
+            })
+            .filter(|e| e.file_type().is_file())
+            .map(|entry| entry.path().to_path_buf())
+            .filter(|path| self.accepts(path))
+            // One extra file tells "exactly at the limit" apart from "cut off"
+            .take(self.max_files.map_or(usize::MAX, |max_files| max_files.saturating_add(1)))
+            .collect();
+
+        if depth_limited {
+            self.warn_max_depth();
+        }
+        self.report_walk_errors(walk_errors)?;
+        Ok(files)
+    }
+
+    /* ========================================================================================== */
+    fn report_walk_errors(&self, walk_errors: Vec<FileIssue>) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(first) = walk_errors.first() {
+            if self.strict {
+                return Err(format!("Cannot walk '{}': {} (strict_walk)", first.path, first.error).into());
+            }
+            let style = self.output_style();
+            self.observer.on_warning(&style.message("progress.walk_errors", &[&walk_errors.len(), &first.path, &first.error]));
+        }
+
+        self.diagnostics.lock().unwrap().walk_errors = walk_errors;
+        Ok(())
+    }
+
+    /* ========================================================================================== */
+    /// Problems found by the last walk
+    pub fn diagnostics(&self) -> Diagnostics {
+        self.diagnostics.lock().unwrap().clone()
+    }
+
+    /* ========================================================================================== */
+    fn walk_provider(&self, provider: &dyn FileProvider) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        // Provider paths are expected below `directory`, like walked paths are
+        let root = Path::new(&self.directory);
+        let mut depth_limited = false;
+        let mut files: Vec<PathBuf> = provider.list()?
+            .into_iter()
+            .filter(|path| !self.is_excluded(root, path))
+            .filter(|path| {
+                let depth = path.strip_prefix(root).map_or(0, |relative| relative.components().count());
+                let within_depth = self.max_depth.is_none_or(|max_depth| depth <= max_depth);
+                depth_limited |= !within_depth;
+                within_depth
+            })
+            .filter(|path| self.accepts(path))
+            .collect();
+        // Walk order is the provider's; sorting keeps max_files cut-offs reproducible
+        files.sort();
+
+        if depth_limited {
+            self.warn_max_depth();
+        }
+        Ok(files)
+    }
+
+    /* ========================================================================================== */
+    fn filter_file_list(&self, file_list: &[PathBuf], provider: Option<&dyn FileProvider>) -> Vec<PathBuf> {
+        // Listed files skip the walk, but still go through the same extension and exclude filters
+        file_list
+            .iter()
+            // A provider's files need not exist on disk; unknown ones fail to read later and are skipped
+            .filter(|path| provider.is_some() || path.is_file())
+            .filter(|path| !self.scan_filter.as_ref().is_some_and(|filter| filter.is_excluded(path)))
+            .filter(|path| self.accepts(path))
+            .cloned()
+            .collect()
+    }
+
+    /* ========================================================================================== */
+    fn warn_max_depth(&self) {
+        if let Some(max_depth) = self.max_depth {
+            let style = self.output_style();
+            self.observer.on_warning(&style.message("progress.max_depth_reached", &[&max_depth, &self.directory]));
+        }
+    }
+
+    /* ========================================================================================== */
+    fn output_style(&self) -> OutputStyle {
+        self.config.as_ref()
+            .map(|config| OutputStyle::from_config(&config.output))
+            .unwrap_or_default()
+    }
+
+    /* ========================================================================================== */
+    fn is_excluded(&self, root: &Path, path: &Path) -> bool {
+        // Only components below the walk root count, so scanning inside e.g. `build/` still works
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        self.scan_filter.as_ref().is_some_and(|filter| filter.is_excluded(relative))
+    }
+
+    /* ========================================================================================== */
+    fn accepts(&self, path: &Path) -> bool {
+        let passes_scan_filter = self.scan_filter.as_ref().is_none_or(|filter| {
+            filter.is_css_file(path) || filter.is_usage_file(path)
+        });
+        passes_scan_filter && (self.file_filter)(path)
+    }
+
+    /* ========================================================================================== */
+    pub fn with_scan_filter(mut self, scan_filter: ScanFilter) -> Self {
+        self.scan_filter = Some(scan_filter);
+        self
+    }
+
+    /* ========================================================================================== */
+    pub fn with_file_list(mut self, file_list: Option<Vec<PathBuf>>) -> Self {
+        self.file_list = file_list;
+        self
+    }
+
+    /* ========================================================================================== */
+    pub fn with_provider(mut self, provider: Arc<dyn FileProvider>) -> Self {
+        self.provider = OnceLock::from(provider);
+        self
+    }
+
+    /* ========================================================================================== */
+    pub fn with_strict_walk(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /* ========================================================================================== */
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /* ========================================================================================== */
+    pub fn with_max_files(mut self, max_files: Option<usize>) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
     /* ========================================================================================== */
     pub fn with_extensions(mut self, extensions: Vec<&str>) -> Self {
         // Lifetime shittery so do it this way
@@ -95,6 +322,20 @@ impl FileWalker {
     }
 }
 
+impl ObserverConfigurable for FileWalker {
+    fn with_observer(mut self, observer: Arc<dyn AnalysisObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+}
+
+impl ProgressConfigurable for FileWalker {
+    fn with_progress(mut self, show_progress: bool) -> Self {
+        self.show_progress = show_progress;
+        self
+    }
+}
+
 impl ThreadCountConfigurable for FileWalker {
     fn with_thread_count(mut self, count: usize) -> Self {
         self.thread_count = Some(count);
@@ -104,36 +345,69 @@ impl ThreadCountConfigurable for FileWalker {
 
 impl ConfigConfigurable for FileWalker {
     fn with_config(mut self, config: Config) -> Self {
-        let exclude_dirs = config.scan.exclude_dirs.clone();
-        let include_extensions = config.scan.include_extensions.clone();
-        let css_extensions = config.scan.css_extensions.clone();
-        
-        // Combine include and CSS extensions for file filtering
-        let all_allowed_extensions = {
-            let mut combined = include_extensions.clone();
-            combined.extend(css_extensions);
-            combined
+        self.scan_filter = Some(config.scan_filter());
+        self.max_depth = config.scan.max_depth;
+        // 0 turns the safeguard off
+        self.max_files = Some(config.scan.max_files).filter(|max_files| *max_files > 0);
+        self.strict = config.scan.strict_walk;
+        // The walker's threads only read files
+        self.thread_count = self.thread_count.or(config.scan.io_threads);
+        self.config = Some(config);
+        self
+    }
+}
+
+impl FileProvider for FileWalker {
+    fn list(&self) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        self.walk()
+    }
+
+    /* ========================================================================================== */
+    fn read(&self, path: &Path) -> std::io::Result<String> {
+        let provider = self.provider().map_err(|e| std::io::Error::other(e.to_string()))?;
+        let read_once = || match provider {
+            Some(provider) => provider.read(path),
+            None => std::fs::read_to_string(path),
         };
-        
-        self.file_filter = Box::new(move |path: &Path| {
-            // Check directory exclusions
-            for component in path.components() {
-                if let Some(dir_name) = component.as_os_str().to_str() {
-                    if exclude_dirs.iter().any(|excluded| excluded == dir_name) {
-                        return false;
-                    }
+
+        // Network filesystems fail the odd read; retrying beats silently losing the file
+        let mut result = read_once();
+        let mut delay = READ_RETRY_DELAY;
+        for _ in 1..READ_ATTEMPTS {
+            match &result {
+                Err(e) if is_transient(e) => {
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                    result = read_once();
                 }
+                _ => break,
             }
-            
-            // Check file extension inclusions
-            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                all_allowed_extensions.iter().any(|allowed| allowed == ext)
-            } else {
-                false // Exclude files without extensions
-            }
-        });
+        }
 
-        self.config = Some(config);
-        self
+        match &result {
+            Ok(_) => self.observer.on_file_read(path),
+            Err(e) => self.diagnostics.lock().unwrap().read_failures.push(FileIssue {
+                path: path.to_string_lossy().to_string(),
+                error: e.to_string(),
+            }),
+        }
+        result
     }
-}
\ No newline at end of file
+}
+
+/* ============================================================================================== */
+fn is_transient(error: &std::io::Error) -> bool {
+    // Missing files, missing permissions and non-UTF-8 contents won't change by trying again
+    !matches!(
+        error.kind(),
+        ErrorKind::NotFound | ErrorKind::PermissionDenied | ErrorKind::InvalidData | ErrorKind::IsADirectory | ErrorKind::Unsupported
+    )
+}
+
+/* ============================================================================================== */
+fn walk_issue(root: &Path, error: &walkdir::Error) -> FileIssue {
+    let path = error.path().unwrap_or(root);
+    // The io error alone ("Permission denied") reads better than walkdir's message, which repeats the path
+    let message = error.io_error().map_or_else(|| error.to_string(), |io_error| io_error.to_string());
+    FileIssue { path: path.to_string_lossy().to_string(), error: message }
+}
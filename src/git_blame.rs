@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Who last touched a line, and when
+#[derive(Debug, Clone)]
+pub struct BlameLine {
+    pub author: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// `git blame` per file, run once per file and cached.
+/// Files outside a git work tree (or read from an archive) have no blame.
+#[derive(Default)]
+pub struct GitBlame {
+    files: HashMap<PathBuf, Option<Vec<BlameLine>>>,
+}
+
+impl GitBlame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /* ========================================================================================== */
+    /// Blame for a 1-based line number
+    pub fn line(&mut self, path: &Path, line: usize) -> Option<&BlameLine> {
+        self.files
+            .entry(path.to_path_buf())
+            .or_insert_with(|| blame_file(path))
+            .as_ref()?
+            .get(line.checked_sub(1)?)
+    }
+}
+
+/* ============================================================================================== */
+fn blame_file(path: &Path) -> Option<Vec<BlameLine>> {
+    // Run from the file's own directory, so it works whatever repository the scan root is in
+    let directory = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["blame", "--line-porcelain", "--"])
+        .arg(path.file_name()?)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_line_porcelain(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/* ============================================================================================== */
+fn parse_line_porcelain(output: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut author = String::new();
+    let mut timestamp = 0;
+
+    // Every line repeats its commit headers, and the line content itself starts with a tab.
+    // Uncommitted lines are attributed to "Not Committed Yet" with the current time.
+    for header in output.lines() {
+        if let Some(name) = header.strip_prefix("author ") {
+            author = name.to_string();
+        } else if let Some(time) = header.strip_prefix("author-time ") {
+            timestamp = time.trim().parse().unwrap_or(0);
+        } else if header.starts_with('\t') {
+            lines.push(BlameLine { author: std::mem::take(&mut author), timestamp });
+        }
+    }
+
+    lines
+}
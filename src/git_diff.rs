@@ -0,0 +1,83 @@
+use crate::exit_status::ConfigError;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Lines added since the merge base with a branch, per file.
+/// Covers commits on the current branch as well as uncommitted changes in the work tree.
+pub struct AddedLines {
+    /// Top-level directory of the work tree; diff paths are relative to it
+    root: PathBuf,
+    /// Canonical path -> 1-based line numbers added since the merge base
+    files: HashMap<PathBuf, HashSet<usize>>,
+}
+
+impl AddedLines {
+    /// `directory` is any path inside the work tree, `base` a ref like `origin/main`
+    pub fn since(directory: &str, base: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let root = git(directory, &["rev-parse", "--show-toplevel"])
+            .map_err(|e| ConfigError::new(format!("--diff-base needs a git work tree: {}", e)))?;
+        let root = PathBuf::from(root.trim());
+        let merge_base = git(directory, &["merge-base", base, "HEAD"])
+            .map_err(|e| ConfigError::new(format!("No merge base with '{}': {}", base, e)))?;
+
+        // No context lines, so every hunk header is exactly the added range
+        let diff = git(directory, &["diff", "--unified=0", "--no-color", "--no-renames", "--no-ext-diff", merge_base.trim(), "--"])?;
+        let files = parse_unified_diff(&diff).into_iter()
+            .map(|(path, lines)| (canonical(&root.join(path)), lines))
+            .collect();
+        Ok(Self { root, files })
+    }
+
+    /* ========================================================================================== */
+    /// Whether a 1-based line of `file` was added on this branch; files outside the work tree never are
+    pub fn contains(&self, file: &str, line: usize) -> bool {
+        let path = canonical(Path::new(file));
+        path.starts_with(&self.root) && self.files.get(&path).is_some_and(|lines| lines.contains(&line))
+    }
+}
+
+/* ============================================================================================== */
+fn git(directory: &str, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Cannot run git: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string().into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/* ============================================================================================== */
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/* ============================================================================================== */
+/// Added line numbers per new-side path in `git diff --unified=0` output
+fn parse_unified_diff(diff: &str) -> HashMap<String, HashSet<usize>> {
+    let mut files: HashMap<String, HashSet<usize>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            // Deleted files have "+++ /dev/null" and add nothing
+            current = path.strip_prefix("b/").map(str::to_string);
+        } else if let (Some(hunk), Some(path)) = (line.strip_prefix("@@ "), current.as_ref()) {
+            // "@@ -12,3 +14,5 @@": five lines added from line 14; a missing count means one
+            let Some(added) = hunk.split_whitespace().find_map(|range| range.strip_prefix('+')) else {
+                continue;
+            };
+            let (start, count) = match added.split_once(',') {
+                Some((start, count)) => (start.parse().unwrap_or(0), count.parse().unwrap_or(0)),
+                None => (added.parse().unwrap_or(0), 1),
+            };
+            files.entry(path.clone()).or_default().extend(start..start + count);
+        }
+    }
+
+    files
+}
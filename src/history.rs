@@ -0,0 +1,92 @@
+use crate::unused_detector::UnusedReport;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSnapshot {
+    pub file: String,
+    pub total_classes: usize,
+    pub unused_classes: Vec<String>,
+    /// From `[owners]`, when one of its globs matches the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// `@doc` comments of unused classes that have one, by class name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub docs: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub directory: String,
+    pub total_classes: usize,
+    pub unused_classes: usize,
+    pub files: Vec<FileSnapshot>,
+}
+
+impl HistoryEntry {
+    pub fn from_report(report: &UnusedReport, directory: &str) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        let mut files: Vec<FileSnapshot> = report.by_file
+            .iter()
+            .map(|(file, classes)| FileSnapshot {
+                file: file.clone(),
+                total_classes: classes.len(),
+                unused_classes: classes.iter()
+                    .filter(|c| c.is_unused)
+                    .map(|c| c.class.name.clone())
+                    .collect(),
+                owner: report.owners.get(file).cloned(),
+                docs: classes.iter()
+                    .filter(|c| c.is_unused)
+                    .filter_map(|c| Some((c.class.name.clone(), c.class.doc.clone()?)))
+                    .collect(),
+            })
+            .collect();
+        files.sort_by(|a, b| a.file.cmp(&b.file));
+
+        Self {
+            timestamp,
+            directory: directory.to_string(),
+            total_classes: report.total_classes,
+            unused_classes: report.unused_classes.len(),
+            files,
+        }
+    }
+}
+
+/* ============================================================================================== */
+/*                        History store: one JSON entry per line, append-only                     */
+/* ============================================================================================== */
+pub fn append_history(path: &str, entry: &HistoryEntry) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/* ============================================================================================== */
+pub fn load_history(path: &str) -> Result<Vec<HistoryEntry>, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read history '{}': {}", path, e))?;
+
+    let mut entries = Vec::new();
+    for (line_num, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: HistoryEntry = serde_json::from_str(line)
+            .map_err(|e| format!("Invalid history entry at {}:{}: {}", path, line_num + 1, e))?;
+        entries.push(entry);
+    }
+
+    entries.sort_by_key(|entry| entry.timestamp);
+    Ok(entries)
+}
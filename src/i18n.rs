@@ -0,0 +1,413 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+// Checked before LANG so the tool can be localized without changing the whole shell
+const LOCALE_ENV_VAR: &str = "TAG_FINDER_LANG";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Nl,
+}
+
+impl Locale {
+    /// Accepts plain codes (`nl`) as well as POSIX/BCP 47 tags (`nl_NL.UTF-8`, `nl-BE`)
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match language.as_str() {
+            "en" => Some(Locale::En),
+            "nl" => Some(Locale::Nl),
+            _ => None,
+        }
+    }
+
+    /* ========================================================================================== */
+    pub fn detect(configured: Option<&str>) -> Self {
+        // Config wins over TAG_FINDER_LANG, which wins over the system LANG
+        configured
+            .and_then(Self::from_tag)
+            .or_else(|| std::env::var(LOCALE_ENV_VAR).ok().and_then(|tag| Self::from_tag(&tag)))
+            .or_else(|| std::env::var("LANG").ok().and_then(|tag| Self::from_tag(&tag)))
+            .unwrap_or_default()
+    }
+
+    /* ========================================================================================== */
+    fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => EN,
+            Locale::Nl => NL,
+        }
+    }
+}
+
+/* ============================================================================================== */
+/*                     Message lookup: missing keys fall back to English, then the key            */
+/* ============================================================================================== */
+pub fn message(locale: Locale, key: &'static str) -> &'static str {
+    lookup(locale.catalog(), key)
+        .or_else(|| lookup(EN, key))
+        .unwrap_or(key)
+}
+
+/* ============================================================================================== */
+pub fn format_message(locale: Locale, key: &'static str, args: &[&dyn Display]) -> String {
+    // Positional {0}, {1}, ... so translations can reorder arguments
+    let mut text = message(locale, key).to_string();
+    for (index, arg) in args.iter().enumerate() {
+        text = text.replace(&format!("{{{}}}", index), &arg.to_string());
+    }
+    text
+}
+
+/* ============================================================================================== */
+pub fn message_catalog(locale: Locale) -> HashMap<String, String> {
+    let mut catalog: HashMap<String, String> = EN.iter()
+        .map(|(key, text)| (key.to_string(), text.to_string()))
+        .collect();
+    catalog.extend(locale.catalog().iter().map(|(key, text)| (key.to_string(), text.to_string())));
+    catalog
+}
+
+/* ============================================================================================== */
+fn lookup(catalog: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    catalog.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+}
+
+/* ============================================================================================== */
+/*                                            Catalogs                                            */
+/* ============================================================================================== */
+const EN: &[(&str, &str)] = &[
+    // Analysis progress
+    ("progress.reading_files", "Reading {0} files using {1} threads..."),
+    ("progress.bundle_modules", "Bundle metafile lists {0} modules, {1} scanned script files are not bundled"),
+    ("progress.reachable_stylesheets", "{0} of {1} stylesheets are reachable from the entry points"),
+    ("progress.sampling", "Sampling {0} of {1} usage files"),
+    ("progress.extracting", "Extracting CSS classes..."),
+    ("progress.found_classes", "Found {0} CSS classes. Checking usage..."),
+    ("progress.excluded_layers", "Skipped {0} classes in excluded layers ({1})"),
+    ("progress.detecting_patterns", "Detecting dynamic patterns..."),
+    ("progress.found_patterns", "Found {0} dynamic patterns:"),
+    ("progress.pattern_covers", "{0} (covers {1} classes)"),
+    ("progress.analyzing", "Analyzing {0} classes using {1} threads..."),
+    ("progress.step1_done", "Step 1 complete: {0} used via exact match, {1} need pattern check"),
+    ("progress.step2_done", "Step 2 complete: {0} used via dynamic pattern, {1} remain unused"),
+    ("progress.duplicate_content", "{0} usage files have the same content as an earlier file and weren't scanned again"),
+    ("progress.timed_out", "Time limit of {0} reached: {1} usage files not scanned, their classes are reported as not analyzed"),
+    ("progress.complete", "Analysis complete!"),
+    ("progress.max_depth_reached", "Not descending more than {0} levels into {1} (max_depth); deeper files are skipped"),
+    ("progress.walk_errors", "Skipped {0} unreadable paths while walking (first: {1}: {2}); use --strict-walk to fail instead"),
+    ("progress.read_failures", "Left out {0} files that couldn't be read (first: {1}: {2})"),
+    ("progress.compiling_scss", "Compiling SCSS before extracting classes..."),
+    ("progress.interpolated_classes", "Expanded {0} classes from SCSS interpolation, {1} selectors left dynamic"),
+    ("progress.scss_compile_failures", "Using the source of {0} SCSS files that didn't compile (first: {1}: {2})"),
+    ("progress.parallel_items", "{0} {1} items using {2} threads..."),
+    ("progress.streaming_files", "{0} {1} files using {2} reader and {3} analyzer threads (memory budget {4} MB)..."),
+    ("progress.processed", "Processed {0}/{1} items..."),
+    ("progress.reading_file_contents", "Reading files"),
+    ("progress.reading_stylesheets", "Reading stylesheets"),
+    ("progress.compiling_scss_files", "Compiling SCSS"),
+    ("progress.processing_css_files", "Processing files for CSS classes"),
+    ("progress.indexing_usage", "Indexing usage files"),
+    ("progress.analyzing_usage", "Analyzing"),
+    ("progress.scanning_files", "Scanning files"),
+    ("progress.max_files_reached", "Stopped after {0} files in {1} (max_files); the rest of the directory is not scanned"),
+    // Unused report
+    ("report.title", "UNUSED CSS CLASSES REPORT"),
+    ("report.filtered", "Only findings in {0}; usage anywhere in the project still counts"),
+    ("report.timed_out", "Incomplete: time limit of {0} exceeded after {1} of {2} usage files"),
+    ("report.total", "Total classes analyzed: {0}"),
+    ("report.unused", "Unused classes: {0}"),
+    ("report.used", "Used classes: {0}"),
+    ("markdown.title", "Unused CSS classes"),
+    ("markdown.class", "Class"),
+    ("markdown.file", "File"),
+    ("markdown.line", "Line"),
+    ("markdown.column", "Column"),
+    ("quickfix.unused", "unused class .{0}"),
+    ("report.low_confidence", "Low-confidence unused (stop words): {0}"),
+    ("report.recent", "Unused but younger than --min-age (not counted): {0}"),
+    ("report.legacy", "Unused but older than --diff-base (not counted): {0}"),
+    ("report.dead_code", "Used only by dead code: {0}"),
+    ("report.not_analyzed", "Not analyzed (no usage found before the time limit): {0}"),
+    ("report.used_only_in", "Used only in {0}: {1}"),
+    ("report.dead_combinations", "Possibly dead combinations: {0}"),
+    ("report.redundant_rules", "Redundant rules: {0}"),
+    ("report.duplicate_blocks", "Duplicate declaration blocks: {0}"),
+    ("report.unused_media_queries", "Media queries styling only unused classes: {0} of {1}"),
+    ("report.unused_data_attributes", "Unused data attribute selectors: {0}"),
+    ("report.unused_font_faces", "Unused @font-face families: {0}"),
+    ("report.unused_assets", "Assets referenced only from unused rules: {0}"),
+    ("report.dynamic_definitions", "Dynamic SCSS definitions (not checked): {0}"),
+    ("report.percentage", "Unused percentage: {0}%"),
+    ("report.sample", "Sampled {0} of {1} usage files (seed {2})"),
+    ("report.estimate", "Unused: at most {0}%"),
+    ("report.estimate_note", "Classes used only in files outside the sample count as unused, so a full scan can only report fewer."),
+    ("report.unused_heading", "UNUSED CLASSES:"),
+    ("report.tip", "TIP: Review these unused classes and consider removing them to clean up your CSS."),
+    ("report.dead_code_heading", "USED ONLY BY DEAD CODE (not in the bundle):"),
+    ("report.not_analyzed_heading", "NOT ANALYZED (time limit reached, no usage in the files scanned):"),
+    ("report.used_only_in_heading", "USED ONLY IN {0}:"),
+    ("report.dead_combinations_heading", "POSSIBLY DEAD COMBINATIONS (never applied together):"),
+    ("report.combination_in_file", "{0} in {1} (line {2})"),
+    ("report.redundant_rules_heading", "REDUNDANT RULES (every declaration overridden later):"),
+    ("report.redundant_rule", "{0} [{1}] in {2} (line {3}), overridden at line {4}"),
+    ("report.duplicate_blocks_heading", "DUPLICATE DECLARATION BLOCKS (consider a shared class or mixin):"),
+    ("report.duplicate_block", "{0}x {1}"),
+    ("report.unused_media_queries_heading", "UNUSED MEDIA QUERIES (every rule targets unused classes):"),
+    ("report.unused_data_attributes_heading", "UNUSED DATA ATTRIBUTES (never set, read or queried):"),
+    ("report.data_attribute_in_file", "{0} in {1}, {2} (line {3})"),
+    ("report.unused_font_faces_heading", "UNUSED @FONT-FACE FAMILIES (never named in font-family):"),
+    ("report.unused_assets_heading", "ASSETS REFERENCED ONLY FROM UNUSED RULES:"),
+    ("report.dynamic_definitions_heading", "DYNAMIC DEFINITIONS (class names only known after compiling):"),
+    ("report.media_query_rules", "{0} ({1} rules)"),
+    ("report.rules_heading", "RULES:"),
+    ("report.rule_findings", "{0} ({1}): {2} findings"),
+    ("report.rules_failed", "Failed: findings for error-level rules {0}"),
+    ("report.by_file_heading", "BY FILE BREAKDOWN:"),
+    ("report.class_in_file", ".{0} in {1} (line {2})"),
+    ("report.class_line", ".{0} (line {1})"),
+    ("report.layer_note", " [@layer {0}]"),
+    ("report.scope_note", " [@scope {0}]"),
+    ("report.low_confidence_note", " (low confidence)"),
+    ("report.recent_note", " (recent)"),
+    ("report.legacy_note", " (legacy)"),
+    ("report.author_note", " - {0}"),
+    ("report.by_author_heading", "UNUSED CLASSES LAST TOUCHED BY {0} ({1}):"),
+    ("report.owner_note", " ({0})"),
+    ("report.by_owner_heading", "UNUSED CLASSES OWNED BY {0} ({1}):"),
+    ("report.file_totals", "Total: {0}, Unused: {1}, Used: {2}"),
+    ("report.file_unused", "Unused classes:"),
+    ("report.file_dead_code", "Used only by dead code:"),
+    ("report.file_not_analyzed", "Not analyzed:"),
+    ("report.preview_heading", "UNUSED CLASSES (first 10):"),
+    ("report.preview_more", "... and {0} more"),
+    ("report.preview_hint", "Use --detailed for full list or --by-file for file breakdown"),
+    // Command messages
+    ("cli.error", "Error: {0}"),
+    ("gui.unused_changed", "{0}: unused classes went from {1} to {2}"),
+    ("cli.no_examples", "No examples for '{0}'."),
+    ("cli.dashboard_written", "Dashboard with {0} runs written to {1}/index.html ({2} pages)"),
+    ("cli.index_written", "Index of {0} classes and {1} usages written to {2}"),
+    ("where.not_found", "No stylesheet defines .{0}"),
+    ("where.definitions", "Defined in ({0}):"),
+    ("where.usages", "Used in ({0}):"),
+    ("where.no_usages", "(no usages; see `tag-finder explain` for dynamic patterns)"),
+    ("cli.files_scanned", "{0} files would be scanned"),
+    ("cli.css_files", "CSS/SCSS files ({0}):"),
+    ("cli.usage_files", "Usage files ({0}):"),
+    ("cli.quickfix_written", "Quickfix list written to {0} (open with `vim -q {0}`)"),
+    ("cli.history_recorded", "Run recorded in {0}"),
+    ("cli.partial_not_recorded", "Incomplete run: not recorded in the history and no webhook notification"),
+    ("cli.repo_fetching", "Fetching {0} at {1}..."),
+    ("cli.repo_cloned", "Shallow clone ready in {0}"),
+    ("cli.repo_downloaded", "git clone failed, downloaded a tarball to {0} instead"),
+    ("cli.decision_log_written", "{0} decisions written to {1}"),
+    ("cli.report_written", "Report written to {0}"),
+    ("cli.graph_written", "Co-usage graph with {0} classes and {1} links written to {2}"),
+    ("cli.notify_no_thresholds", "--notify-webhook given but no [notify] thresholds configured, nothing to check"),
+    ("cli.notified", "Threshold breached, webhook notified"),
+    ("cli.cancelled", "Cancelled"),
+    ("cli.config_loaded", "Loaded configuration from {0}"),
+    ("cli.config_loaded_profile", "Loaded configuration from {0} (profile {1})"),
+    ("cli.config_defaults", "No config file found, using defaults"),
+    ("cli.ctrlc_unavailable", "Ctrl-C won't report a cancelled run: {0}"),
+    ("cli.batch_started", "Running {0} jobs from {1} ({2} at a time)"),
+    ("cli.batch_job_done", "{0}: {1} of {2} classes unused, report in {3}"),
+    ("cli.batch_job_failed", "{0}: {1}"),
+    ("cli.batch_finished", "{0} of {1} jobs completed"),
+    ("cli.batch_summary_written", "Batch summary written to {0}"),
+    ("cli.selftest_fixture_passed", "{0}: as expected"),
+    ("cli.selftest_fixture_failed", "{0}: differs from the golden report (- expected, + got)"),
+    ("cli.selftest_finished", "{0} of {1} fixtures as expected"),
+    ("aggregate.title", "UNUSED CSS ACROSS PROJECTS"),
+    ("aggregate.totals", "{0} of {1} classes unused ({2}%) in {3} projects"),
+    ("aggregate.project", "{0}: {1} of {2} unused"),
+    ("aggregate.worst_offenders", "Files with the most unused classes:"),
+    ("aggregate.conflicts", "Shared class conflicts: {0}"),
+    ("aggregate.conflict", ".{0} used in {1}, unused in {2}"),
+    // Word search
+    ("word.results", "Search results for word: '{0}'"),
+    ("word.found_css", "Found in CSS/SCSS files:"),
+    ("word.found_other", "Found in other files:"),
+    ("word.found_in_category", "Found in {0} files:"),
+    ("word.occurrence", "1 occurrence"),
+    ("word.occurrences", "{0} occurrences"),
+    ("word.not_css_only", "Word '{0}' found but not CSS-only. Use --all to see details."),
+    ("word.not_found", "Word '{0}' not found in any files."),
+    ("word.css_only", "SUCCESS: '{0}' appears ONLY in CSS/SCSS files!"),
+    ("word.safe_to_remove", "This code might be extraneous and safe to remove."),
+    ("word.also_elsewhere", "Word '{0}' appears in non-CSS files too."),
+    ("word.used_only_in", "Word '{0}' appears outside CSS only in {1}."),
+];
+
+const NL: &[(&str, &str)] = &[
+    // Analysis progress
+    ("progress.reading_files", "{0} bestanden lezen met {1} threads..."),
+    ("progress.bundle_modules", "Bundel-metafile bevat {0} modules, {1} gescande scriptbestanden zitten niet in de bundel"),
+    ("progress.reachable_stylesheets", "{0} van {1} stylesheets zijn bereikbaar vanaf de entrypoints"),
+    ("progress.sampling", "Steekproef van {0} van {1} gebruiksbestanden"),
+    ("progress.extracting", "CSS-klassen extraheren..."),
+    ("progress.found_classes", "{0} CSS-klassen gevonden. Gebruik controleren..."),
+    ("progress.excluded_layers", "{0} klassen in uitgesloten lagen overgeslagen ({1})"),
+    ("progress.detecting_patterns", "Dynamische patronen detecteren..."),
+    ("progress.found_patterns", "{0} dynamische patronen gevonden:"),
+    ("progress.pattern_covers", "{0} (dekt {1} klassen)"),
+    ("progress.analyzing", "{0} klassen analyseren met {1} threads..."),
+    ("progress.step1_done", "Stap 1 voltooid: {0} gebruikt via exacte overeenkomst, {1} vereisen een patrooncontrole"),
+    ("progress.step2_done", "Stap 2 voltooid: {0} gebruikt via dynamisch patroon, {1} blijven ongebruikt"),
+    ("progress.duplicate_content", "{0} gebruiksbestanden hebben dezelfde inhoud als een eerder bestand en zijn niet opnieuw gescand"),
+    ("progress.timed_out", "Tijdslimiet van {0} bereikt: {1} gebruiksbestanden niet gescand, hun klassen worden als niet geanalyseerd gemeld"),
+    ("progress.complete", "Analyse voltooid!"),
+    ("progress.max_depth_reached", "Niet dieper dan {0} niveaus in {1} (max_depth); diepere bestanden worden overgeslagen"),
+    ("progress.walk_errors", "{0} onleesbare paden overgeslagen tijdens het doorlopen (eerste: {1}: {2}); gebruik --strict-walk om te stoppen"),
+    ("progress.read_failures", "{0} bestanden weggelaten die niet gelezen konden worden (eerste: {1}: {2})"),
+    ("progress.compiling_scss", "SCSS compileren voor het zoeken naar klassen..."),
+    ("progress.interpolated_classes", "{0} klassen uit SCSS-interpolatie uitgeschreven, {1} selectors blijven dynamisch"),
+    ("progress.scss_compile_failures", "Bron gebruikt van {0} SCSS-bestanden die niet compileerden (eerste: {1}: {2})"),
+    ("progress.parallel_items", "{0}: {1} items met {2} threads..."),
+    ("progress.streaming_files", "{0}: {1} bestanden met {2} lees- en {3} analysethreads (geheugenbudget {4} MB)..."),
+    ("progress.processed", "{0}/{1} items verwerkt..."),
+    ("progress.reading_file_contents", "Bestanden lezen"),
+    ("progress.reading_stylesheets", "Stylesheets lezen"),
+    ("progress.compiling_scss_files", "SCSS compileren"),
+    ("progress.processing_css_files", "Bestanden doorzoeken op CSS-klassen"),
+    ("progress.indexing_usage", "Gebruiksbestanden indexeren"),
+    ("progress.analyzing_usage", "Analyseren"),
+    ("progress.scanning_files", "Bestanden scannen"),
+    ("progress.max_files_reached", "Gestopt na {0} bestanden in {1} (max_files); de rest van de map wordt niet gescand"),
+    // Unused report
+    ("report.title", "RAPPORT ONGEBRUIKTE CSS-KLASSEN"),
+    ("report.filtered", "Alleen bevindingen in {0}; gebruik in het hele project telt mee"),
+    ("report.timed_out", "Onvolledig: tijdslimiet van {0} overschreden na {1} van {2} gebruiksbestanden"),
+    ("report.total", "Geanalyseerde klassen: {0}"),
+    ("report.unused", "Ongebruikte klassen: {0}"),
+    ("report.used", "Gebruikte klassen: {0}"),
+    ("markdown.title", "Ongebruikte CSS-klassen"),
+    ("markdown.class", "Klasse"),
+    ("markdown.file", "Bestand"),
+    ("markdown.line", "Regel"),
+    ("markdown.column", "Kolom"),
+    ("quickfix.unused", "ongebruikte klasse .{0}"),
+    ("report.low_confidence", "Ongebruikt met lage zekerheid (stopwoorden): {0}"),
+    ("report.recent", "Ongebruikt maar jonger dan --min-age (niet meegeteld): {0}"),
+    ("report.legacy", "Ongebruikt maar ouder dan --diff-base (niet meegeteld): {0}"),
+    ("report.dead_code", "Alleen gebruikt door dode code: {0}"),
+    ("report.not_analyzed", "Niet geanalyseerd (geen gebruik gevonden voor de tijdslimiet): {0}"),
+    ("report.used_only_in", "Alleen gebruikt in {0}: {1}"),
+    ("report.dead_combinations", "Mogelijk dode combinaties: {0}"),
+    ("report.redundant_rules", "Overbodige regels: {0}"),
+    ("report.duplicate_blocks", "Dubbele declaratieblokken: {0}"),
+    ("report.unused_media_queries", "Media queries met alleen ongebruikte klassen: {0} van {1}"),
+    ("report.unused_data_attributes", "Ongebruikte data-attribuutselectors: {0}"),
+    ("report.unused_font_faces", "Ongebruikte @font-face-families: {0}"),
+    ("report.unused_assets", "Bestanden alleen gebruikt door ongebruikte regels: {0}"),
+    ("report.dynamic_definitions", "Dynamische SCSS-definities (niet gecontroleerd): {0}"),
+    ("report.percentage", "Percentage ongebruikt: {0}%"),
+    ("report.sample", "Steekproef: {0} van {1} gebruiksbestanden (seed {2})"),
+    ("report.estimate", "Ongebruikt: hoogstens {0}%"),
+    ("report.estimate_note", "Klassen die alleen buiten de steekproef gebruikt worden tellen als ongebruikt, een volledige scan kan dus alleen minder vinden."),
+    ("report.unused_heading", "ONGEBRUIKTE KLASSEN:"),
+    ("report.tip", "TIP: Bekijk deze ongebruikte klassen en overweeg ze te verwijderen om je CSS op te schonen."),
+    ("report.dead_code_heading", "ALLEEN GEBRUIKT DOOR DODE CODE (niet in de bundel):"),
+    ("report.not_analyzed_heading", "NIET GEANALYSEERD (tijdslimiet bereikt, geen gebruik gevonden in de gescande bestanden):"),
+    ("report.used_only_in_heading", "ALLEEN GEBRUIKT IN {0}:"),
+    ("report.dead_combinations_heading", "MOGELIJK DODE COMBINATIES (nooit samen toegepast):"),
+    ("report.combination_in_file", "{0} in {1} (regel {2})"),
+    ("report.redundant_rules_heading", "OVERBODIGE REGELS (elke declaratie later overschreven):"),
+    ("report.redundant_rule", "{0} [{1}] in {2} (regel {3}), overschreven op regel {4}"),
+    ("report.duplicate_blocks_heading", "DUBBELE DECLARATIEBLOKKEN (overweeg een gedeelde klasse of mixin):"),
+    ("report.duplicate_block", "{0}x {1}"),
+    ("report.unused_media_queries_heading", "ONGEBRUIKTE MEDIA QUERIES (elke regel richt zich op ongebruikte klassen):"),
+    ("report.unused_data_attributes_heading", "ONGEBRUIKTE DATA-ATTRIBUTEN (nergens gezet, gelezen of opgevraagd):"),
+    ("report.data_attribute_in_file", "{0} in {1}, {2} (regel {3})"),
+    ("report.unused_font_faces_heading", "ONGEBRUIKTE @FONT-FACE-FAMILIES (nergens in font-family genoemd):"),
+    ("report.unused_assets_heading", "BESTANDEN ALLEEN GEBRUIKT DOOR ONGEBRUIKTE REGELS:"),
+    ("report.dynamic_definitions_heading", "DYNAMISCHE DEFINITIES (klassennamen pas bekend na compileren):"),
+    ("report.media_query_rules", "{0} ({1} regels)"),
+    ("report.rules_heading", "REGELS:"),
+    ("report.rule_findings", "{0} ({1}): {2} bevindingen"),
+    ("report.rules_failed", "Mislukt: bevindingen voor regels op foutniveau {0}"),
+    ("report.by_file_heading", "OVERZICHT PER BESTAND:"),
+    ("report.class_in_file", ".{0} in {1} (regel {2})"),
+    ("report.class_line", ".{0} (regel {1})"),
+    ("report.layer_note", " [@layer {0}]"),
+    ("report.scope_note", " [@scope {0}]"),
+    ("report.low_confidence_note", " (lage zekerheid)"),
+    ("report.recent_note", " (recent)"),
+    ("report.legacy_note", " (bestaand)"),
+    ("report.author_note", " - {0}"),
+    ("report.by_author_heading", "ONGEBRUIKTE KLASSEN LAATST GEWIJZIGD DOOR {0} ({1}):"),
+    ("report.owner_note", " ({0})"),
+    ("report.by_owner_heading", "ONGEBRUIKTE KLASSEN VAN {0} ({1}):"),
+    ("report.file_totals", "Totaal: {0}, Ongebruikt: {1}, Gebruikt: {2}"),
+    ("report.file_unused", "Ongebruikte klassen:"),
+    ("report.file_dead_code", "Alleen gebruikt door dode code:"),
+    ("report.file_not_analyzed", "Niet geanalyseerd:"),
+    ("report.preview_heading", "ONGEBRUIKTE KLASSEN (eerste 10):"),
+    ("report.preview_more", "... en nog {0}"),
+    ("report.preview_hint", "Gebruik --detailed voor de volledige lijst of --by-file voor een overzicht per bestand"),
+    // Command messages
+    ("cli.error", "Fout: {0}"),
+    ("gui.unused_changed", "{0}: ongebruikte klassen gingen van {1} naar {2}"),
+    ("cli.no_examples", "Geen voorbeelden voor '{0}'."),
+    ("cli.dashboard_written", "Dashboard met {0} runs geschreven naar {1}/index.html ({2} pagina's)"),
+    ("cli.index_written", "Index van {0} classes en {1} gebruiken geschreven naar {2}"),
+    ("where.not_found", "Geen stylesheet definieert .{0}"),
+    ("where.definitions", "Gedefinieerd in ({0}):"),
+    ("where.usages", "Gebruikt in ({0}):"),
+    ("where.no_usages", "(niet gebruikt; zie `tag-finder explain` voor dynamische patronen)"),
+    ("cli.files_scanned", "{0} bestanden zouden gescand worden"),
+    ("cli.css_files", "CSS/SCSS-bestanden ({0}):"),
+    ("cli.usage_files", "Gebruiksbestanden ({0}):"),
+    ("cli.quickfix_written", "Quickfix-lijst geschreven naar {0} (openen met `vim -q {0}`)"),
+    ("cli.history_recorded", "Run vastgelegd in {0}"),
+    ("cli.partial_not_recorded", "Onvolledige run: niet vastgelegd in de geschiedenis en geen webhook-melding"),
+    ("cli.repo_fetching", "{0} ophalen op {1}..."),
+    ("cli.repo_cloned", "Ondiepe kloon klaar in {0}"),
+    ("cli.repo_downloaded", "git clone mislukt, in plaats daarvan een tarball gedownload naar {0}"),
+    ("cli.decision_log_written", "{0} beslissingen geschreven naar {1}"),
+    ("cli.report_written", "Rapport geschreven naar {0}"),
+    ("cli.graph_written", "Graaf van samen gebruikte klassen met {0} klassen en {1} verbindingen geschreven naar {2}"),
+    ("cli.notify_no_thresholds", "--notify-webhook opgegeven maar geen [notify]-drempels ingesteld, niets te controleren"),
+    ("cli.notified", "Drempel overschreden, webhook ingelicht"),
+    ("cli.cancelled", "Geannuleerd"),
+    ("cli.config_loaded", "Configuratie geladen uit {0}"),
+    ("cli.config_loaded_profile", "Configuratie geladen uit {0} (profiel {1})"),
+    ("cli.config_defaults", "Geen configuratiebestand gevonden, standaardinstellingen gebruikt"),
+    ("cli.ctrlc_unavailable", "Ctrl-C meldt een afgebroken run niet: {0}"),
+    ("cli.batch_started", "{0} jobs uit {1} uitvoeren ({2} tegelijk)"),
+    ("cli.batch_job_done", "{0}: {1} van {2} klassen ongebruikt, rapport in {3}"),
+    ("cli.batch_job_failed", "{0}: {1}"),
+    ("cli.batch_finished", "{0} van {1} jobs voltooid"),
+    ("cli.batch_summary_written", "Batch-samenvatting geschreven naar {0}"),
+    ("cli.selftest_fixture_passed", "{0}: zoals verwacht"),
+    ("cli.selftest_fixture_failed", "{0}: wijkt af van het verwachte rapport (- verwacht, + gekregen)"),
+    ("cli.selftest_finished", "{0} van {1} fixtures zoals verwacht"),
+    ("aggregate.title", "ONGEBRUIKTE CSS OVER PROJECTEN"),
+    ("aggregate.totals", "{0} van {1} klassen ongebruikt ({2}%) in {3} projecten"),
+    ("aggregate.project", "{0}: {1} van {2} ongebruikt"),
+    ("aggregate.worst_offenders", "Bestanden met de meeste ongebruikte klassen:"),
+    ("aggregate.conflicts", "Conflicten in gedeelde klassen: {0}"),
+    ("aggregate.conflict", ".{0} gebruikt in {1}, ongebruikt in {2}"),
+    // Word search
+    ("word.results", "Zoekresultaten voor woord: '{0}'"),
+    ("word.found_css", "Gevonden in CSS/SCSS-bestanden:"),
+    ("word.found_other", "Gevonden in andere bestanden:"),
+    ("word.found_in_category", "Gevonden in {0}-bestanden:"),
+    ("word.occurrence", "1 keer"),
+    ("word.occurrences", "{0} keer"),
+    ("word.not_css_only", "Woord '{0}' gevonden, maar niet alleen in CSS. Gebruik --all voor details."),
+    ("word.not_found", "Woord '{0}' in geen enkel bestand gevonden."),
+    ("word.css_only", "GELUKT: '{0}' komt ALLEEN voor in CSS/SCSS-bestanden!"),
+    ("word.safe_to_remove", "Deze code is mogelijk overbodig en kan veilig worden verwijderd."),
+    ("word.also_elsewhere", "Woord '{0}' komt ook voor in niet-CSS-bestanden."),
+    ("word.used_only_in", "Woord '{0}' komt buiten CSS alleen voor in {1}."),
+];
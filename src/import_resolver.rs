@@ -0,0 +1,108 @@
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+pub struct ImportResolver {
+    statement_regex: Regex,
+    target_regex: Regex,
+}
+
+impl Default for ImportResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImportResolver {
+    pub fn new() -> Self {
+        Self {
+            // @import "a", "b"; / @use "sass:math" as m; / @forward "src/list";
+            statement_regex: Regex::new(r"@(?:import|use|forward)\s+([^;]+);").unwrap(),
+            // url("a.css") or a plain quoted string
+            target_regex: Regex::new(r#"url\(\s*["']?([^"')]+)["']?\s*\)|["']([^"']+)["']"#).unwrap(),
+        }
+    }
+
+    /* ========================================================================================== */
+    pub fn collect_reachable(&self, entries: &[PathBuf]) -> Result<HashSet<PathBuf>, Box<dyn std::error::Error>> {
+        let mut reachable = HashSet::new();
+        let mut pending: Vec<PathBuf> = Vec::new();
+
+        for entry in entries {
+            let canonical = std::fs::canonicalize(entry)
+                .map_err(|e| format!("Entry stylesheet '{}' not found: {}", entry.display(), e))?;
+            pending.push(canonical);
+        }
+
+        while let Some(stylesheet) = pending.pop() {
+            if !reachable.insert(stylesheet.clone()) {
+                continue;
+            }
+
+            // Unreadable imports are skipped rather than failing the whole run
+            let Ok(content) = std::fs::read_to_string(&stylesheet) else {
+                continue;
+            };
+
+            let base_dir = stylesheet.parent().unwrap_or(Path::new("."));
+            for target in self.extract_import_targets(&content) {
+                if let Some(resolved) = self.resolve_import(base_dir, &target) {
+                    pending.push(resolved);
+                }
+            }
+        }
+
+        Ok(reachable)
+    }
+
+    /* ========================================================================================== */
+    pub fn extract_import_targets(&self, content: &str) -> Vec<String> {
+        let mut targets = Vec::new();
+
+        for statement in self.statement_regex.captures_iter(content) {
+            for cap in self.target_regex.captures_iter(&statement[1]) {
+                if let Some(target) = cap.get(1).or_else(|| cap.get(2)) {
+                    targets.push(target.as_str().trim().to_string());
+                }
+            }
+        }
+
+        targets
+    }
+
+    /* ========================================================================================== */
+    fn resolve_import(&self, base_dir: &Path, target: &str) -> Option<PathBuf> {
+        // Built-in Sass modules, remote URLs and node_modules shortcuts are not project files
+        if target.starts_with("sass:") || target.starts_with("http") || target.starts_with("//") || target.starts_with('~') {
+            return None;
+        }
+
+        self.candidate_paths(base_dir, target)
+            .into_iter()
+            .find(|candidate| candidate.is_file())
+            .and_then(|found| std::fs::canonicalize(found).ok())
+    }
+
+    /* ========================================================================================== */
+    fn candidate_paths(&self, base_dir: &Path, target: &str) -> Vec<PathBuf> {
+        let path = base_dir.join(target);
+        let parent = path.parent().unwrap_or(base_dir).to_path_buf();
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or(target).to_string();
+
+        if path.extension().is_some() {
+            // Explicit extension, only the partial variant can differ
+            return vec![path.clone(), parent.join(format!("_{}", file_name))];
+        }
+
+        // Sass lookup order: file, partial, plain CSS, then directory index files
+        vec![
+            parent.join(format!("{}.scss", file_name)),
+            parent.join(format!("_{}.scss", file_name)),
+            parent.join(format!("{}.sass", file_name)),
+            parent.join(format!("_{}.sass", file_name)),
+            parent.join(format!("{}.css", file_name)),
+            path.join("_index.scss"),
+            path.join("index.scss"),
+        ]
+    }
+}
@@ -0,0 +1,118 @@
+use crate::utils::has_extension;
+use regex::Regex;
+use std::path::Path;
+
+/// Pug (and its old name Jade), Slim and Haml: markup by indentation, classes by `.name` shorthand
+pub const INDENTED_TEMPLATE_EXTENSIONS: &[&str] = &["pug", "jade", "slim", "haml"];
+
+// Lines that open a block of raw CSS: `style.` (pug), `:css`/`:scss` filters (haml, pug), `css:` (slim), `%style` (haml)
+const STYLE_BLOCK_PATTERN: &str = r"^(?:style(?:\([^)]*\))?\.?|%style\b.*|:(?:css|scss|sass|less|stylus)\b.*|(?:css|scss|sass):.*)$";
+// `style="…"` (pug, slim), `style: "…"` and `:style => "…"` (haml)
+const STYLE_ATTRIBUTE_PATTERN: &str = r#"(?i):?\bstyle\s*(?:=>|=|:)\s*(?:"[^"]*"|'[^']*')"#;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ShorthandTag<'a> {
+    pub tag: Option<&'a str>,
+    pub classes: Vec<&'a str>,
+    /// Byte length of the `%tag.class#id` head, relative to the trimmed line
+    pub length: usize,
+}
+
+/* ============================================================================================== */
+pub fn is_indented_template(path: &Path) -> bool {
+    has_extension(path, INDENTED_TEMPLATE_EXTENSIONS)
+}
+
+/* ============================================================================================== */
+/*                     Parse the `%div.card.card--wide#main` head of a template line              */
+/* ============================================================================================== */
+pub fn parse_shorthand(line: &str) -> Option<ShorthandTag<'_>> {
+    let trimmed = line.trim_start();
+    let bytes = trimmed.as_bytes();
+    let mut position = 0;
+
+    // Haml marks tags with `%`, Pug and Slim start with the bare tag name
+    if bytes.first() == Some(&b'%') {
+        position += 1;
+    }
+    let tag_start = position;
+    position += name_length(&trimmed[position..], true);
+    let tag = (position > tag_start).then(|| &trimmed[tag_start..position]);
+
+    let mut classes = Vec::new();
+    while let Some(marker) = bytes.get(position).copied().filter(|b| *b == b'.' || *b == b'#') {
+        let length = name_length(&trimmed[position + 1..], false);
+        if length == 0 {
+            break;
+        }
+        if marker == b'.' {
+            classes.push(&trimmed[position + 1..position + 1 + length]);
+        }
+        position += 1 + length;
+    }
+
+    (!classes.is_empty()).then_some(ShorthandTag { tag, classes, length: position })
+}
+
+/* ============================================================================================== */
+/*          Usage text for a template: shorthand as class attributes, embedded CSS removed        */
+/* ============================================================================================== */
+pub fn template_usage_text(content: &str) -> String {
+    let style_block = Regex::new(STYLE_BLOCK_PATTERN).unwrap();
+    let style_attribute = Regex::new(STYLE_ATTRIBUTE_PATTERN).unwrap();
+
+    let mut block_indent: Option<usize> = None;
+    let mut lines = Vec::new();
+
+    for line in content.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+
+        // Everything indented below a style block is CSS, which must not count as usage.
+        // Blank lines keep line numbers valid for later passes.
+        if let Some(style_indent) = block_indent {
+            if trimmed.is_empty() || indent > style_indent {
+                lines.push(String::new());
+                continue;
+            }
+            block_indent = None;
+        }
+
+        if style_block.is_match(trimmed) {
+            block_indent = Some(indent);
+            lines.push(String::new());
+            continue;
+        }
+
+        let line = style_attribute.replace_all(line, "");
+        lines.push(match parse_shorthand(&line) {
+            // `#id` shorthand is dropped, so ids don't count as usage of a same-named class
+            Some(shorthand) => format!(
+                "{}{} class=\"{}\"{}",
+                &line[..indent],
+                shorthand.tag.unwrap_or("div"),
+                shorthand.classes.join(" "),
+                &line[indent + shorthand.length..]
+            ),
+            None => line.into_owned(),
+        });
+    }
+
+    lines.join("\n")
+}
+
+/* ============================================================================================== */
+fn name_length(text: &str, is_tag: bool) -> usize {
+    // Tag names start with a letter; class names may also start with `-` or `_`
+    text.char_indices()
+        .take_while(|(index, c)| {
+            if *index == 0 && is_tag {
+                c.is_ascii_alphabetic()
+            } else {
+                c.is_alphanumeric() || *c == '-' || *c == '_'
+            }
+        })
+        .map(|(index, c)| index + c.len_utf8())
+        .last()
+        .unwrap_or(0)
+}
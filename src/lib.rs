@@ -3,20 +3,124 @@ pub mod css_parser;
 pub mod unused_detector;
 pub mod utils;
 pub mod parallel_processor;
+pub mod content_cache;
 pub mod file_walker;
 pub mod text_processor;
 pub mod config;
 pub mod traits;
+pub mod explanation;
+pub mod import_resolver;
+pub mod bundle_metafile;
+pub mod notifier;
+pub mod history;
+pub mod dashboard;
+pub mod output_style;
+pub mod number_format;
+pub mod i18n;
+pub mod decision_log;
+pub mod sampling;
+pub mod deadline;
+pub mod cancellation;
+pub mod scan_filter;
+pub mod data_file;
+pub mod indented_template;
+pub mod component_file;
+pub mod compound_selector;
+pub mod archive;
+pub mod remote_repo;
+pub mod file_provider;
+pub mod rules;
+pub mod git_blame;
+pub mod git_diff;
+pub mod pre_scan;
+pub mod report_page;
+pub mod report_filter;
+pub mod background_scan;
+pub mod version_info;
+pub mod pager;
+pub mod diagnostics;
+pub mod class_index;
+pub mod css_rules;
+pub mod redundant_rules;
+pub mod duplicate_blocks;
+pub mod media_queries;
+pub mod data_attributes;
+pub mod assets;
+pub mod co_usage_graph;
+pub mod observer;
+pub mod output_file;
+pub mod scss_compiler;
+pub mod scss_interpolation;
+pub mod batch;
+pub mod aggregate;
+pub mod owners;
+pub mod exit_status;
+pub mod system_limits;
+pub mod error;
+pub mod presets;
+pub mod selftest;
 
 pub use config::*;
-pub use scanner::{FileScanner, ScanResult};
+pub use scanner::{validate_word, FileScanner, FindWordOptions, InvalidWord, ScanResult, WordSearchResult, MAX_WORD_LENGTH};
 pub use css_parser::*;
 pub use unused_detector::*;
 pub use utils::*;
 pub use parallel_processor::*;
+pub use content_cache::*;
 pub use file_walker::*;
 pub use text_processor::*;
 pub use traits::*;
+pub use explanation::*;
+pub use import_resolver::*;
+pub use bundle_metafile::*;
+pub use notifier::*;
+pub use history::*;
+pub use dashboard::*;
+pub use output_style::*;
+pub use number_format::*;
+pub use i18n::*;
+pub use decision_log::*;
+pub use sampling::*;
+pub use deadline::*;
+pub use cancellation::*;
+pub use scan_filter::*;
+pub use data_file::*;
+pub use indented_template::*;
+pub use component_file::*;
+pub use compound_selector::*;
+pub use archive::*;
+pub use remote_repo::*;
+pub use file_provider::*;
+pub use rules::*;
+pub use git_blame::*;
+pub use git_diff::*;
+pub use pre_scan::*;
+pub use report_page::*;
+pub use report_filter::*;
+pub use background_scan::*;
+pub use version_info::*;
+pub use pager::*;
+pub use diagnostics::*;
+pub use class_index::*;
+pub use css_rules::*;
+pub use redundant_rules::*;
+pub use duplicate_blocks::*;
+pub use media_queries::*;
+pub use data_attributes::*;
+pub use assets::*;
+pub use co_usage_graph::*;
+pub use observer::*;
+pub use output_file::*;
+pub use scss_compiler::*;
+pub use scss_interpolation::*;
+pub use batch::*;
+pub use aggregate::*;
+pub use owners::*;
+pub use exit_status::*;
+pub use system_limits::*;
+pub use error::*;
+pub use presets::*;
+pub use selftest::*;
 
 /* =============================== Some clean wrappers for the GUI ============================== */
 pub fn analyze_directory_gui(directory: &str) -> Result<UnusedReport, Box<dyn std::error::Error>> {
@@ -25,19 +129,198 @@ pub fn analyze_directory_gui(directory: &str) -> Result<UnusedReport, Box<dyn st
     detector.generate_report()
 }
 
+/* ============================================================================================== */
+pub fn analyze_workspace_gui(directory: &str) -> Result<UnusedReport, Box<dyn std::error::Error>> {
+    // Each workspace tab uses the config that lives in its own project, not the app's cwd
+    let detector = UnusedDetector::new(directory.to_string())
+        .with_config(Config::load_for_directory(directory));
+    detector.generate_report()
+}
+
+/* ============================================================================================== */
+pub fn analyze_workspace_with_events_gui(
+    directory: &str,
+    events: crossbeam_channel::Sender<AnalysisEvent>,
+) -> Result<UnusedReport, Box<dyn std::error::Error>> {
+    // Run on a worker thread; the app forwards each event to the window as it arrives
+    let detector = UnusedDetector::new(directory.to_string())
+        .with_config(Config::load_for_directory(directory))
+        .with_observer(std::sync::Arc::new(ChannelObserver::new(events)));
+    detector.generate_report()
+}
+
+/* ============================================================================================== */
+pub fn count_files_gui(directory: &str) -> Result<PreScan, Box<dyn std::error::Error>> {
+    // Runs right after a folder is picked, so a huge tree can be narrowed down before analyzing
+    pre_scan(directory, &Config::load_for_directory(directory))
+}
+
 /* ============================================================================================== */
 pub fn find_word_gui(word: &str, directory: &str) -> Result<ScanResult, Box<dyn std::error::Error>> {
-    // Need to manually invoke walker ourselves
-    let mut scanner = FileScanner::new();
-    let mut walker = FileWalker::new(directory.to_string());
-    let threads = None;
-    
-    if let Some(thread_count) = threads {
-        scanner = scanner.with_thread_count(thread_count);
-        walker = walker.with_thread_count(thread_count)
+    let options = FindWordOptions { include_all: true, ..FindWordOptions::default() };
+    Ok(find_word_with_options_gui(word, directory, &options)?.result)
+}
+
+/* ============================================================================================== */
+pub fn find_word_with_options_gui(word: &str, directory: &str, options: &FindWordOptions) -> Result<WordSearchResult, Box<dyn std::error::Error>> {
+    // Same config, threads and exclusions as `tag-finder find-word` run inside the project
+    validate_word(word)?;
+    let config = Config::load_for_directory(directory);
+    let scanner = FileScanner::new()
+        .configure_threads(options.threads)
+        .with_config(config.clone())
+        .with_scan_filter(config.scan_filter().with_root(directory))
+        .with_match_options(options.case_sensitive, options.regex);
+
+    let scope = options.scope.iter().map(|glob| glob_to_regex(glob)).collect::<Result<Vec<_>, _>>()?;
+    let root = std::path::PathBuf::from(directory);
+    let walker = FileWalker::new(directory.to_string())
+        .configure_threads(options.threads)
+        .with_config(config)
+        .with_filter(move |path| {
+            let relative = path.strip_prefix(&root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+            scope.is_empty() || scope.iter().any(|glob| glob.is_match(&relative))
+        });
+
+    let result = scanner.scan(word.to_string(), walker.walk_with_content_parallel()?)?;
+    Ok(WordSearchResult {
+        show_matches: options.include_all || result.is_css_only,
+        result,
+    })
+}
+
+/* ============================================================================================== */
+pub fn explain_class_gui(class_name: &str, directory: &str) -> Result<ClassExplanation, Box<dyn std::error::Error>> {
+    // Same config as the analysis of that project, or the verdict could disagree with the report
+    let detector = UnusedDetector::new(directory.to_string())
+        .with_config(Config::load_for_directory(directory));
+    detector.explain_class(class_name)
+}
+
+/* ============================================================================================== */
+pub fn quickfix_for_file_gui(directory: &str, report: &UnusedReport, file: &str) -> String {
+    // Lets the GUI open a file once with every unused class location instead of one click per class
+    let style = OutputStyle::from_config(&Config::load_for_directory(directory).output);
+    report.to_quickfix(Some(file), &style)
+}
+
+/* ============================================================================================== */
+pub fn markdown_summary_gui(directory: &str, report: &UnusedReport, selected_classes: &[String]) -> String {
+    // Headings in the locale of the open project; empty selection means "copy everything"
+    let style = OutputStyle::from_config(&Config::load_for_directory(directory).output);
+    if selected_classes.is_empty() {
+        report.to_markdown(None, &style)
+    } else {
+        report.to_markdown(Some(selected_classes), &style)
     }
+}
 
-    let files_with_content = walker.walk_with_content_parallel()?;
+/* ============================================================================================== */
+pub fn editor_command_gui(directory: &str, class: &CssClass) -> Vec<String> {
+    // Split before filling in, so paths with spaces stay one argument
+    let config = Config::load_for_directory(directory);
+    config.gui.editor
+        .split_whitespace()
+        .map(|part| part.replace("{file}", &class.file).replace("{line}", &class.line.to_string()))
+        .collect()
+}
+
+/* ============================================================================================== */
+pub fn version_info_gui(directory: &str) -> VersionInfo {
+    // The config hash is for the open project, the one its reports were made with
+    VersionInfo::for_config(&Config::load_for_directory(directory))
+}
+
+/* ============================================================================================== */
+pub fn message_catalog_gui(directory: &str) -> std::collections::HashMap<String, String> {
+    // Frontend strings come from the same catalog, in the locale of the open project
+    let config = Config::load_for_directory(directory);
+    message_catalog(Locale::detect(config.output.locale.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn scratch_project(name: &str, config: Option<&str>) -> PathBuf {
+        // The space is on purpose, checkouts under "My Projects" are common on the desktop
+        let directory = std::env::temp_dir().join(format!("tag-finder gui-{}-{}", name, std::process::id()));
+        fs::create_dir_all(directory.join("src").join("components")).unwrap();
+        fs::create_dir_all(directory.join("legacy")).unwrap();
+        fs::write(directory.join("src").join("styles.css"), ".card { color: red; }\n.stale { color: blue; }\n.orphan { margin: 0; }\n").unwrap();
+        fs::write(directory.join("src").join("components").join("card.html"), "<div class=\"card\"></div>\n").unwrap();
+        fs::write(directory.join("legacy").join("old.html"), "<div class=\"card\"></div>\n").unwrap();
+        if let Some(config) = config {
+            fs::write(directory.join("tag-finder.toml"), config).unwrap();
+        }
+        directory
+    }
+
+    fn unused_class(report: &UnusedReport, name: &str) -> CssClass {
+        report.unused_classes.iter().find(|class| class.name == name).cloned().unwrap()
+    }
 
-    scanner.scan(word.to_string(), files_with_content)
-}
\ No newline at end of file
+    #[test]
+    fn editor_command_keeps_paths_with_spaces_in_one_argument() {
+        let directory = scratch_project("editor", Some("[scan]\n\n[gui]\neditor = \"subl --wait {file}:{line}\"\n"));
+        let directory_text = directory.to_string_lossy().to_string();
+        let report = analyze_workspace_gui(&directory_text).unwrap();
+        let stale = unused_class(&report, "stale");
+        let command = editor_command_gui(&directory_text, &stale);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(stale.file.contains(' '), "{}", stale.file);
+        assert_eq!(command, vec!["subl".to_string(), "--wait".to_string(), format!("{}:2", stale.file)]);
+    }
+
+    #[test]
+    fn editor_command_falls_back_to_the_default_editor() {
+        let directory = scratch_project("default-editor", None);
+        let directory_text = directory.to_string_lossy().to_string();
+        let report = analyze_workspace_gui(&directory_text).unwrap();
+        let orphan = unused_class(&report, "orphan");
+        let command = editor_command_gui(&directory_text, &orphan);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(command, vec!["code".to_string(), "--goto".to_string(), format!("{}:3", orphan.file)]);
+    }
+
+    #[test]
+    fn find_word_scope_limits_the_searched_files() {
+        let directory = scratch_project("scope", None);
+        let directory_text = directory.to_string_lossy().to_string();
+        let everywhere = find_word_with_options_gui("card", &directory_text, &FindWordOptions::default()).unwrap();
+        let scoped_options = FindWordOptions { scope: vec!["src/**/*.html".to_string()], ..FindWordOptions::default() };
+        let scoped = find_word_with_options_gui("card", &directory_text, &scoped_options).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let found = |result: &WordSearchResult| {
+            let mut files: Vec<String> = result.result.css_files.iter().chain(&result.result.other_files)
+                .map(|file| file.replace('\\', "/"))
+                .collect();
+            files.sort();
+            files
+        };
+        assert_eq!(found(&everywhere).len(), 3, "{:?}", found(&everywhere));
+        let scoped_files = found(&scoped);
+        assert_eq!(scoped_files.len(), 1, "{:?}", scoped_files);
+        assert!(scoped_files[0].ends_with("src/components/card.html"), "{:?}", scoped_files);
+    }
+
+    #[test]
+    fn markdown_summary_lists_only_the_selected_classes() {
+        let directory = scratch_project("markdown", Some("[scan]\n\n[output]\nlocale = \"en\"\n"));
+        let directory_text = directory.to_string_lossy().to_string();
+        let report = analyze_workspace_gui(&directory_text).unwrap();
+        let selected = markdown_summary_gui(&directory_text, &report, &["stale".to_string()]);
+        let everything = markdown_summary_gui(&directory_text, &report, &[]);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(selected.contains("`.stale`") && !selected.contains("`.orphan`"), "{}", selected);
+        assert!(everything.contains("`.stale`") && everything.contains("`.orphan`"), "{}", everything);
+        // The counts cover the whole report either way
+        assert!(selected.contains("- Unused classes: 2\n"), "{}", selected);
+    }
+}
@@ -1,28 +1,77 @@
-use clap::{Parser, Subcommand};
-use tag_finder::{print_header_line, FileWalker, FileScanner, UnusedDetector, print_banner, Config, traits::*};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum, ValueHint};
+use clap_complete::Shell;
+use std::path::Path;
+use std::time::Duration;
+use std::sync::Arc;
+use tag_finder::{separate_items_by_condition, append_history, batch_exit_status, request_cancel, is_cancelled, load_history, FileWalker, FileScanner, validate_word, UnusedDetector, WebhookNotifier, DashboardGenerator, HistoryEntry, BatchManifest, ExitStatus, ConfigError, ProjectReport, AggregateReport, ReportStatus, Preset, builtin_preset_names, builtin_preset_source, run_selftest, print_banner, write_decision_log, DecisionField, FileSampler, DEFAULT_SAMPLE_SEED, default_memory_budget_mb, STYLES_CATEGORY, OTHER_CATEGORY, Config, OutputConfig, ReportFilter, ReportSort, ScanConfig, OutputStyle, RemoteCheckout, FetchMethod, pager_command, run_paged, ClassIndex, AnalysisObserver, ConsoleObserver, write_output, STDOUT_PATH, traits::*};
 
 #[derive(Parser)]
 #[command(name = "tag-finder")]
-#[command(about = "Find unused classes in CSS/SCSS files")]
+#[command(version, about = "Find unused classes in CSS/SCSS files")]
+#[command(long_about = "Find unused classes in CSS/SCSS files.
+
+A class counts as unused when its name only ever appears in CSS/SCSS files: it is defined \
+in a stylesheet but no markup, script or template mentions it, either literally or through \
+a dynamic pattern such as `btn-${variant}`. Run `tag-finder help-examples` for a tour.")]
+#[command(after_help = "Exit codes:
+  0  Clean: no findings of an `error` rule
+  1  Findings of an `error` rule (unused-classes, batch)
+  2  Invalid config file, profile, environment override, command line or search word (or a batch job's)
+  3  A file, directory, archive or repository couldn't be read or written (or a batch job failed that way)
+  4  Cancelled with Ctrl-C
+  5  Stopped by --timeout; the partial report is still printed and written")]
 struct Args {
     #[command(subcommand)]
     command: Commands,
 
     /// Path to config file (defaults to auto-discovery)
-    #[arg(short, long)]
+    #[arg(short, long, value_hint = ValueHint::FilePath)]
     config: Option<String>,
+
+    /// Lay the [profile.NAME] section of the config file over the rest of it, e.g. `ci` or `local`
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Plain ASCII output without emoji or box characters
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Width of separator lines in reports
+    #[arg(long, global = true, value_name = "COLUMNS")]
+    width: Option<usize>,
+
+    /// Directory levels to descend into (overrides max_depth in [scan])
+    #[arg(long, global = true, value_name = "LEVELS")]
+    max_depth: Option<usize>,
+
+    /// Stop after this many files, 0 for no limit (overrides max_files in [scan])
+    #[arg(long, global = true, value_name = "COUNT")]
+    max_files: Option<usize>,
+
+    /// Fail when a directory or file can't be read during the walk (overrides strict_walk in [scan])
+    #[arg(long, global = true)]
+    strict_walk: bool,
+
+    /// Print long reports straight to the terminal instead of through a pager
+    #[arg(long, global = true)]
+    no_pager: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Find a specific word that appears only in CSS/SCSS files
+    ///
+    /// Searches every scanned file for the exact word and reports whether it appears ONLY in
+    /// CSS/SCSS files. A CSS-only word is usually a leftover selector that nothing references
+    /// anymore. Words with other characters, like Tailwind's `md:flex` or `w-[10px]`, only match
+    /// as a whole class token: `md:flex` isn't found in `lg:md:flex` or `md:flex-1`.
     FindWord {
         /// The word to search for (exact match)
         #[arg(short, long)]
         word: String,
         
         /// Directory to search in
-        #[arg(short, long, default_value = ".")]
+        #[arg(short, long, default_value = ".", value_hint = ValueHint::DirPath)]
         directory: String,
         
         /// Show all matches, not just CSS-only ones
@@ -32,75 +81,1076 @@ enum Commands {
         /// Number of threads to use (default: auto-detect)
         #[arg(short, long)]
         threads: Option<usize>,
+
+        /// Order of the matched files
+        #[arg(short, long, value_enum, default_value_t = WordSort::Path)]
+        sort: WordSort,
     },
     /// Analyze all CSS classes and find unused ones
-    UnusedClasses {
+    ///
+    /// Extracts every class defined in CSS/SCSS files and checks the rest of the project for
+    /// usage, first by exact word match and then through detected dynamic patterns
+    /// (template literals, string concatenation, classList calls). Classes that only appear
+    /// in stylesheets are reported as unused.
+    UnusedClasses(Box<UnusedClassesArgs>),
+    /// Explain why a single class is classified as used or unused
+    ///
+    /// Runs the same scan and classification as `unused-classes` for one class, including usage
+    /// policies and the bundle metafile, and prints the evidence behind the verdict: definition
+    /// sites, exact usage hits, ignored hits in style sections, covering dynamic patterns and the
+    /// filter rules in effect.
+    Explain {
+        /// The class to explain (with or without leading dot)
+        #[arg(long)]
+        class: String,
+
         /// Directory to analyze
-        #[arg(short, long, default_value = ".")]
+        #[arg(short, long, default_value = ".", value_hint = ValueHint::DirPath)]
         directory: String,
-        
-        /// Show detailed breakdown by file
+
+        /// Number of threads to use (default: auto-detect)
         #[arg(short, long)]
-        by_file: bool,
-        
-        /// Show full detailed report
+        threads: Option<usize>,
+
+        /// Bundler metafile (esbuild metafile or webpack stats JSON); usage only in un-bundled modules doesn't count
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        bundle_metafile: Option<String>,
+
+        /// Upper bound for file contents held in memory while usage files are streamed [default: 512, or a quarter of the free memory when that is less]
+        #[arg(long, value_name = "MB")]
+        max_memory_mb: Option<usize>,
+    },
+    /// Write every class with its definitions and usages to a JSON index
+    ///
+    /// Unlike `unused-classes`, which reports only what is unused, the index maps each class
+    /// to all of its definition sites and the usage lines mentioning it. It is meant as an
+    /// interchange format for editor plugins, documentation generators and design-system audits.
+    Index {
+        /// Directory to index
+        #[arg(short, long, default_value = ".", value_hint = ValueHint::DirPath)]
+        directory: String,
+
+        /// File to write the index to
+        #[arg(short, long, default_value = "class-index.json", value_hint = ValueHint::FilePath)]
+        out: String,
+
+        /// Number of threads to use (default: auto-detect)
+        #[arg(short, long)]
+        threads: Option<usize>,
+    },
+    /// Print where a class is defined and used
+    ///
+    /// Class-aware counterpart of `find-word`: looks the class up in the same index that
+    /// `index` writes, so `btn` matches `.btn` selectors and class attributes but not
+    /// `btn-primary` or an unrelated substring. Builds the index on the fly unless --index is given.
+    Where {
+        /// The class to look up (with or without leading dot)
         #[arg(long)]
-        detailed: bool,
+        class: String,
+
+        /// Directory to analyze
+        #[arg(short, long, default_value = ".", value_hint = ValueHint::DirPath)]
+        directory: String,
+
+        /// Read this index (written by `index`) instead of analyzing the directory
+        #[arg(long, value_hint = ValueHint::FilePath, conflicts_with = "directory")]
+        index: Option<String>,
 
         /// Number of threads to use (default: auto-detect)
         #[arg(short, long)]
         threads: Option<usize>,
     },
+    /// List the files that would be scanned with the current filters
+    ListFiles {
+        /// Directory to list
+        #[arg(short, long, default_value = ".", value_hint = ValueHint::DirPath)]
+        directory: String,
+
+        /// Group files into CSS (definitions) and usage files
+        #[arg(long)]
+        categorize: bool,
+    },
+    /// Inspect the configuration in effect
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Render a static HTML dashboard from a history file
+    Dashboard {
+        /// History file written by `unused-classes --history`
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        history: String,
+
+        /// Output directory for the generated site
+        #[arg(short, long, default_value = "site", value_hint = ValueHint::DirPath)]
+        out: String,
+    },
+    /// Run the analyses listed in a JSON manifest, one report per job
+    ///
+    /// Each job names a directory, an optional config file (and profile) and the file its JSON
+    /// report is written to. Jobs run one after another unless the manifest or --parallel says
+    /// otherwise; a failing job is reported and the others still run.
+    Batch {
+        /// Manifest with a `jobs` list of {directory, config, output} entries
+        #[arg(value_hint = ValueHint::FilePath)]
+        manifest: String,
+
+        /// Jobs to run at the same time (overrides `parallel` in the manifest)
+        #[arg(long, value_name = "JOBS")]
+        parallel: Option<usize>,
+    },
+    /// Merge the JSON reports of several projects into one cross-repository summary
+    ///
+    /// Totals per project, the stylesheets with the most unused classes over all of them, and
+    /// shared-class conflicts: classes defined in several projects that are used in some and
+    /// unused in others, so deleting them from a shared stylesheet would break a project.
+    Aggregate {
+        /// Reports written by `unused-classes --output` or `batch`, as PATH or NAME=PATH
+        #[arg(value_name = "REPORT", value_hint = ValueHint::FilePath, required_unless_present = "batch_summary")]
+        reports: Vec<String>,
+
+        /// Take the reports of every finished job from a batch summary file
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        batch_summary: Option<String>,
+
+        /// Write the aggregate: HTML for a .html path, JSON otherwise
+        #[arg(short, long, value_hint = ValueHint::FilePath)]
+        out: Option<String>,
+    },
+    /// Check this build against the bundled fixture projects
+    ///
+    /// Analyzes small HTML, React, Vue, PHP and nested SCSS projects compiled into the binary and
+    /// compares the results with their golden reports, to verify an install or a custom build.
+    /// Exits with status 1 when a fixture differs.
+    Selftest,
+    /// Print usage examples for every subcommand
+    HelpExamples {
+        /// Only show examples for this subcommand
+        command: Option<String>,
+    },
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the effective configuration: defaults, config file, profile, environment and flags merged
+    Show {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ConfigFormat::Toml)]
+        format: ConfigFormat,
+    },
+    /// List the built-in presets, or print one's definition as a starting point for overriding it
+    Presets {
+        /// Preset to print, e.g. bootstrap5
+        name: Option<String>,
+    },
+}
+
+#[derive(clap::Args)]
+struct UnusedClassesArgs {
+    /// Directory to analyze, or a .zip/.tar/.tar.gz/.tgz archive of one
+    #[arg(short, long, default_value = ".", value_hint = ValueHint::AnyPath)]
+    directory: String,
+    
+    /// Show detailed breakdown by file
+    #[arg(short, long)]
+    by_file: bool,
+    
+    /// Show full detailed report
+    #[arg(long)]
+    detailed: bool,
+
+    /// Only report findings in files matching this glob, relative to the directory (repeatable), e.g. "components/**"
+    #[arg(long, value_name = "GLOB")]
+    only: Vec<String>,
+
+    /// Only report classes starting with this prefix (repeatable), e.g. "legacy-"
+    #[arg(long, value_name = "PREFIX")]
+    class_prefix: Vec<String>,
+
+    /// Order of files and classes in --detailed, --by-file and --output: count, name, file or percentage [default: sort in [output], else file]
+    #[arg(long, value_name = "ORDER", value_parser = parse_report_sort)]
+    sort: Option<ReportSort>,
+
+    /// Declarations shown under each unused class in --detailed and --output, 0 for none [default: preview_declarations in [output], else 3]
+    #[arg(long, value_name = "N")]
+    preview_declarations: Option<usize>,
+
+    /// Print only the summary block, without banner, progress or class list
+    #[arg(long, conflicts_with_all = ["by_file", "detailed", "count"])]
+    summary: bool,
+
+    /// Print only the number of unused classes, for shell scripts
+    #[arg(long, conflicts_with_all = ["by_file", "detailed"])]
+    count: bool,
+
+    /// Number of threads to use (default: auto-detect)
+    #[arg(short, long)]
+    threads: Option<usize>,
+
+    /// Threads reading files, e.g. more than the core count on network drives (overrides --threads and io_threads in [scan])
+    #[arg(long, value_name = "COUNT")]
+    io_threads: Option<usize>,
+
+    /// Threads parsing and matching file contents (overrides --threads and cpu_threads in [scan])
+    #[arg(long, value_name = "COUNT")]
+    cpu_threads: Option<usize>,
+
+    /// Write the full report as JSON; `-` prints it to stdout instead of the text report
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    output: Option<String>,
+
+    /// Write unused classes as a quickfix list (file:line:column: message) for Vim/VS Code
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    quickfix: Option<String>,
+
+    /// Entry stylesheet; only stylesheets reachable through @import/@use are analyzed (repeatable)
+    #[arg(long = "entry", value_hint = ValueHint::FilePath)]
+    entries: Vec<String>,
+
+    /// Leave classes in this cascade layer and its sublayers out of the analysis, e.g. `utilities` (repeatable, adds to exclude_layers in [analysis])
+    #[arg(long = "exclude-layer", value_name = "LAYER")]
+    exclude_layers: Vec<String>,
+
+    /// Bundler metafile (esbuild metafile or webpack stats JSON); classes used only in un-bundled modules are flagged as dead-code usage
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    bundle_metafile: Option<String>,
+
+    /// POST a JSON summary to this URL when the [notify] thresholds are exceeded
+    #[arg(long, value_hint = ValueHint::Url)]
+    notify_webhook: Option<String>,
+
+    /// Append this run's results to a history file (JSON lines) for `dashboard`
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    history: Option<String>,
+
+    /// Write one JSON line per class with its final status, evidence and timing
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    decision_log: Option<String>,
+
+    /// Only write these comma-separated fields to the decision log: name, file, line, column, offset, status, evidence, used-only-in, elapsed-us
+    #[arg(long, value_name = "FIELDS", value_delimiter = ',', value_parser = parse_decision_field, requires = "decision_log")]
+    fields: Option<Vec<DecisionField>>,
+
+    /// Write a graph of classes applied together on one element: JSON for a .json path, Graphviz DOT otherwise
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    graph: Option<String>,
+
+    /// Only analyze this fraction (0-1] of the usage files and report an upper bound for the unused percentage
+    #[arg(long, value_name = "RATE", value_parser = parse_sample_rate)]
+    sample: Option<f64>,
+
+    /// Upper bound for file contents held in memory while usage files are streamed [default: 512, or a quarter of the free memory when that is less]
+    #[arg(long, value_name = "MB")]
+    max_memory_mb: Option<usize>,
+
+    /// Stop analyzing after this long, e.g. 10m, 90s or 1h, and report the classes without usage so far as not analyzed (exit status 5)
+    #[arg(long, value_name = "DURATION", value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+
+    /// Analyze the files listed here (one path per line, `-` for stdin) instead of walking the directory
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    files_from: Option<String>,
+
+    /// Don't count unused classes whose definition line (per git blame) is younger than this, e.g. 30d, 2w or 12h
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    min_age: Option<Duration>,
+
+    /// Only count unused classes defined on lines added since the merge base with this ref, e.g. origin/main
+    #[arg(long, value_name = "REF", conflicts_with = "repo")]
+    diff_base: Option<String>,
+
+    /// Annotate unused classes with the last git author of their definition line and group them by author
+    #[arg(long)]
+    with_authors: bool,
+
+    /// Repository URL to shallow-clone into a temporary directory and analyze instead of --directory
+    #[arg(long, value_hint = ValueHint::Url, conflicts_with_all = ["directory", "files_from"])]
+    repo: Option<String>,
+
+    /// Branch or tag to analyze with --repo (default: the default branch)
+    #[arg(long = "ref", value_name = "REF", requires = "repo")]
+    git_ref: Option<String>,
+
+    /// Seed for --sample; the same seed picks the same files
+    #[arg(long, default_value_t = DEFAULT_SAMPLE_SEED, requires = "sample")]
+    seed: u64,
+}
+
+impl UnusedClassesArgs {
+    /// Output meant for scripts gets nothing but the result on stdout
+    fn is_quiet(&self) -> bool {
+        self.summary || self.count || self.writes_to_stdout()
+    }
+
+    fn writes_to_stdout(&self) -> bool {
+        [&self.output, &self.quickfix, &self.decision_log, &self.graph].iter()
+            .any(|path| path.as_deref() == Some(STDOUT_PATH))
+    }
+}
+
+struct Example {
+    command: &'static str,
+    invocation: &'static str,
+    description: &'static str,
+}
+
+// Single source for `--help` example sections and `help-examples`
+const EXAMPLES: &[Example] = &[
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes", description: "Summary of unused classes in the current directory" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --directory ./src --by-file", description: "Per-file breakdown for one folder" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --detailed", description: "Every unused class, grouped by file" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --by-file --sort count", description: "Files with the most unused classes first" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --detailed --only \"components/**\" --class-prefix legacy-", description: "Focus the report on one area; usage elsewhere still counts" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --detailed --preview-declarations 5", description: "Show more of each unused rule to decide deletions from the report" },
+    Example { command: "unused-classes", invocation: "if [ \"$(tag-finder unused-classes --count)\" -gt 0 ]; then ...; fi", description: "Only the number of unused classes, for shell scripts" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --quickfix unused.txt", description: "Write a quickfix list and open it with `vim -q unused.txt`" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --entry styles/main.scss", description: "Only analyze stylesheets reachable from the bundle entry" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --exclude-layer utilities", description: "Ignore framework utilities kept in their own cascade layer" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --bundle-metafile dist/meta.json", description: "Flag classes only referenced from modules the bundler dropped" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --graph classes.dot && dot -Tsvg classes.dot -o classes.svg", description: "Draw which classes are used together; unused ones are dashed" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --history .tag-finder-history.jsonl", description: "Record this run for the dashboard" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --sample 0.2", description: "Quick upper bound from a fifth of the usage files before a full scan" },
+    Example { command: "unused-classes", invocation: "git ls-files | tag-finder unused-classes --files-from -", description: "Analyze exactly the files git tracks" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --directory site-export.zip", description: "Analyze a zip or tarball without extracting it" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --min-age 30d", description: "Leave classes added in the last 30 days out of the unused count" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --timeout 10m --output report.json", description: "Never let a CI job hang: stop after 10 minutes with a partial report" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --diff-base origin/main", description: "Only fail on unused classes this branch introduced" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --with-authors --by-file", description: "Group unused classes by who last touched them, for handing out cleanup" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --repo https://github.com/org/app --ref main", description: "One-off audit of a remote repository without cloning it yourself" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --decision-log decisions.ndjson", description: "Audit trail of every classification with its evidence" },
+    Example { command: "unused-classes", invocation: "tag-finder unused-classes --decision-log classes.ndjson --fields name,file,line,status", description: "Smaller export with only the columns a dashboard needs" },
+    Example { command: "dashboard", invocation: "tag-finder dashboard --history .tag-finder-history.jsonl --out site", description: "Render a static dashboard, e.g. for GitHub Pages" },
+    Example { command: "batch", invocation: "tag-finder batch jobs.json --parallel 4", description: "Audit every repository listed in a manifest, four at a time, one JSON report each" },
+    Example { command: "aggregate", invocation: "tag-finder aggregate --batch-summary reports/summary.json --out reports/index.html", description: "One cross-repo page for leadership after a batch run" },
+    Example { command: "aggregate", invocation: "tag-finder aggregate shop=shop.json blog=blog.json.gz --out aggregate.json", description: "Merge reports from separate CI runs, with conflicts between them" },
+    Example { command: "find-word", invocation: "tag-finder find-word --word navbar-brand", description: "Check whether a class name only lives in stylesheets" },
+    Example { command: "find-word", invocation: "tag-finder find-word --word container --all --sort count", description: "Show every file using a term, heaviest users first" },
+    Example { command: "explain", invocation: "tag-finder explain --class card--wide", description: "Show the evidence behind a class's verdict" },
+    Example { command: "where", invocation: "tag-finder where --class btn-primary", description: "Every definition and usage of one class, as file:line" },
+    Example { command: "where", invocation: "tag-finder where --class btn-primary --index class-index.json", description: "Look it up in a saved index instead of re-analyzing" },
+    Example { command: "index", invocation: "tag-finder index --out class-index.json", description: "Every class with its definitions and usages, for editor plugins and audits" },
+    Example { command: "config", invocation: "TAG_FINDER_SCAN__MAX_DEPTH=4 tag-finder --profile ci config show --format json", description: "See the settings a run would use, after profile, environment and flags" },
+    Example { command: "selftest", invocation: "tag-finder selftest", description: "Check a fresh install against the bundled fixture projects" },
+    Example { command: "list-files", invocation: "tag-finder list-files --categorize", description: "Check which files pass the configured filters" },
+    Example { command: "completions", invocation: "tag-finder completions zsh > ~/.zfunc/_tag-finder", description: "Install zsh completions" },
+];
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum WordSort {
+    /// Most occurrences first
+    Count,
+    /// Alphabetical by file path
+    Path,
+}
+
+// Where the configuration came from, reported once it's clear the output isn't for a script
+enum ConfigSource {
+    File(String),
+    /// Config file and profile name
+    Profile(String, String),
+    Defaults,
+}
+
+impl ConfigSource {
+    fn report(&self, style: &OutputStyle) {
+        match self {
+            ConfigSource::File(path) => println!("{}", style.message("cli.config_loaded", &[path])),
+            ConfigSource::Profile(path, profile) => println!("{}", style.message("cli.config_loaded_profile", &[path, profile])),
+            ConfigSource::Defaults => println!("{}", style.text("cli.config_defaults")),
+        }
+    }
 }
 
 fn main() {
-    let args = Args::parse();
+    let matches = build_cli().get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
-    print_banner(Some("src/banner/banner.txt"));
+    // Help output goes straight to stdout, so skip the banner and config chatter
+    match &args.command {
+        Commands::Completions { shell } => {
+            print_completions(*shell);
+            return;
+        }
+        Commands::HelpExamples { command } => {
+            let output = apply_output_overrides(OutputConfig::default(), args.ascii, args.width);
+            print_examples(command.as_deref(), &OutputStyle::from_config(&output));
+            return;
+        }
+        _ => {}
+    }
+
+    // Hand off before loading the config, so its messages aren't printed twice
+    if !args.no_pager && produces_long_output(&args.command)
+        && let Some(pager) = pager_command(configured_pager(args.config.as_deref(), args.profile.as_deref()).as_deref())
+        && let Some(code) = run_paged(&pager)
+    {
+        std::process::exit(code);
+    }
+
+    let quiet = match &args.command {
+        Commands::UnusedClasses(unused_args) => unused_args.is_quiet(),
+        Commands::Config { .. } => true,
+        _ => false,
+    };
 
     // Load configuration
-    let config = match args.config {
-        Some(config_path) => Config::from_file_or_default(&config_path),
-        None => Config::load_or_default(),
+    // Only a missing file means the defaults; one that can't be parsed stops the run. Without a
+    // config, its errors are in the language of TAG_FINDER_LANG/LANG
+    let exit_with_config_error = |e: Box<dyn std::error::Error>| -> ! {
+        eprintln!("{}", OutputStyle::default().message("cli.error", &[&e]));
+        std::process::exit(ExitStatus::for_error(e.as_ref()).code());
     };
-    
-    match args.command {
-        Commands::FindWord { word, directory, all, threads } => {
-            if let Err(e) = handle_find_word(word, directory, all, threads, config) {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            }
+    let (mut config, config_source) = load_config(args.config.as_deref(), args.profile.as_deref())
+        .unwrap_or_else(|e| exit_with_config_error(e));
+    // Environment variables go over the file, command-line flags over both
+    config = config.with_env_overrides().unwrap_or_else(|e| exit_with_config_error(e));
+    config.output = apply_output_overrides(config.output, args.ascii, args.width);
+    config.scan = apply_scan_limits(config.scan, args.max_depth, args.max_files, args.strict_walk);
+    let style = OutputStyle::from_config(&config.output);
+    if !quiet {
+        config_source.report(&style);
+    }
+
+    // The scan and report writer stop at the flag and remove their temp files; a second Ctrl-C
+    // doesn't wait for that
+    let cancelled = style.text("cli.cancelled");
+    if let Err(e) = ctrlc::set_handler(move || {
+        if request_cancel() {
+            std::process::exit(ExitStatus::Cancelled.code());
+        }
+        eprintln!("\n{}", cancelled);
+    }) {
+        eprintln!("{}  {}", style.icon("⚠️"), style.message("cli.ctrlc_unavailable", &[&e]));
+    }
+
+    if !quiet {
+        print_banner(Some("src/banner/banner.txt"), &style);
+    }
+
+    let result = match args.command {
+        Commands::FindWord { word, directory, all, threads, sort } => {
+            handle_find_word(word, directory, all, threads, sort, config, &style).map(|_| ExitStatus::Clean)
         }
-        Commands::UnusedClasses { directory, by_file, detailed, threads } => {
-            if let Err(e) = handle_unused_classes(directory, by_file, detailed, threads, config) {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+        Commands::UnusedClasses(unused_args) => {
+            handle_unused_classes(*unused_args, config, &style)
+        }
+        Commands::Explain { class, directory, threads, bundle_metafile, max_memory_mb } => {
+            handle_explain(class, directory, threads, bundle_metafile, max_memory_mb, config, &style).map(|_| ExitStatus::Clean)
+        }
+        Commands::Index { directory, out, threads } => {
+            handle_index(directory, out, threads, config, &style).map(|_| ExitStatus::Clean)
+        }
+        Commands::Where { class, directory, index, threads } => {
+            handle_where(class, directory, index, threads, config, &style).map(|_| ExitStatus::Clean)
+        }
+        Commands::ListFiles { directory, categorize } => {
+            handle_list_files(directory, categorize, config, &style).map(|_| ExitStatus::Clean)
+        }
+        Commands::Config { action: ConfigAction::Show { format } } => {
+            handle_config_show(format, &config).map(|_| ExitStatus::Clean)
+        }
+        Commands::Config { action: ConfigAction::Presets { name } } => {
+            handle_config_presets(name).map(|_| ExitStatus::Clean)
+        }
+        Commands::Dashboard { history, out } => {
+            handle_dashboard(history, out, &style).map(|_| ExitStatus::Clean)
+        }
+        Commands::Batch { manifest, parallel } => handle_batch(manifest, parallel, &style),
+        Commands::Aggregate { reports, batch_summary, out } => {
+            handle_aggregate(reports, batch_summary, out, &style).map(|_| ExitStatus::Clean)
+        }
+        Commands::Selftest => handle_selftest(&style),
+        Commands::Completions { .. } | Commands::HelpExamples { .. } => unreachable!("handled before config loading"),
+    };
+
+    match result {
+        Ok(_) if is_cancelled() => std::process::exit(ExitStatus::Cancelled.code()),
+        Ok(status) => std::process::exit(status.code()),
+        Err(e) => {
+            let status = ExitStatus::for_error(e.as_ref());
+            // The handler already said so
+            if status != ExitStatus::Cancelled {
+                eprintln!("{}", style.message("cli.error", &[&e]));
             }
+            std::process::exit(status.code());
         }
     }
 }
 
 /* ============================================================================================== */
-fn handle_unused_classes(
-    directory: String, 
-    by_file: bool, 
-    detailed: bool, 
+fn produces_long_output(command: &Commands) -> bool {
+    // Summaries fit on a screen; full listings easily run to thousands of lines
+    match command {
+        Commands::UnusedClasses(unused_args) => (unused_args.detailed || unused_args.by_file) && !unused_args.writes_to_stdout(),
+        Commands::ListFiles { .. } => true,
+        _ => false,
+    }
+}
+
+/* ============================================================================================== */
+fn configured_pager(config_path: Option<&str>, profile: Option<&str>) -> Option<String> {
+    // Read quietly; the run behind the pager loads the config again and reports on it
+    let config = load_config_quietly(config_path, profile).unwrap_or_default();
+    config.clone().with_env_overrides().unwrap_or(config).output.pager
+}
+
+/* ============================================================================================== */
+fn load_config_quietly(config_path: Option<&str>, profile: Option<&str>) -> Result<Config, Box<dyn std::error::Error>> {
+    load_config(config_path, profile).map(|(config, _)| config)
+}
+
+/* ============================================================================================== */
+fn load_config(config_path: Option<&str>, profile: Option<&str>) -> Result<(Config, ConfigSource), Box<dyn std::error::Error>> {
+    let config_path = config_path.map(str::to_string).or_else(Config::find_config_file);
+    if let Some(profile) = profile {
+        return load_profile(config_path, profile);
+    }
+    match config_path.filter(|config_path| Path::new(config_path).exists()) {
+        Some(config_path) => Ok((Config::from_file_or_default(&config_path)?, ConfigSource::File(config_path))),
+        None => Ok((Config::default(), ConfigSource::Defaults)),
+    }
+}
+
+/* ============================================================================================== */
+fn load_profile(config_path: Option<String>, profile: &str) -> Result<(Config, ConfigSource), Box<dyn std::error::Error>> {
+    // Unlike a missing config file, a profile that can't be applied is an error: CI would
+    // otherwise run with settings nobody asked for
+    let config_path = config_path
+        .ok_or_else(|| ConfigError::new(format!("--profile {} needs a config file, none was found", profile)))?;
+    let config = Config::from_file_with_profile(&config_path, Some(profile))?;
+    Ok((config, ConfigSource::Profile(config_path, profile.to_string())))
+}
+
+/* ============================================================================================== */
+fn apply_output_overrides(mut output: OutputConfig, ascii: bool, width: Option<usize>) -> OutputConfig {
+    // Command-line flags win over the [output] config section
+    if ascii {
+        output.ascii = true;
+    }
+    if let Some(width) = width {
+        output.width = width;
+    }
+    output
+}
+
+/* ============================================================================================== */
+fn apply_scan_limits(mut scan: ScanConfig, max_depth: Option<usize>, max_files: Option<usize>, strict_walk: bool) -> ScanConfig {
+    if max_depth.is_some() {
+        scan.max_depth = max_depth;
+    }
+    if let Some(max_files) = max_files {
+        scan.max_files = max_files;
+    }
+    if strict_walk {
+        scan.strict_walk = true;
+    }
+    scan
+}
+
+/* ============================================================================================== */
+fn read_file_list(source: &str) -> Result<Vec<std::path::PathBuf>, Box<dyn std::error::Error>> {
+    let content = if source == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(source).map_err(|e| format!("Cannot read file list '{}': {}", source, e))?
+    };
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(std::path::PathBuf::from)
+        .collect())
+}
+
+/* ============================================================================================== */
+fn parse_sample_rate(value: &str) -> Result<f64, String> {
+    let rate: f64 = value.parse().map_err(|_| format!("'{}' is not a number", value))?;
+    if rate > 0.0 && rate <= 1.0 {
+        Ok(rate)
+    } else {
+        Err(format!("sample rate must be in (0, 1], got {}", rate))
+    }
+}
+
+/* ============================================================================================== */
+fn parse_report_sort(value: &str) -> Result<ReportSort, String> {
+    ReportSort::from_name(value).ok_or_else(|| format!("unknown order '{}' (expected {})", value, ReportSort::NAMES.join(", ")))
+}
+
+/* ============================================================================================== */
+fn parse_age(value: &str) -> Result<Duration, String> {
+    // A bare number means days, the usual unit for "how long has this been around"
+    let (number, unit_seconds) = match value.char_indices().last() {
+        Some((index, 'h')) => (&value[..index], 60 * 60),
+        Some((index, 'd')) => (&value[..index], 24 * 60 * 60),
+        Some((index, 'w')) => (&value[..index], 7 * 24 * 60 * 60),
+        _ => (value, 24 * 60 * 60),
+    };
+    let count: u64 = number.parse().map_err(|_| format!("'{}' is not an age like 30d, 2w or 12h", value))?;
+    let seconds = count.checked_mul(unit_seconds).ok_or_else(|| format!("'{}' is too long for an age", value))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+/* ============================================================================================== */
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    // A bare number means seconds
+    let (number, unit_seconds) = match value.char_indices().last() {
+        Some((index, 's')) => (&value[..index], 1),
+        Some((index, 'm')) => (&value[..index], 60),
+        Some((index, 'h')) => (&value[..index], 60 * 60),
+        _ => (value, 1),
+    };
+    let count: u64 = number.parse().map_err(|_| format!("'{}' is not a duration like 10m, 90s or 1h", value))?;
+    if count == 0 {
+        return Err("the timeout must be longer than 0".to_string());
+    }
+    let seconds = count.checked_mul(unit_seconds).ok_or_else(|| format!("'{}' is too long for a timeout", value))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+/* ============================================================================================== */
+fn parse_decision_field(value: &str) -> Result<DecisionField, String> {
+    DecisionField::from_name(value).ok_or_else(|| {
+        format!("unknown field '{}' (expected name, file, line, status, evidence, used-only-in or elapsed-us)", value)
+    })
+}
+
+/* ============================================================================================== */
+fn build_cli() -> clap::Command {
+    let mut command = Args::command();
+    let subcommands: Vec<String> = command.get_subcommands().map(|sub| sub.get_name().to_string()).collect();
+
+    for name in subcommands {
+        if let Some(examples) = format_examples(&name) {
+            command = command.mut_subcommand(&name, |sub| sub.after_long_help(examples));
+        }
+    }
+
+    command
+}
+
+/* ============================================================================================== */
+fn format_examples(command: &str) -> Option<String> {
+    let examples: Vec<&Example> = EXAMPLES.iter().filter(|example| example.command == command).collect();
+    if examples.is_empty() {
+        return None;
+    }
+
+    let mut text = String::from("Examples:\n");
+    for example in examples {
+        text.push_str(&format!("  # {}\n  {}\n\n", example.description, example.invocation));
+    }
+    Some(text.trim_end().to_string())
+}
+
+/* ============================================================================================== */
+fn print_examples(command: Option<&str>, style: &OutputStyle) {
+    let mut commands: Vec<&str> = Vec::new();
+    for example in EXAMPLES {
+        if !commands.contains(&example.command) && command.is_none_or(|c| c == example.command) {
+            commands.push(example.command);
+        }
+    }
+
+    if commands.is_empty() {
+        println!("{}", style.message("cli.no_examples", &[&command.unwrap_or_default()]));
+        return;
+    }
+
+    for name in commands {
+        println!("{}", name);
+        style.print_header_line();
+        if let Some(examples) = format_examples(name) {
+            println!("{}\n", examples.trim_start_matches("Examples:\n"));
+        }
+    }
+}
+
+/* ============================================================================================== */
+fn print_completions(shell: Shell) {
+    let mut command = build_cli();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+}
+
+/* ============================================================================================== */
+fn handle_explain(
+    class: String,
+    directory: String,
     threads: Option<usize>,
-    config: Config
+    bundle_metafile: Option<String>,
+    max_memory_mb: Option<usize>,
+    config: Config,
+    style: &OutputStyle,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let detector = UnusedDetector::new(directory)
         .configure_threads(threads)
-        .with_config(config);
+        .with_config(config)
+        .with_bundle_metafile(bundle_metafile)
+        .with_memory_budget_mb(max_memory_mb.unwrap_or_else(default_memory_budget_mb))
+        .with_observer(console_observer(style));
+
+    let explanation = detector.explain_class(&class)?;
+    explanation.print(style);
+
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn handle_index(
+    directory: String,
+    out: String,
+    threads: Option<usize>,
+    config: Config,
+    style: &OutputStyle,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let index = UnusedDetector::new(directory)
+        .configure_threads(threads)
+        .with_config(config)
+        .with_observer(console_observer(style))
+        .build_index()?;
+
+    index.write(&out)?;
+    println!("\n{} {}", style.icon("📝"), style.message("cli.index_written", &[&index.classes.len(), &index.usage_count(), &out]));
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn handle_where(
+    class: String,
+    directory: String,
+    index_path: Option<String>,
+    threads: Option<usize>,
+    config: Config,
+    style: &OutputStyle,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let index = match index_path {
+        Some(index_path) => ClassIndex::load(&index_path)?,
+        None => UnusedDetector::new(directory)
+            .configure_threads(threads)
+            .with_config(config)
+            .with_observer(console_observer(style))
+            .build_index()?,
+    };
+
+    index.print_class(&class, style);
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn handle_config_show(format: ConfigFormat, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    // Profiles are already applied and left out; what's printed is exactly what a run would use
+    let output = match format {
+        ConfigFormat::Toml => toml::to_string_pretty(config)?,
+        ConfigFormat::Json => serde_json::to_string_pretty(config)?,
+    };
+    println!("{}", output.trim_end());
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn handle_config_presets(name: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(name) = name else {
+        for name in builtin_preset_names() {
+            println!("{:<12} {}", name, Preset::load(name, &[] as &[&str])?.description);
+        }
+        return Ok(());
+    };
+
+    let source = builtin_preset_source(&name).ok_or_else(|| ConfigError::new(format!(
+        "Unknown preset '{}' (available: {})", name, builtin_preset_names().join(", ")
+    )))?;
+    print!("{}", source);
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn handle_dashboard(history: String, out: String, style: &OutputStyle) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = load_history(&history)?;
+    let runs = entries.len();
+    let pages = DashboardGenerator::new(entries).with_number_format(style.numbers).write(&out)?;
+    println!("{} {}", style.icon("📊"), style.message("cli.dashboard_written", &[&runs, &out, &pages]));
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn handle_batch(manifest_path: String, parallel: Option<usize>, style: &OutputStyle) -> Result<ExitStatus, Box<dyn std::error::Error>> {
+    let mut manifest = BatchManifest::from_file(&manifest_path)?;
+    if let Some(parallel) = parallel {
+        if parallel == 0 {
+            return Err(ConfigError::new("--parallel must be at least 1").into());
+        }
+        manifest.parallel = parallel;
+    }
+
+    println!("{} {}", style.icon("📦"), style.message("cli.batch_started", &[&manifest.jobs.len(), &manifest_path, &manifest.parallel]));
+    let results = manifest.run(|result| match &result.error {
+        Some(error) => eprintln!("   {} {}", style.icon("❌"), style.message("cli.batch_job_failed", &[&result.name, error])),
+        None => println!("   {} {}", style.icon(if result.passed { "✅" } else { "⚠️" }), style.message("cli.batch_job_done", &[
+            &result.name, &style.count(result.unused_classes), &style.count(result.total_classes), &result.output,
+        ])),
+    })?;
+
+    let succeeded = results.iter().filter(|result| result.error.is_none()).count();
+    println!("\n{} {}", style.icon("📊"), style.message("cli.batch_finished", &[&succeeded, &results.len()]));
+    if let Some(summary) = &manifest.summary {
+        println!("{} {}", style.icon("📝"), style.message("cli.batch_summary_written", &[summary]));
+    }
+    Ok(batch_exit_status(&results))
+}
+
+/* ============================================================================================== */
+fn handle_selftest(style: &OutputStyle) -> Result<ExitStatus, Box<dyn std::error::Error>> {
+    let outcomes = run_selftest()?;
+    for outcome in &outcomes {
+        if outcome.passed() {
+            println!("   {} {}", style.icon("✅"), style.message("cli.selftest_fixture_passed", &[&outcome.name]));
+            continue;
+        }
+
+        let (missing, unexpected) = outcome.differences();
+        println!("   {} {}", style.icon("❌"), style.message("cli.selftest_fixture_failed", &[&outcome.name]));
+        for line in missing {
+            println!("      - {}", line);
+        }
+        for line in unexpected {
+            println!("      + {}", line);
+        }
+    }
+
+    let passed = outcomes.iter().filter(|outcome| outcome.passed()).count();
+    println!("\n{} {}", style.icon("📊"), style.message("cli.selftest_finished", &[&passed, &outcomes.len()]));
+    Ok(ExitStatus::from_passed(passed == outcomes.len()))
+}
+
+/* ============================================================================================== */
+fn handle_aggregate(reports: Vec<String>, batch_summary: Option<String>, out: Option<String>, style: &OutputStyle) -> Result<(), Box<dyn std::error::Error>> {
+    let mut projects = batch_summary.as_deref().map(ProjectReport::load_batch_summary).transpose()?.unwrap_or_default();
+    for report in reports {
+        projects.push(match report.split_once('=') {
+            Some((name, path)) => ProjectReport::load(path, Some(name.to_string()))?,
+            None => ProjectReport::load(&report, None)?,
+        });
+    }
+    let aggregate = AggregateReport::from_projects(&projects);
+
+    println!("\n{} {}", style.icon("📋"), style.text("aggregate.title"));
+    style.print_header_line();
+    println!("{}", style.message("aggregate.totals", &[
+        &style.count(aggregate.unused_classes), &style.count(aggregate.total_classes),
+        &style.percentage(aggregate.unused_percentage()), &aggregate.projects.len(),
+    ]));
+    for project in &aggregate.projects {
+        println!("   {} {}", style.icon("📁"), style.message("aggregate.project", &[
+            &project.name, &style.count(project.unused_classes), &style.count(project.total_classes),
+        ]));
+    }
+    if !aggregate.worst_offenders.is_empty() {
+        println!("\n{} {}", style.icon("🎯"), style.text("aggregate.worst_offenders"));
+        for offender in &aggregate.worst_offenders {
+            println!("   {} [{}] {}: {}", style.icon("•"), offender.project, offender.file, style.count(offender.unused_classes));
+        }
+    }
+    println!("\n{} {}", style.icon("⚠️"), style.message("aggregate.conflicts", &[&aggregate.shared_class_conflicts.len()]));
+    for conflict in &aggregate.shared_class_conflicts {
+        println!("   {} {}", style.icon("•"), style.message("aggregate.conflict", &[
+            &conflict.class, &conflict.used_in.join(", "), &conflict.unused_in.join(", "),
+        ]));
+    }
+
+    if let Some(out) = out {
+        aggregate.write(&out, style.numbers)?;
+        println!("\n{} {}", style.icon("📝"), style.message("cli.report_written", &[&out]));
+    }
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn handle_list_files(
+    directory: String,
+    categorize: bool,
+    config: Config,
+    style: &OutputStyle,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let walker = FileWalker::new(directory)
+        .with_config(config.clone())
+        .with_observer(console_observer(style));
+
+    let mut files = walker.walk()?;
+    files.sort();
+
+    if !categorize {
+        for file in &files {
+            println!("{}", file.display());
+        }
+        println!("\n{}", style.message("cli.files_scanned", &[&files.len()]));
+        return Ok(());
+    }
+
+    let scan_filter = config.scan_filter();
+    let (css_files, usage_files) = separate_items_by_condition(files, |file| scan_filter.is_css_file(file));
+
+    println!("{}", style.message("cli.css_files", &[&css_files.len()]));
+    for file in &css_files {
+        println!("  {} {}", style.icon("✓"), file.display());
+    }
+
+    println!("\n{}", style.message("cli.usage_files", &[&usage_files.len()]));
+    for file in &usage_files {
+        println!("  {} {}", style.icon("•"), file.display());
+    }
+
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn handle_unused_classes(
+    args: UnusedClassesArgs,
+    mut config: Config,
+    style: &OutputStyle,
+) -> Result<ExitStatus, Box<dyn std::error::Error>> {
+    config.analysis.exclude_layers.extend(args.exclude_layers.iter().cloned());
+    if let Some(sort) = args.sort {
+        config.output.sort = sort;
+    }
+    if let Some(count) = args.preview_declarations {
+        config.output.preview_declarations = count;
+    }
+    let notify_config = config.notify.clone();
+    let rules = config.rules.clone();
+    let file_list = args.files_from.as_deref().map(read_file_list).transpose()?;
+    // Kept alive until the end of the run; dropping it removes the temporary checkout
+    let quiet = args.is_quiet();
+    let to_stdout = args.writes_to_stdout();
+    let checkout = args.repo.as_deref()
+        .map(|url| fetch_repository(url, args.git_ref.as_deref(), quiet, style))
+        .transpose()?;
+    let directory = checkout.as_ref()
+        .map_or_else(|| args.directory.clone(), |checkout| checkout.path().to_string_lossy().to_string());
+    let detector = UnusedDetector::new(directory.clone())
+        .configure_threads(args.cpu_threads.or(args.threads))
+        .with_io_threads(args.io_threads)
+        .with_config(config)
+        .with_entry_points(args.entries)
+        .with_bundle_metafile(args.bundle_metafile)
+        .with_sample(args.sample.map(|rate| FileSampler::new(rate, args.seed)))
+        .with_memory_budget_mb(args.max_memory_mb.unwrap_or_else(default_memory_budget_mb))
+        .with_timeout(args.timeout)
+        .with_file_list(file_list)
+        .with_min_age(args.min_age)
+        .with_diff_base(args.diff_base)
+        .with_authors(args.with_authors)
+        .with_co_usage_graph(args.graph.is_some())
+        .with_report_filter(ReportFilter { only: args.only, class_prefixes: args.class_prefix })
+        .with_progress(!quiet)
+        .with_observer(console_observer(style));
     
     let report = detector.generate_report()?;
     
-    match (detailed, by_file) {
-        (true, _) => report.print_detailed(),
-        (false, true) => report.print_by_file(),
-        (false, false) => print_summary_with_preview(&report),
+    match (args.detailed, args.by_file) {
+        _ if to_stdout => {}
+        _ if args.count => println!("{}", report.unused_classes.len()),
+        _ if args.summary => report.print_summary(style),
+        (true, _) => report.print_detailed(style),
+        (false, true) => report.print_by_file(style),
+        (false, false) => print_summary_with_preview(&report, style),
+    }
+    if !quiet {
+        report.print_rule_results(&rules, style);
+    }
+
+    if let Some(output_path) = &args.output {
+        write_output(output_path, serde_json::to_string_pretty(&report)?.as_bytes())
+            .map_err(|e| format!("Cannot write report '{}': {}", output_path, e))?;
+        if !quiet {
+            println!("\n{} {}", style.icon("📝"), style.message("cli.report_written", &[output_path]));
+        }
+    }
+
+    if let Some(quickfix_path) = args.quickfix {
+        write_output(&quickfix_path, report.to_quickfix(None, style).as_bytes())
+            .map_err(|e| format!("Cannot write quickfix list '{}': {}", quickfix_path, e))?;
+        if !quiet {
+            println!("\n{} {}", style.icon("📝"), style.message("cli.quickfix_written", &[&quickfix_path]));
+        }
+    }
+
+    if let Some(decision_log_path) = args.decision_log {
+        write_decision_log(&decision_log_path, &report.decisions, args.fields.as_deref())?;
+        if !quiet {
+            println!("\n{} {}", style.icon("📝"), style.message("cli.decision_log_written", &[&report.decisions.len(), &decision_log_path]));
+        }
+    }
+
+    if let Some(graph_path) = args.graph
+        && let Some(graph) = &report.co_usage_graph {
+        graph.write(&graph_path)?;
+        if !quiet {
+            println!("\n{} {}", style.icon("📝"), style.message("cli.graph_written", &[&graph.nodes.len(), &graph.edges.len(), &graph_path]));
+        }
+    }
+
+    // A partial run would show up as a drop in unused classes, a sampled one as a jump
+    let partial = report.status == ReportStatus::TimedOut;
+    let incomplete = partial || report.sample.is_some();
+    if incomplete && (args.history.is_some() || args.notify_webhook.is_some()) {
+        eprintln!("\n{}  {}", style.icon("⚠️"), style.text("cli.partial_not_recorded"));
+    }
+
+    if let Some(history_path) = args.history.filter(|_| !incomplete) {
+        // The temporary checkout path means nothing in later runs, the URL does
+        let source = args.repo.as_deref().unwrap_or(&directory);
+        append_history(&history_path, &HistoryEntry::from_report(&report, source))?;
+        if !quiet {
+            println!("\n{} {}", style.icon("🕒"), style.message("cli.history_recorded", &[&history_path]));
+        }
+    }
+
+    if let Some(webhook_url) = args.notify_webhook.filter(|_| !incomplete) {
+        let notifier = WebhookNotifier::new(webhook_url, notify_config).with_output_style(style.clone());
+        if !notifier.has_thresholds() {
+            eprintln!("\n{}  {}", style.icon("⚠️"), style.text("cli.notify_no_thresholds"));
+        } else if notifier.notify_if_needed(&report)? && !quiet {
+            println!("\n{} {}", style.icon("📣"), style.text("cli.notified"));
+        }
     }
     
-    Ok(())
+    if partial {
+        return Ok(ExitStatus::TimedOut);
+    }
+    // Only error-level rules fail the run, so teams can adopt new checks as warnings first
+    Ok(ExitStatus::from_passed(report.failed_rules(&rules).is_empty()))
+}
+
+/* ============================================================================================== */
+fn fetch_repository(url: &str, git_ref: Option<&str>, quiet: bool, style: &OutputStyle) -> Result<RemoteCheckout, Box<dyn std::error::Error>> {
+    let git_ref_name = git_ref.unwrap_or("HEAD");
+    if !quiet {
+        println!("{} {}", style.icon("📥"), style.message("cli.repo_fetching", &[&url, &git_ref_name]));
+    }
+
+    let checkout = RemoteCheckout::fetch(url, git_ref)?;
+    let key = match checkout.method() {
+        FetchMethod::ShallowClone => "cli.repo_cloned",
+        FetchMethod::Tarball => "cli.repo_downloaded",
+    };
+    if !quiet {
+        println!("{} {}", style.icon("📥"), style.message(key, &[&checkout.path().display()]));
+    }
+    Ok(checkout)
 }
 
 /* ============================================================================================== */
@@ -109,31 +1159,42 @@ fn handle_find_word(
     directory: String, 
     all: bool, 
     threads: Option<usize>,
+    sort: WordSort,
     config: Config,
+    style: &OutputStyle,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    // Before the walk, so a blank word fails at once instead of after reading the whole tree
+    validate_word(&word)?;
     let scanner = FileScanner::new()
         .configure_threads(threads)
-        .with_config(config.clone());
+        .with_config(config.clone())
+        .with_scan_filter(config.scan_filter().with_root(&directory));
 
     let walker = FileWalker::new(directory.clone())
         .configure_threads(threads)
-        .with_config(config);
+        .with_config(config)
+        .with_observer(console_observer(style));
 
     let files_with_content = walker.walk_with_content_parallel()?;
 
     let result = scanner.scan(word.clone(), files_with_content)?;
     
     if should_show_results(&result, all) {
-        print_word_search_results(&word, &result);
+        print_word_search_results(&word, &result, sort, style);
     } else if has_non_css_matches(&result) {
-        println!("Word '{}' found but not CSS-only. Use --all to see details.", word);
+        println!("{}", style.message("word.not_css_only", &[&word]));
     } else {
-        println!("Word '{}' not found in any files.", word);
+        println!("{}", style.message("word.not_found", &[&word]));
     }
     
     Ok(())
 }
 
+/* ============================================================================================== */
+fn console_observer(style: &OutputStyle) -> Arc<dyn AnalysisObserver> {
+    Arc::new(ConsoleObserver::new(style.clone()))
+}
+
 /* ============================================================================================== */
 fn should_show_results(result: &tag_finder::ScanResult, all: bool) -> bool {
     all || result.is_css_only
@@ -145,55 +1206,84 @@ fn has_non_css_matches(result: &tag_finder::ScanResult) -> bool {
 }
 
 /* ============================================================================================== */
-fn print_word_search_results(word: &str, result: &tag_finder::ScanResult) {
-    println!("Search results for word: '{}'", word);
-    print_header_line(50);
+fn print_word_search_results(word: &str, result: &tag_finder::ScanResult, sort: WordSort, style: &OutputStyle) {
+    println!("{}", style.message("word.results", &[&word]));
+    style.print_header_line();
     
     if !result.css_files.is_empty() {
-        println!("Found in CSS/SCSS files:");
-        for file in &result.css_files {
-            println!("  ✓ {}", file);
+        println!("{}", style.text("word.found_css"));
+        for file in sort_matched_files(&result.css_files, result, sort) {
+            println!("  {} {} ({})", style.icon("✓"), file, format_occurrences(result, file, style));
         }
     }
     
-    if !result.other_files.is_empty() {
-        println!("Found in other files:");
-        for file in &result.other_files {
-            println!("  • {}", file);
+    // Non-CSS matches are grouped by their configured category
+    for (category, files) in result.categories.iter().filter(|(category, _)| category.as_str() != STYLES_CATEGORY) {
+        if category == OTHER_CATEGORY {
+            println!("{}", style.text("word.found_other"));
+        } else {
+            println!("{}", style.message("word.found_in_category", &[category]));
+        }
+        for file in sort_matched_files(files, result, sort) {
+            println!("  {} {} ({})", style.icon("•"), file, format_occurrences(result, file, style));
         }
     }
     
-    print_word_search_conclusion(word, result);
+    print_word_search_conclusion(word, result, style);
+}
+
+/* ============================================================================================== */
+fn sort_matched_files<'a>(files: &'a [String], result: &tag_finder::ScanResult, sort: WordSort) -> Vec<&'a String> {
+    let mut sorted: Vec<&String> = files.iter().collect();
+    match sort {
+        WordSort::Path => sorted.sort(),
+        WordSort::Count => sorted.sort_by(|a, b| {
+            let count_a = result.occurrences.get(*a).copied().unwrap_or(0);
+            let count_b = result.occurrences.get(*b).copied().unwrap_or(0);
+            count_b.cmp(&count_a).then_with(|| a.cmp(b))
+        }),
+    }
+    sorted
+}
+
+/* ============================================================================================== */
+fn format_occurrences(result: &tag_finder::ScanResult, file: &str, style: &OutputStyle) -> String {
+    match result.occurrences.get(file).copied().unwrap_or(0) {
+        1 => style.text("word.occurrence").to_string(),
+        count => style.message("word.occurrences", &[&count]),
+    }
 }
 
 /* ============================================================================================== */
-fn print_word_search_conclusion(word: &str, result: &tag_finder::ScanResult) {
+fn print_word_search_conclusion(word: &str, result: &tag_finder::ScanResult, style: &OutputStyle) {
     if result.is_css_only {
-        println!("\n🎯 SUCCESS: '{}' appears ONLY in CSS/SCSS files!", word);
-        println!("This code might be extraneous and safe to remove.");
+        println!("\n{} {}", style.icon("🎯"), style.message("word.css_only", &[&word]));
+        println!("{}", style.text("word.safe_to_remove"));
     } else if result.css_files.is_empty() && result.other_files.is_empty() {
-        println!("\n❌ Word '{}' not found in any files.", word);
+        println!("\n{} {}", style.icon("❌"), style.message("word.not_found", &[&word]));
+    } else if !result.used_only_in.is_empty() {
+        println!("\n{}  {}", style.icon("⚠️"), style.message("word.used_only_in", &[&word, &result.used_only_in.join(", ")]));
     } else {
-        println!("\n⚠️  Word '{}' appears in non-CSS files too.", word);
+        println!("\n{}  {}", style.icon("⚠️"), style.message("word.also_elsewhere", &[&word]));
     }
 }
 
 
 /* ============================================================================================== */
-fn print_summary_with_preview(report: &tag_finder::UnusedReport) {
-    report.print_summary();
+fn print_summary_with_preview(report: &tag_finder::UnusedReport, style: &OutputStyle) {
+    report.print_summary(style);
     
     if report.unused_classes.is_empty() {
         return;
     }
     
-    println!("\n🗑️  UNUSED CLASSES (first 10):");
+    println!("\n{}  {}", style.icon("🗑️"), style.text("report.preview_heading"));
     for class in report.unused_classes.iter().take(10) {
-        println!("  .{} in {} (line {})", class.name, class.file, class.line);
+        println!("  {}{}", style.message("report.class_in_file", &[&class.name, &class.file, &class.line]), report.author_note(class, style));
     }
     
     if report.unused_classes.len() > 10 {
-        println!("  ... and {} more", report.unused_classes.len() - 10);
-        println!("\nUse --detailed for full list or --by-file for file breakdown");
+        println!("  {}", style.message("report.preview_more", &[&(report.unused_classes.len() - 10)]));
+        println!("\n{}", style.text("report.preview_hint"));
     }
 }
@@ -0,0 +1,48 @@
+use crate::css_rules::{selector_classes, CssRule};
+use crate::duplicate_blocks::BlockLocation;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Every style rule under one `@media` condition, across all stylesheets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaQueryUsage {
+    /// The condition as written, e.g. `@media (max-width: 600px)`; nested conditions are joined with ` and `
+    pub condition: String,
+    pub rules: Vec<BlockLocation>,
+    /// Every rule only targets unused classes, so the whole block can go
+    pub unused: bool,
+}
+
+/* ============================================================================================== */
+/*                  Rules grouped by @media condition, and which groups are dead                  */
+/* ============================================================================================== */
+pub fn media_query_usage(rules: &[CssRule], unused_classes: &HashSet<&str>) -> Vec<MediaQueryUsage> {
+    let mut conditions: BTreeMap<String, (Vec<BlockLocation>, bool)> = BTreeMap::new();
+
+    for rule in rules {
+        let media: Vec<&str> = rule.at_rules.iter()
+            .map(String::as_str)
+            .filter(|at_rule| at_rule.to_lowercase().starts_with("@media"))
+            .collect();
+        if media.is_empty() {
+            continue;
+        }
+
+        let (locations, unused) = conditions.entry(media.join(" and ")).or_insert((Vec::new(), true));
+        locations.push(BlockLocation { selector: rule.selector.clone(), file: rule.file.clone(), line: rule.line });
+        *unused &= is_dead_rule(rule, unused_classes);
+    }
+
+    conditions.into_iter()
+        .map(|(condition, (rules, unused))| MediaQueryUsage { condition, rules, unused })
+        .collect()
+}
+
+/* ============================================================================================== */
+pub(crate) fn is_dead_rule(rule: &CssRule, unused_classes: &HashSet<&str>) -> bool {
+    // A selector can't match once any of its classes is never applied; rules without classes
+    // (element or id selectors) may still match and keep the block alive
+    rule.selectors.iter().all(|selector| {
+        selector_classes(selector).iter().any(|class| unused_classes.contains(class.as_ref()))
+    })
+}
@@ -0,0 +1,118 @@
+use crate::config::NotifyConfig;
+use crate::output_file::write_output;
+use crate::output_style::OutputStyle;
+use crate::unused_detector::UnusedReport;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+pub struct WebhookNotifier {
+    url: String,
+    config: NotifyConfig,
+    /// Language of the attached Markdown summary
+    style: OutputStyle,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationPayload {
+    /// Slack and most chat webhooks render this field as the message
+    pub text: String,
+    pub total_classes: usize,
+    pub unused_classes: usize,
+    pub previous_unused_classes: Option<usize>,
+    pub delta: Option<i64>,
+    pub breaches: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub markdown: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NotifyState {
+    unused_classes: usize,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String, config: NotifyConfig) -> Self {
+        Self { url, config, style: OutputStyle::default() }
+    }
+
+    /* ========================================================================================== */
+    pub fn with_output_style(mut self, style: OutputStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /* ========================================================================================== */
+    pub fn notify_if_needed(&self, report: &UnusedReport) -> Result<bool, Box<dyn std::error::Error>> {
+        let previous = self.read_previous_count();
+        let current = report.unused_classes.len();
+        let breaches = self.find_breaches(current, previous);
+
+        if breaches.is_empty() {
+            // Nothing to tell, so the next delta is relative to this run
+            self.write_current_count(current)?;
+            return Ok(false);
+        }
+
+        let payload = self.build_payload(report, previous, breaches);
+        ureq::post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&serde_json::to_string(&payload)?)
+            .map_err(|e| format!("Webhook POST to {} failed: {}", self.url, e))?;
+
+        // Only once someone was told, or a failed POST would hide the growth from the next run
+        self.write_current_count(current)?;
+        Ok(true)
+    }
+
+    /* ========================================================================================== */
+    pub fn has_thresholds(&self) -> bool {
+        self.config.max_unused.is_some() || self.config.max_increase.is_some()
+    }
+
+    /* ========================================================================================== */
+    fn find_breaches(&self, current: usize, previous: Option<usize>) -> Vec<String> {
+        let mut breaches = Vec::new();
+
+        if let Some(max_unused) = self.config.max_unused
+            && current > max_unused {
+            breaches.push(format!("{} unused classes exceeds the limit of {}", current, max_unused));
+        }
+
+        if let (Some(max_increase), Some(previous)) = (self.config.max_increase, previous)
+            && current > previous + max_increase {
+            breaches.push(format!("unused classes grew by {} (limit {})", current - previous, max_increase));
+        }
+
+        breaches
+    }
+
+    /* ========================================================================================== */
+    fn build_payload(&self, report: &UnusedReport, previous: Option<usize>, breaches: Vec<String>) -> NotificationPayload {
+        let current = report.unused_classes.len();
+        let text = format!("tag-finder: CSS debt threshold breached - {}", breaches.join("; "));
+
+        NotificationPayload {
+            text,
+            total_classes: report.total_classes,
+            unused_classes: current,
+            previous_unused_classes: previous,
+            delta: previous.map(|previous| current as i64 - previous as i64),
+            breaches,
+            markdown: self.config.include_markdown.then(|| report.to_markdown(None, &self.style)),
+        }
+    }
+
+    /* ========================================================================================== */
+    fn read_previous_count(&self) -> Option<usize> {
+        let content = fs::read_to_string(&self.config.state_file).ok()?;
+        let state: NotifyState = serde_json::from_str(&content).ok()?;
+        Some(state.unused_classes)
+    }
+
+    /* ========================================================================================== */
+    fn write_current_count(&self, unused_classes: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let state = NotifyState { unused_classes };
+        write_output(&self.config.state_file, serde_json::to_string(&state)?.as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use crate::i18n::Locale;
+
+/// Digit grouping and decimal mark for counts and percentages in reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    group_separator: &'static str,
+    decimal_mark: char,
+}
+
+// Regions whose convention differs from their language's are checked first
+const REGION_FORMATS: &[(&str, &str, char)] = &[
+    ("de-ch", "'", '.'),
+    ("fr-ch", "'", '.'),
+    ("it-ch", "'", '.'),
+    ("es-mx", ",", '.'),
+];
+const LANGUAGE_FORMATS: &[(&[&str], &str, char)] = &[
+    (&["en", "ja", "zh", "ko", "he", "th", "ga"], ",", '.'),
+    (&["nl", "de", "it", "es", "pt", "id", "da", "tr", "el", "ro", "hr", "sl", "sr"], ".", ','),
+    // No-break space, so a number never wraps across lines
+    (&["fr", "nb", "no", "sv", "fi", "pl", "cs", "sk", "ru", "uk", "hu", "bg", "et", "lv", "lt"], "\u{a0}", ','),
+];
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::for_locale(Locale::default())
+    }
+}
+
+impl NumberFormat {
+    /// No grouping and a decimal point, the way scripts expect numbers
+    pub const PLAIN: NumberFormat = NumberFormat { group_separator: "", decimal_mark: '.' };
+
+    /* ========================================================================================== */
+    /// Accepts `plain` and the same tags as messages (`nl`, `de_CH.UTF-8`, `pt-BR`)
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let mut parts = tag.split(['_', '-', '.', '@']);
+        let language = parts.next().unwrap_or_default().to_lowercase();
+        let region = parts.next().unwrap_or_default().to_lowercase();
+        if language == "plain" {
+            return Some(Self::PLAIN);
+        }
+
+        let language_region = format!("{}-{}", language, region);
+        REGION_FORMATS.iter()
+            .find(|(tag, _, _)| *tag == language_region)
+            .map(|(_, group_separator, decimal_mark)| Self { group_separator, decimal_mark: *decimal_mark })
+            .or_else(|| LANGUAGE_FORMATS.iter()
+                .find(|(languages, _, _)| languages.contains(&language.as_str()))
+                .map(|(_, group_separator, decimal_mark)| Self { group_separator, decimal_mark: *decimal_mark }))
+    }
+
+    /* ========================================================================================== */
+    pub fn for_locale(locale: Locale) -> Self {
+        match locale {
+            Locale::En => Self { group_separator: ",", decimal_mark: '.' },
+            Locale::Nl => Self { group_separator: ".", decimal_mark: ',' },
+        }
+    }
+
+    /* ========================================================================================== */
+    /// `configured` (`number_locale` in `[output]`) wins; otherwise numbers follow the message language
+    pub fn detect(configured: Option<&str>, locale: Locale) -> Self {
+        configured.and_then(Self::from_tag).unwrap_or_else(|| Self::for_locale(locale))
+    }
+
+    /* ========================================================================================== */
+    pub fn count(&self, value: usize) -> String {
+        self.group(&value.to_string())
+    }
+
+    /* ========================================================================================== */
+    /// One decimal, without the `%` sign, which the messages place themselves
+    pub fn percentage(&self, value: f64) -> String {
+        self.decimal(value, 1)
+    }
+
+    /* ========================================================================================== */
+    pub fn decimal(&self, value: f64, places: usize) -> String {
+        let formatted = format!("{:.*}", places, value.abs());
+        let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+        let sign = if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+        match fraction.is_empty() {
+            true => format!("{}{}", sign, self.group(integer)),
+            false => format!("{}{}{}{}", sign, self.group(integer), self.decimal_mark, fraction),
+        }
+    }
+
+    /* ========================================================================================== */
+    fn group(&self, digits: &str) -> String {
+        if self.group_separator.is_empty() {
+            return digits.to_string();
+        }
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * self.group_separator.len());
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                grouped.push_str(self.group_separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
@@ -0,0 +1,139 @@
+use crate::decision_log::ClassDecision;
+use crate::output_style::OutputStyle;
+use crossbeam_channel::Sender;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisStage {
+    Reading,
+    Sampling,
+    BundleMetafile,
+    ResolvingImports,
+    Extracting,
+    DetectingPatterns,
+    Analyzing,
+    Complete,
+}
+
+/// Receives what an analysis is doing while it runs. Library code never prints; every message
+/// goes through one of these, already localized. All methods default to doing nothing.
+pub trait AnalysisObserver: Send + Sync {
+    /// A stage begins; `message` is its progress line
+    fn on_stage_start(&self, _stage: AnalysisStage, _message: &str) {}
+    /// Counts and other details about the stage in progress
+    fn on_stage_detail(&self, _message: &str) {}
+    fn on_progress(&self, _done: usize, _total: usize) {}
+    /// Called from reader threads, possibly many at once
+    fn on_file_read(&self, _path: &Path) {}
+    /// Final status of one class, once the report is complete
+    fn on_class_classified(&self, _decision: &ClassDecision) {}
+    /// Something was skipped or cut off, but the analysis goes on
+    fn on_warning(&self, _message: &str) {}
+}
+
+/// The default for library users: nothing is reported
+pub struct SilentObserver;
+
+impl AnalysisObserver for SilentObserver {}
+
+/* ============================================================================================== */
+/*                           Command line: progress on stdout, warnings on stderr                 */
+/* ============================================================================================== */
+pub struct ConsoleObserver {
+    style: OutputStyle,
+}
+
+impl ConsoleObserver {
+    pub fn new(style: OutputStyle) -> Self {
+        Self { style }
+    }
+}
+
+impl AnalysisObserver for ConsoleObserver {
+    fn on_stage_start(&self, stage: AnalysisStage, message: &str) {
+        println!("{} {}", self.style.icon(stage.icon()), message);
+    }
+
+    fn on_stage_detail(&self, message: &str) {
+        println!("   {}", message);
+    }
+
+    fn on_progress(&self, done: usize, total: usize) {
+        println!("      {}", self.style.message("progress.processed", &[&done, &total]));
+    }
+
+    fn on_warning(&self, message: &str) {
+        eprintln!("{}  {}", self.style.icon("⚠️"), message);
+    }
+}
+
+impl AnalysisStage {
+    /* ========================================================================================== */
+    pub fn icon(self) -> &'static str {
+        match self {
+            AnalysisStage::Reading => "📁",
+            AnalysisStage::Sampling => "🎲",
+            AnalysisStage::BundleMetafile | AnalysisStage::ResolvingImports => "📦",
+            AnalysisStage::Extracting | AnalysisStage::DetectingPatterns | AnalysisStage::Analyzing => "🔍",
+            AnalysisStage::Complete => "✅",
+        }
+    }
+}
+
+/* ============================================================================================== */
+/*                     GUI: events forwarded to the UI thread, which emits them                   */
+/* ============================================================================================== */
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AnalysisEvent {
+    StageStart { stage: AnalysisStage, message: String },
+    StageDetail { message: String },
+    Progress { done: usize, total: usize },
+    FileRead { path: String },
+    ClassClassified { decision: ClassDecision },
+    Warning { message: String },
+}
+
+pub struct ChannelObserver {
+    sender: Sender<AnalysisEvent>,
+}
+
+impl ChannelObserver {
+    pub fn new(sender: Sender<AnalysisEvent>) -> Self {
+        Self { sender }
+    }
+
+    /* ========================================================================================== */
+    fn send(&self, event: AnalysisEvent) {
+        // A closed window stops listening; the analysis itself still finishes
+        let _ = self.sender.send(event);
+    }
+}
+
+impl AnalysisObserver for ChannelObserver {
+    fn on_stage_start(&self, stage: AnalysisStage, message: &str) {
+        self.send(AnalysisEvent::StageStart { stage, message: message.to_string() });
+    }
+
+    fn on_stage_detail(&self, message: &str) {
+        self.send(AnalysisEvent::StageDetail { message: message.to_string() });
+    }
+
+    fn on_progress(&self, done: usize, total: usize) {
+        self.send(AnalysisEvent::Progress { done, total });
+    }
+
+    fn on_file_read(&self, path: &Path) {
+        self.send(AnalysisEvent::FileRead { path: path.to_string_lossy().to_string() });
+    }
+
+    fn on_class_classified(&self, decision: &ClassDecision) {
+        self.send(AnalysisEvent::ClassClassified { decision: decision.clone() });
+    }
+
+    fn on_warning(&self, message: &str) {
+        self.send(AnalysisEvent::Warning { message: message.to_string() });
+    }
+}
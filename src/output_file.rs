@@ -0,0 +1,161 @@
+use crate::cancellation::{is_cancelled, Cancelled};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Output path that writes to stdout instead of a file
+pub const STDOUT_PATH: &str = "-";
+
+/// Picked from the file extension, so `report.json.gz` is written gzipped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl OutputCompression {
+    /* ========================================================================================== */
+    pub fn from_path(path: &str) -> Self {
+        let extension = Path::new(path).extension().map(|extension| extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("gz") => OutputCompression::Gzip,
+            Some("zst" | "zstd") => OutputCompression::Zstd,
+            _ => OutputCompression::None,
+        }
+    }
+}
+
+/* ============================================================================================== */
+/// The path without a compression extension, for picking the format: `graph.json.gz` -> `graph.json`
+pub fn uncompressed_path(path: &str) -> &str {
+    match OutputCompression::from_path(path) {
+        OutputCompression::None => path,
+        _ => path.rsplit_once('.').map_or(path, |(stem, _)| stem),
+    }
+}
+
+/* ============================================================================================== */
+/*                Reports written through a temp file, so readers never see half of one           */
+/* ============================================================================================== */
+pub fn write_output(path: &str, content: &[u8]) -> io::Result<()> {
+    write_output_with(path, |writer| writer.write_all(content))
+}
+
+/* ============================================================================================== */
+/// Streams into a temp file next to `path` and renames it into place once complete. An
+/// interrupted or failed run leaves the previous file untouched instead of a truncated one.
+pub fn write_output_with<F>(path: &str, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    if path == STDOUT_PATH {
+        let mut stdout = io::stdout().lock();
+        write(&mut stdout)?;
+        return stdout.flush();
+    }
+
+    let temp_path = temp_path(Path::new(path));
+    let result = File::create(&temp_path).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        match OutputCompression::from_path(path) {
+            OutputCompression::None => write(&mut writer)?,
+            OutputCompression::Gzip => {
+                let mut encoder = GzEncoder::new(&mut writer, flate2::Compression::default());
+                write(&mut encoder)?;
+                encoder.finish()?;
+            }
+            OutputCompression::Zstd => {
+                let mut encoder = zstd::Encoder::new(&mut writer, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+                write(&mut encoder)?;
+                encoder.finish()?;
+            }
+        }
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        // Cancelled while writing: the report may be cut short, so it never replaces the old one
+        match is_cancelled() {
+            true => Err(io::Error::new(io::ErrorKind::Interrupted, Cancelled)),
+            false => Ok(()),
+        }
+    });
+
+    match result.and_then(|_| fs::rename(&temp_path, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(e)
+        }
+    }
+}
+
+/* ============================================================================================== */
+/// Reads back a file written by `write_output`, decompressing it by extension
+pub fn read_output(path: &str) -> io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut content = String::new();
+    match OutputCompression::from_path(path) {
+        OutputCompression::None => reader.read_to_string(&mut content)?,
+        OutputCompression::Gzip => GzDecoder::new(reader).read_to_string(&mut content)?,
+        OutputCompression::Zstd => zstd::Decoder::with_buffer(reader)?.read_to_string(&mut content)?,
+    };
+    Ok(content)
+}
+
+/* ============================================================================================== */
+fn temp_path(path: &Path) -> PathBuf {
+    // Same directory, so the rename never crosses filesystems; hidden, so watchers skip it
+    let name = path.file_name().map_or_else(|| "output".into(), |name| name.to_string_lossy());
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
+
+/* ============================================================================================== */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("tag-finder-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn compressed_outputs_read_back() {
+        let directory = scratch_directory("output-compression");
+        for name in ["report.json", "report.json.gz", "report.json.zst"] {
+            let path = directory.join(name).to_string_lossy().to_string();
+            write_output(&path, b"{\"total_classes\": 3}").unwrap();
+            assert_eq!(read_output(&path).unwrap(), "{\"total_classes\": 3}", "{}", name);
+        }
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn failed_write_keeps_the_previous_file() {
+        let directory = scratch_directory("output-failure");
+        let path = directory.join("report.json").to_string_lossy().to_string();
+        write_output(&path, b"previous").unwrap();
+
+        let result = write_output_with(&path, |writer| {
+            writer.write_all(b"half of a rep")?;
+            Err(io::Error::other("interrupted"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "previous");
+        // No temp file left behind next to the report
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn compression_follows_the_extension() {
+        assert_eq!(OutputCompression::from_path("a.json.GZ"), OutputCompression::Gzip);
+        assert_eq!(OutputCompression::from_path("a.zstd"), OutputCompression::Zstd);
+        assert_eq!(uncompressed_path("graph.json.gz"), "graph.json");
+        assert_eq!(uncompressed_path("graph.dot"), "graph.dot");
+    }
+}
@@ -0,0 +1,112 @@
+use crate::config::OutputConfig;
+use crate::i18n::{format_message, message, Locale};
+use crate::number_format::NumberFormat;
+use std::fmt::Display;
+
+// Emoji and box characters with their plain ASCII stand-ins
+const ICONS: &[(&str, &str)] = &[
+    ("📋", "=="),
+    ("🗑️", "[-]"),
+    ("💡", "[i]"),
+    ("📁", "[dir]"),
+    ("🪦", "[dead]"),
+    ("🔍", ">>"),
+    ("📊", "::"),
+    ("✅", "[ok]"),
+    ("📦", "[pkg]"),
+    ("🔎", ">>"),
+    ("🎯", "[*]"),
+    ("🙈", "[ignored]"),
+    ("🧩", "[pattern]"),
+    ("📏", "[rules]"),
+    ("❌", "[x]"),
+    ("⚠️", "[!]"),
+    ("📝", "[file]"),
+    ("📣", "[notify]"),
+    ("🕒", "[history]"),
+    ("🎲", "[sample]"),
+    ("🧪", "[only]"),
+    ("👤", "[author]"),
+    ("👥", "[owner]"),
+    ("📥", "[fetch]"),
+    ("♻️", "[redundant]"),
+    ("👯", "[duplicate]"),
+    ("📐", "[media]"),
+    ("🏷️", "[data]"),
+    ("🔤", "[font]"),
+    ("🖼️", "[asset]"),
+    ("🌀", "[dynamic]"),
+    ("⏱️", "[timeout]"),
+    ("📖", "[doc]"),
+    ("✓", "+"),
+    ("•", "-"),
+];
+
+#[derive(Debug, Clone)]
+pub struct OutputStyle {
+    pub ascii: bool,
+    pub width: usize,
+    pub locale: Locale,
+    pub numbers: NumberFormat,
+}
+
+impl Default for OutputStyle {
+    fn default() -> Self {
+        Self::from_config(&OutputConfig::default())
+    }
+}
+
+impl OutputStyle {
+    pub fn from_config(config: &OutputConfig) -> Self {
+        let locale = Locale::detect(config.locale.as_deref());
+        Self {
+            ascii: config.ascii,
+            width: config.width,
+            locale,
+            numbers: NumberFormat::detect(config.number_locale.as_deref(), locale),
+        }
+    }
+
+    /* ========================================================================================== */
+    pub fn icon(&self, emoji: &'static str) -> &'static str {
+        if !self.ascii {
+            return emoji;
+        }
+
+        ICONS.iter()
+            .find(|(icon, _)| *icon == emoji)
+            .map(|(_, ascii)| *ascii)
+            .unwrap_or("*")
+    }
+
+    /* ========================================================================================== */
+    pub fn text(&self, key: &'static str) -> &'static str {
+        message(self.locale, key)
+    }
+
+    /* ========================================================================================== */
+    pub fn message(&self, key: &'static str, args: &[&dyn Display]) -> String {
+        format_message(self.locale, key, args)
+    }
+
+    /* ========================================================================================== */
+    pub fn count(&self, value: usize) -> String {
+        self.numbers.count(value)
+    }
+
+    /* ========================================================================================== */
+    pub fn percentage(&self, value: f64) -> String {
+        self.numbers.percentage(value)
+    }
+
+    /* ========================================================================================== */
+    pub fn print_header_line(&self) {
+        println!("{spacer:=>width$}", spacer="=", width = self.width);
+    }
+
+    /* ========================================================================================== */
+    pub fn print_section_line(&self) {
+        // Sub-sections stay visibly shorter than headers
+        println!("{spacer:->width$}", spacer="-", width = self.width * 3 / 5);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::exit_status::ConfigError;
+use crate::utils::glob_to_regex;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The `[owners]` globs, compiled once per run
+pub struct OwnerMap {
+    /// Most specific glob first (most literal characters), so `src/components/Button/**` wins over `src/**`
+    patterns: Vec<(Regex, String)>,
+}
+
+impl OwnerMap {
+    pub fn from_config(owners: &BTreeMap<String, String>) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut globs: Vec<(&String, &String)> = owners.iter().collect();
+        globs.sort_by_key(|(glob, _)| std::cmp::Reverse(glob.chars().filter(|c| !matches!(c, '*' | '?')).count()));
+
+        let patterns = globs.into_iter()
+            .map(|(glob, owner)| {
+                glob_to_regex(glob)
+                    .map(|regex| (regex, owner.clone()))
+                    .map_err(|e| ConfigError::new(format!("Invalid glob '{}' in [owners]: {}", glob, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    /* ========================================================================================== */
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /* ========================================================================================== */
+    /// Globs are relative to `root`, the analyzed directory
+    pub fn owner_of(&self, root: &str, file: &str) -> Option<&str> {
+        let path = Path::new(file);
+        let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        self.patterns.iter()
+            .find(|(regex, _)| regex.is_match(&relative))
+            .map(|(_, owner)| owner.as_str())
+    }
+}
@@ -0,0 +1,63 @@
+use std::io::IsTerminal;
+use std::process::{Command, Stdio};
+
+/// Quits right away when the output fits on one screen, and leaves it on screen afterwards
+pub const DEFAULT_PAGER: &str = "less -FRX";
+
+/* ============================================================================================== */
+/*          Pager command from config, then $PAGER, then less; None when paging is off            */
+/* ============================================================================================== */
+pub fn pager_command(configured: Option<&str>) -> Option<String> {
+    let command = configured
+        .map(str::to_string)
+        .or_else(|| std::env::var("PAGER").ok())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let command = command.trim();
+    // An empty pager or plain `cat` means the user wants no paging at all
+    (!command.is_empty() && command != "cat").then(|| command.to_string())
+}
+
+/* ============================================================================================== */
+/// Re-runs this process with the same arguments and its stdout piped into the pager.
+/// Returns the child's exit code once the pager has quit, or `None` when stdout isn't a
+/// terminal or the pager can't be started, in which case the caller prints as usual.
+pub fn run_paged(pager: &str) -> Option<i32> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    let mut pager_process = shell_command(pager)
+        .stdin(Stdio::piped())
+        .spawn()
+        .ok()?;
+    let pager_input = pager_process.stdin.take()?;
+
+    // The child's stdout is the pager pipe, not a terminal, so it prints without paging again
+    let child_status = std::env::current_exe()
+        .and_then(|exe| Command::new(exe)
+            .args(std::env::args_os().skip(1))
+            .stdout(pager_input)
+            .status());
+    let Ok(child_status) = child_status else {
+        let _ = pager_process.kill();
+        let _ = pager_process.wait();
+        return None;
+    };
+
+    let _ = pager_process.wait();
+    Some(child_status.code().unwrap_or(1))
+}
+
+/* ============================================================================================== */
+fn shell_command(command: &str) -> Command {
+    // Through the shell, so PAGER values with arguments or quoting work as they do for git
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
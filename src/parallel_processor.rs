@@ -1,27 +1,76 @@
 use rayon::prelude::*;
-use std::sync::{Arc, Mutex};
-use  crate::utils::{create_thread_pool, update_progress, calculate_progress_step_size};
-use crate::traits::{ThreadCountConfigurable, ProgressConfigurable};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use  crate::utils::{create_thread_pool, update_progress, calculate_progress_step_size, get_thread_count_or_default};
+use crate::traits::{ThreadCountConfigurable, ProgressConfigurable, MemoryBudgetConfigurable, ObserverConfigurable};
+use crate::observer::{AnalysisObserver, SilentObserver};
+use crate::system_limits::default_memory_budget_mb;
+use crate::cancellation::{is_cancelled, Cancelled};
+use crate::output_style::OutputStyle;
+
+/// Upper bound; less on machines with little memory, see `default_memory_budget_mb`
+pub const DEFAULT_MEMORY_BUDGET_MB: usize = 512;
+
+// Files read ahead per analyzer thread, on top of the byte budget
+const QUEUE_DEPTH_PER_ANALYZER: usize = 4;
+
+type SyncError = Box<dyn std::error::Error + Send + Sync>;
 
 pub struct ParallelProcessor {
     thread_count: Option<usize>,
+    /// Reader threads for `stream_fold`; `thread_count` is for the analyzers
+    io_thread_count: Option<usize>,
     show_progress: bool,
+    memory_budget_mb: usize,
+    observer: Arc<dyn AnalysisObserver>,
+    /// Language of the progress lines
+    style: OutputStyle,
+}
+
+// Bytes of file content currently queued or being analyzed
+struct MemoryBudget {
+    limit: usize,
+    in_use: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl Default for ParallelProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ParallelProcessor {
     pub fn new() -> Self {
         Self { 
             thread_count: None,
+            io_thread_count: None,
             show_progress: true,
+            memory_budget_mb: default_memory_budget_mb(),
+            observer: Arc::new(SilentObserver),
+            style: OutputStyle::default(),
         }
     }
 
     /* ========================================================================================== */
+    pub fn with_io_threads(mut self, count: Option<usize>) -> Self {
+        self.io_thread_count = count;
+        self
+    }
+
+    /* ========================================================================================== */
+    pub fn with_output_style(mut self, style: OutputStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /* ========================================================================================== */
+    /// `message` is the catalog key of the progress line, e.g. `progress.reading_stylesheets`
     pub fn process<T, R, F>(
         &self,
         items: Vec<T>,
         processor: F,
-        message: &str,
+        message: &'static str,
     ) -> Result<Vec<R>, Box<dyn std::error::Error>> 
     where
         T: Send + Sync,
@@ -32,7 +81,7 @@ impl ParallelProcessor {
         let total = items.len();
 
         if self.show_progress {
-            println!("{} {} items using {} threads...", message, total, pool.current_num_threads());
+            self.observer.on_stage_detail(&self.style.message("progress.parallel_items", &[&self.style.text(message), &total, &pool.current_num_threads()]));
         }
 
         let results: Result<Vec<_>, Box<dyn std::error::Error + Send + Sync>> = if self.show_progress {
@@ -43,8 +92,8 @@ impl ParallelProcessor {
                 items
                     .par_iter()
                     .map(|item| {
-                        update_progress(&progress_counter, total, step_size);
-                        processor(item)
+                        update_progress(&progress_counter, total, step_size, self.observer.as_ref());
+                        cancellable(|| processor(item))
                     })
                     .collect()
             })
@@ -52,13 +101,16 @@ impl ParallelProcessor {
             pool.install(|| {
                 items
                     .par_iter()
-                    .map(|item| processor(item))
+                    .map(|item| cancellable(|| processor(item)))
                     .collect()
             })
         };
 
         results.map_err(|e| -> Box<dyn std::error::Error> {
-            Box::new(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+            match e.is::<Cancelled>() {
+                true => Box::new(Cancelled),
+                false => Box::new(std::io::Error::other(e.to_string())),
+            }
         })
     }
 
@@ -67,7 +119,7 @@ impl ParallelProcessor {
          &self,
         items: Vec<T>,
         mapper: F,
-        message: &str,
+        message: &'static str,
     ) -> Result<Vec<R>, Box<dyn std::error::Error>>
     where
         T: Send + Sync,
@@ -78,7 +130,7 @@ impl ParallelProcessor {
         let total = items.len();
 
         if self.show_progress {
-            println!("{} {} items using {} threads...", message, total, pool.current_num_threads());
+            self.observer.on_stage_detail(&self.style.message("progress.parallel_items", &[&self.style.text(message), &total, &pool.current_num_threads()]));
         }
 
         let results: Vec<R> = if self.show_progress {
@@ -89,8 +141,8 @@ impl ParallelProcessor {
                 items
                     .par_iter()
                     .flat_map(|item| {
-                        update_progress(&progress_counter, total, step_size);
-                        mapper(item)
+                        update_progress(&progress_counter, total, step_size, self.observer.as_ref());
+                        if is_cancelled() { Vec::new() } else { mapper(item) }
                     })
                     .collect()
             })
@@ -98,13 +150,165 @@ impl ParallelProcessor {
             pool.install(|| {
                 items
                     .par_iter()
-                    .flat_map(|item| mapper(item))
+                    .flat_map(|item| if is_cancelled() { Vec::new() } else { mapper(item) })
                     .collect()
             })
         };
 
+        if is_cancelled() {
+            return Err(Box::new(Cancelled));
+        }
         Ok(results)
     }
+
+    /* ========================================================================================== */
+    /*        Streaming pipeline: reader threads -> bounded queue -> analyzer threads with folds      */
+    /* ========================================================================================== */
+    pub fn stream_fold<A, R, I, F>(
+        &self,
+        paths: Vec<PathBuf>,
+        read: R,
+        init: I,
+        fold: F,
+        message: &'static str,
+    ) -> Result<Vec<A>, Box<dyn std::error::Error>>
+    where
+        A: Send,
+        R: Fn(&Path) -> std::io::Result<String> + Sync,
+        I: Fn() -> A + Sync,
+        F: Fn(&mut A, &Path, String) -> Result<(), SyncError> + Sync,
+    {
+        let analyzer_count = get_thread_count_or_default(self.thread_count);
+        // Reading is I/O bound, a few readers keep the analyzers busy unless told otherwise
+        let reader_count = self.io_thread_count.unwrap_or(analyzer_count / 2).max(1);
+        let total = paths.len();
+
+        if self.show_progress {
+            self.observer.on_stage_detail(&self.style.message("progress.streaming_files",
+                &[&self.style.text(message), &total, &reader_count, &analyzer_count, &self.memory_budget_mb]));
+        }
+
+        let (path_sender, path_receiver) = crossbeam_channel::unbounded::<PathBuf>();
+        for path in paths {
+            path_sender.send(path)?;
+        }
+        drop(path_sender);
+
+        let (content_sender, content_receiver) = crossbeam_channel::bounded::<(PathBuf, String)>(analyzer_count * QUEUE_DEPTH_PER_ANALYZER);
+        let budget = MemoryBudget::new(self.memory_budget_mb * 1024 * 1024);
+        let progress_counter = Arc::new(Mutex::new(0usize));
+        let step_size = calculate_progress_step_size(total, 20);
+
+        let results: Vec<Result<A, SyncError>> = std::thread::scope(|scope| {
+            for _ in 0..reader_count {
+                let path_receiver = path_receiver.clone();
+                let content_sender = content_sender.clone();
+                let (budget, read) = (&budget, &read);
+                scope.spawn(move || {
+                    for path in path_receiver.iter() {
+                        if is_cancelled() {
+                            break;
+                        }
+                        // Skip files we can't read, same as the eager walker
+                        let Ok(content) = read(&path) else {
+                            continue;
+                        };
+                        budget.acquire(content.len());
+                        if content_sender.send((path, content)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(content_sender);
+
+            let analyzers: Vec<_> = (0..analyzer_count)
+                .map(|_| {
+                    let content_receiver = content_receiver.clone();
+                    let (budget, init, fold, progress_counter) = (&budget, &init, &fold, &progress_counter);
+                    scope.spawn(move || -> Result<A, SyncError> {
+                        let mut accumulator = init();
+                        let mut first_error = None;
+
+                        // Keep draining after an error so readers never block on a full queue
+                        for (path, content) in content_receiver.iter() {
+                            let size = content.len();
+                            if first_error.is_none()
+                                && let Err(e) = fold(&mut accumulator, &path, content) {
+                                first_error = Some(e);
+                            }
+                            budget.release(size);
+
+                            if self.show_progress {
+                                update_progress(progress_counter, total, step_size, self.observer.as_ref());
+                            }
+                        }
+
+                        match first_error {
+                            Some(e) => Err(e),
+                            None => Ok(accumulator),
+                        }
+                    })
+                })
+                .collect();
+
+            analyzers.into_iter()
+                .map(|analyzer| analyzer.join().unwrap_or_else(|_| Err("Analyzer thread panicked".into())))
+                .collect()
+        });
+
+        if is_cancelled() {
+            return Err(Box::new(Cancelled));
+        }
+        results.into_iter()
+            .collect::<Result<Vec<A>, SyncError>>()
+            .map_err(|e| -> Box<dyn std::error::Error> {
+                Box::new(std::io::Error::other(e.to_string()))
+            })
+    }
+}
+
+/* ============================================================================================== */
+fn cancellable<R>(work: impl FnOnce() -> Result<R, SyncError>) -> Result<R, SyncError> {
+    // Collecting into a Result stops at the first error, so the remaining items are skipped
+    if is_cancelled() {
+        return Err(Box::new(Cancelled));
+    }
+    work()
+}
+
+impl MemoryBudget {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            in_use: Mutex::new(0),
+            freed: Condvar::new(),
+        }
+    }
+
+    /* ========================================================================================== */
+    fn acquire(&self, bytes: usize) {
+        let mut in_use = self.in_use.lock().unwrap();
+        // A single file larger than the whole budget is still let through once the queue is empty
+        while *in_use > 0 && *in_use + bytes > self.limit {
+            in_use = self.freed.wait(in_use).unwrap();
+        }
+        *in_use += bytes;
+    }
+
+    /* ========================================================================================== */
+    fn release(&self, bytes: usize) {
+        let mut in_use = self.in_use.lock().unwrap();
+        *in_use = in_use.saturating_sub(bytes);
+        self.freed.notify_all();
+    }
+}
+
+impl MemoryBudgetConfigurable for ParallelProcessor {
+    fn with_memory_budget_mb(mut self, megabytes: usize) -> Self {
+        self.memory_budget_mb = megabytes.max(1);
+        self
+    }
 }
 
 impl ThreadCountConfigurable for ParallelProcessor {
@@ -114,6 +318,13 @@ impl ThreadCountConfigurable for ParallelProcessor {
     }
 }
 
+impl ObserverConfigurable for ParallelProcessor {
+    fn with_observer(mut self, observer: Arc<dyn AnalysisObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+}
+
 impl ProgressConfigurable for ParallelProcessor {
     fn with_progress(mut self, show_progress: bool) -> Self {
         self.show_progress = show_progress;
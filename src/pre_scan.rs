@@ -0,0 +1,54 @@
+use crate::config::Config;
+use crate::file_walker::FileWalker;
+use crate::traits::ConfigConfigurable;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Above either of these a full analysis clearly takes minutes, so the GUI asks before starting
+pub const LARGE_SCAN_FILES: usize = 20_000;
+pub const LARGE_SCAN_BYTES: u64 = 200 * 1024 * 1024;
+
+/// What an analysis of a directory would read, found by walking without reading contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreScan {
+    pub stylesheets: usize,
+    pub usage_files: usize,
+    pub total_bytes: u64,
+    /// Biggest directories (relative to the root) by file count, the first candidates for `exclude_dirs`
+    pub largest_dirs: Vec<(String, usize)>,
+    pub is_large: bool,
+}
+
+/* ============================================================================================== */
+pub fn pre_scan(directory: &str, config: &Config) -> Result<PreScan, Box<dyn std::error::Error>> {
+    let scan_filter = config.scan_filter();
+    let files = FileWalker::new(directory.to_string())
+        .with_config(config.clone())
+        .walk()?;
+
+    let stylesheets = files.iter().filter(|path| scan_filter.is_css_file(path)).count();
+    // Metadata only; archive entries have no size on disk and count as 0
+    let total_bytes = files.iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+
+    let mut dir_counts: HashMap<String, usize> = HashMap::new();
+    for path in &files {
+        let relative = path.strip_prefix(directory).unwrap_or(path);
+        if let Some(top) = relative.components().next().filter(|_| relative.components().count() > 1) {
+            *dir_counts.entry(top.as_os_str().to_string_lossy().to_string()).or_default() += 1;
+        }
+    }
+    let mut largest_dirs: Vec<(String, usize)> = dir_counts.into_iter().collect();
+    largest_dirs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    largest_dirs.truncate(5);
+
+    Ok(PreScan {
+        stylesheets,
+        usage_files: files.len() - stylesheets,
+        total_bytes,
+        largest_dirs,
+        is_large: files.len() > LARGE_SCAN_FILES || total_bytes > LARGE_SCAN_BYTES,
+    })
+}
@@ -0,0 +1,88 @@
+use crate::exit_status::ConfigError;
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+
+// Embedded, so presets work from any directory without shipping data files alongside the binary
+const BUILTIN_PRESETS: &[(&str, &str)] = &[
+    ("bootstrap5", include_str!("presets/bootstrap5.toml")),
+    ("foundation6", include_str!("presets/foundation6.toml")),
+    ("wordpress", include_str!("presets/wordpress.toml")),
+];
+
+/// Class families a framework or CMS generates at runtime, turned on with `presets` in `[analysis]`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Preset {
+    #[serde(skip)]
+    pub name: String,
+    pub description: String,
+    pub families: Vec<PresetFamily>,
+}
+
+/// Classes that count as used once a usage file matches `trigger`, e.g. a theme calling `body_class()`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PresetFamily {
+    /// Shown as the pattern in decision logs and `explain`
+    pub name: String,
+    pub trigger: String,
+    #[serde(default)]
+    pub names: Vec<String>,
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+    /// Regexes matched against the whole class name, for families a prefix would overreach
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+impl Preset {
+    /// `<name>.toml` in one of `directories` replaces the built-in preset of that name, or adds a new one
+    pub fn load(name: &str, directories: &[impl AsRef<Path>]) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = directories.iter()
+            .map(|directory| directory.as_ref().join(format!("{}.toml", name)))
+            .find(|path| path.is_file());
+
+        let content = match file {
+            Some(path) => std::fs::read_to_string(&path).map_err(|e| format!("Cannot read preset '{}': {}", path.display(), e))?,
+            None => match BUILTIN_PRESETS.iter().find(|(builtin, _)| *builtin == name) {
+                Some((_, content)) => content.to_string(),
+                None => return Err(ConfigError::new(format!("Unknown preset '{}' (available: {})", name, builtin_preset_names().join(", "))).into()),
+            },
+        };
+
+        let mut preset: Preset = toml::from_str(&content).map_err(|e| ConfigError::new(format!("Invalid preset '{}': {}", name, e)))?;
+        preset.name = name.to_string();
+        Ok(preset)
+    }
+}
+
+impl PresetFamily {
+    /// Names, prefixes and patterns in one anchored regex
+    pub fn class_regex(&self) -> Result<Regex, ConfigError> {
+        let alternatives: Vec<String> = self.names.iter().map(|name| regex::escape(name))
+            .chain(self.prefixes.iter().map(|prefix| format!("{}.*", regex::escape(prefix))))
+            .chain(self.patterns.iter().map(|pattern| format!("(?:{})", pattern)))
+            .collect();
+        // An empty family matches nothing rather than everything
+        let pattern = match alternatives.is_empty() {
+            true => "[^\\s\\S]".to_string(),
+            false => format!("^(?:{})$", alternatives.join("|")),
+        };
+        Regex::new(&pattern).map_err(|e| ConfigError::new(format!("Invalid class pattern in preset family '{}': {}", self.name, e)))
+    }
+
+    /* ========================================================================================== */
+    pub fn trigger_regex(&self) -> Result<Regex, ConfigError> {
+        Regex::new(&self.trigger).map_err(|e| ConfigError::new(format!("Invalid trigger for preset family '{}': {}", self.name, e)))
+    }
+}
+
+/* ============================================================================================== */
+pub fn builtin_preset_names() -> Vec<&'static str> {
+    BUILTIN_PRESETS.iter().map(|(name, _)| *name).collect()
+}
+
+/* ============================================================================================== */
+/// The embedded definition of a built-in preset, a starting point for overriding it
+pub fn builtin_preset_source(name: &str) -> Option<&'static str> {
+    BUILTIN_PRESETS.iter().find(|(builtin, _)| *builtin == name).map(|(_, content)| *content)
+}
@@ -0,0 +1,62 @@
+use crate::css_rules::{CssRule, Specificity};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// A rule whose every declaration is overridden by a later rule for the same selector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedundantRule {
+    pub selector: String,
+    pub specificity: Specificity,
+    pub file: String,
+    pub line: usize,
+    /// Line of the later rule in the same file that wins
+    pub overridden_at: usize,
+    pub properties: Vec<String>,
+}
+
+/* ============================================================================================== */
+/*            Rules of used classes that never apply because a later rule always wins             */
+/* ============================================================================================== */
+pub fn find_redundant_rules(rules: &[CssRule], used_classes: &HashSet<&str>) -> Vec<RedundantRule> {
+    // Only the same selector in the same file and at-rule context is sure to match the same
+    // elements with the same specificity, so source order alone decides. Across files the order
+    // depends on the bundle, which isn't known here.
+    let mut groups: HashMap<(&str, &str, &[String]), Vec<&CssRule>> = HashMap::new();
+    for rule in rules {
+        let classes = rule.classes();
+        if !classes.is_empty() && classes.iter().all(|class| used_classes.contains(class.as_ref())) {
+            groups.entry((&rule.file, &rule.selector, &rule.at_rules)).or_default().push(rule);
+        }
+    }
+
+    let mut redundant: Vec<RedundantRule> = groups.values()
+        .flat_map(|group| group.iter().enumerate().filter_map(|(index, rule)| {
+            let later = group[index + 1..].iter().find(|later| overrides(later, rule))?;
+            Some(RedundantRule {
+                selector: rule.selector.clone(),
+                specificity: rule.specificity(),
+                file: rule.file.clone(),
+                line: rule.line,
+                overridden_at: later.line,
+                properties: rule.declarations.iter().map(|declaration| declaration.property.clone()).collect(),
+            })
+        }))
+        .collect();
+
+    redundant.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+    redundant
+}
+
+/* ============================================================================================== */
+fn overrides(later: &CssRule, earlier: &CssRule) -> bool {
+    // Mixins and @extend can add declarations we don't see, so such rules are never called dead
+    if earlier.declarations.is_empty() || earlier.declarations.iter().any(|declaration| !declaration.is_property()) {
+        return false;
+    }
+
+    earlier.declarations.iter().all(|declaration| {
+        later.declarations.iter().any(|winner| {
+            winner.property.eq_ignore_ascii_case(&declaration.property) && (winner.important || !declaration.important)
+        })
+    })
+}
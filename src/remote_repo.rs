@@ -0,0 +1,123 @@
+use crate::exit_status::ConfigError;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// A stalled tarball download fails instead of hanging; slow but progressing ones still finish
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A remote repository fetched into a temporary directory for one analysis.
+/// The temporary directory is removed again when the checkout is dropped.
+pub struct RemoteCheckout {
+    temp_dir: PathBuf,
+    path: PathBuf,
+    method: FetchMethod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchMethod {
+    /// `git clone --depth 1` into a directory
+    ShallowClone,
+    /// Tarball download, analyzed in memory without extracting it
+    Tarball,
+}
+
+impl RemoteCheckout {
+    pub fn fetch(url: &str, git_ref: Option<&str>) -> Result<Self, Box<dyn std::error::Error>> {
+        // git would read these as options, e.g. `--upload-pack=…` runs a command
+        if url.starts_with('-') {
+            return Err(ConfigError::new(format!("Invalid repository URL '{}'", url)).into());
+        }
+        if let Some(git_ref) = git_ref.filter(|git_ref| git_ref.starts_with('-')) {
+            return Err(ConfigError::new(format!("Invalid ref '{}'", git_ref)).into());
+        }
+
+        let temp_dir = std::env::temp_dir().join(format!("tag-finder-repo-{}-{}", std::process::id(), unique_suffix()));
+        fs::create_dir_all(&temp_dir)?;
+        // Constructed before fetching, so a failed fetch still cleans up on drop
+        let mut checkout = Self { path: temp_dir.join("repo"), temp_dir, method: FetchMethod::ShallowClone };
+
+        let clone_error = match shallow_clone(url, git_ref, &checkout.path) {
+            Ok(()) => return Ok(checkout),
+            Err(e) => e,
+        };
+
+        // Without git (or when cloning fails) GitHub repositories can still be fetched as a tarball
+        let Some(tarball_url) = github_tarball_url(url, git_ref) else {
+            return Err(format!("Cannot clone {}: {}", url, clone_error).into());
+        };
+        checkout.path = checkout.temp_dir.join("repo.tar.gz");
+        checkout.method = FetchMethod::Tarball;
+        download(&tarball_url, &checkout.path)
+            .map_err(|e| format!("Cannot clone {} ({}) or download {}: {}", url, clone_error, tarball_url, e))?;
+
+        Ok(checkout)
+    }
+
+    /* ========================================================================================== */
+    /// Directory or archive to pass on as `--directory`
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /* ========================================================================================== */
+    pub fn method(&self) -> FetchMethod {
+        self.method
+    }
+}
+
+impl Drop for RemoteCheckout {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.temp_dir);
+    }
+}
+
+/* ============================================================================================== */
+fn shallow_clone(url: &str, git_ref: Option<&str>, target: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command = Command::new("git");
+    command.args(["clone", "--quiet", "--depth", "1", "--single-branch"]);
+    // `--branch` takes branch and tag names, which is what `--ref` documents
+    if let Some(git_ref) = git_ref {
+        command.args(["--branch", git_ref]);
+    }
+    command.arg("--").arg(url).arg(target);
+
+    let output = command.output().map_err(|e| format!("git is not available ({})", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string().into());
+    }
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn github_tarball_url(url: &str, git_ref: Option<&str>) -> Option<String> {
+    let repo_path = url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .strip_prefix("https://github.com/")?;
+    let (owner, name) = repo_path.split_once('/')?;
+    if owner.is_empty() || name.is_empty() || name.contains('/') {
+        return None;
+    }
+    // HEAD resolves to the default branch
+    Some(format!("https://codeload.github.com/{}/{}/tar.gz/{}", owner, name, git_ref.unwrap_or("HEAD")))
+}
+
+/* ============================================================================================== */
+fn download(url: &str, target: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(READ_TIMEOUT)
+        .build();
+    let response = agent.get(url).call()?;
+    let mut file = File::create(target)?;
+    std::io::copy(&mut response.into_reader(), &mut file)?;
+    Ok(())
+}
+
+/* ============================================================================================== */
+fn unique_suffix() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos())
+}
@@ -0,0 +1,112 @@
+use crate::exit_status::ConfigError;
+use crate::unused_detector::UnusedReport;
+use crate::utils::glob_to_regex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Narrows a finished report to one area of the project. Usage anywhere still counts; only
+/// the findings outside the area are left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportFilter {
+    /// Globs relative to the analyzed directory, e.g. `components/**`; findings must be in a matching file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<String>,
+    /// Class findings must name a class starting with one of these, e.g. `legacy-`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub class_prefixes: Vec<String>,
+}
+
+// The filter with its globs compiled, for one report
+struct CompiledFilter<'a> {
+    root: &'a str,
+    globs: Vec<Regex>,
+    class_prefixes: &'a [String],
+}
+
+impl ReportFilter {
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.class_prefixes.is_empty()
+    }
+
+    /* ========================================================================================== */
+    /// Globs and class prefixes joined for display, e.g. "components/**, .legacy-*"
+    pub fn describe(&self) -> String {
+        self.only.iter().cloned()
+            .chain(self.class_prefixes.iter().map(|prefix| format!(".{}*", prefix)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /* ========================================================================================== */
+    fn compile<'a>(&'a self, root: &'a str) -> Result<CompiledFilter<'a>, ConfigError> {
+        let globs = self.only.iter()
+            .map(|glob| glob_to_regex(glob).map_err(|e| ConfigError::new(format!("Invalid glob '{}' for --only: {}", glob, e))))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CompiledFilter { root, globs, class_prefixes: &self.class_prefixes })
+    }
+}
+
+impl CompiledFilter<'_> {
+    fn includes_file(&self, file: &str) -> bool {
+        let path = Path::new(file);
+        let relative = path.strip_prefix(self.root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        let relative = relative.trim_start_matches("./");
+        self.globs.is_empty() || self.globs.iter().any(|glob| glob.is_match(relative))
+    }
+
+    /* ========================================================================================== */
+    fn includes_class(&self, name: &str, file: &str) -> bool {
+        let has_prefix = self.class_prefixes.is_empty() || self.class_prefixes.iter().any(|prefix| name.starts_with(prefix.as_str()));
+        has_prefix && self.includes_file(file)
+    }
+}
+
+impl UnusedReport {
+    /// Drops findings outside `filter` and recounts the total, so summaries and rule results
+    /// describe only that area. Findings that aren't about one class are filtered by file only.
+    pub fn apply_filter(&mut self, filter: &ReportFilter, root: &str) -> Result<(), ConfigError> {
+        if filter.is_empty() {
+            return Ok(());
+        }
+        let compiled = filter.compile(root)?;
+
+        for classes in [
+            &mut self.unused_classes,
+            &mut self.used_classes,
+            &mut self.low_confidence_classes,
+            &mut self.dead_code_classes,
+            &mut self.recent_classes,
+            &mut self.legacy_classes,
+            &mut self.not_analyzed_classes,
+        ] {
+            classes.retain(|class| compiled.includes_class(&class.name, &class.file));
+        }
+        self.total_classes = self.unused_classes.len() + self.used_classes.len() + self.low_confidence_classes.len()
+            + self.dead_code_classes.len() + self.recent_classes.len() + self.legacy_classes.len() + self.not_analyzed_classes.len();
+
+        self.by_file.retain(|file, _| compiled.includes_file(file));
+        for classes in self.by_file.values_mut() {
+            classes.retain(|entry| compiled.includes_class(&entry.class.name, &entry.class.file));
+        }
+        self.by_file.retain(|_, classes| !classes.is_empty());
+        for classes in self.by_owner.values_mut() {
+            classes.retain(|class| compiled.includes_class(&class.name, &class.file));
+        }
+        self.by_owner.retain(|_, classes| !classes.is_empty());
+        self.category_only_classes.retain(|usage| compiled.includes_class(&usage.class.name, &usage.class.file));
+        self.decisions.retain(|decision| compiled.includes_class(&decision.class, &decision.file));
+        self.dead_combinations.retain(|compound| compound.classes.iter().any(|name| compiled.includes_class(name, &compound.file)));
+
+        self.redundant_rules.retain(|rule| compiled.includes_file(&rule.file));
+        self.duplicate_blocks.retain(|block| block.locations.iter().any(|location| compiled.includes_file(&location.file)));
+        self.media_queries.retain(|media| media.rules.iter().any(|rule| compiled.includes_file(&rule.file)));
+        self.unused_data_attributes.retain(|selector| compiled.includes_file(&selector.file));
+        self.unused_font_faces.retain(|face| compiled.includes_file(&face.file));
+        self.unused_assets.retain(|asset| asset.references.iter().any(|reference| compiled.includes_file(&reference.file)));
+        self.dynamic_definitions.retain(|definition| compiled.includes_file(&definition.file));
+
+        self.filter = Some(filter.clone());
+        Ok(())
+    }
+}
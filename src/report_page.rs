@@ -0,0 +1,61 @@
+use crate::css_parser::CssClass;
+use crate::duplicate_blocks::DuplicateBlock;
+use crate::unused_detector::{UnusedClass, UnusedReport};
+use serde::{Deserialize, Serialize};
+
+/// One window of a long list, so a frontend only renders the rows in view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportPage<T> {
+    pub offset: usize,
+    /// Length of the whole list, for sizing scrollbars
+    pub total: usize,
+    pub items: Vec<T>,
+}
+
+/// Header row of a file section, without its classes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSection {
+    pub file: String,
+    pub total_classes: usize,
+    pub unused_classes: usize,
+}
+
+impl<T: Clone> ReportPage<T> {
+    pub fn from_slice(items: &[T], offset: usize, limit: usize) -> Self {
+        let start = offset.min(items.len());
+        let end = start.saturating_add(limit).min(items.len());
+        Self { offset: start, total: items.len(), items: items[start..end].to_vec() }
+    }
+}
+
+impl UnusedReport {
+    pub fn unused_classes_page(&self, offset: usize, limit: usize) -> ReportPage<CssClass> {
+        ReportPage::from_slice(&self.unused_classes, offset, limit)
+    }
+
+    /* ========================================================================================== */
+    pub fn file_sections_page(&self, offset: usize, limit: usize) -> ReportPage<FileSection> {
+        // Sorted by path so pages stay stable between requests
+        let mut sections: Vec<FileSection> = self.by_file.iter()
+            .map(|(file, classes)| FileSection {
+                file: file.clone(),
+                total_classes: classes.len(),
+                unused_classes: classes.iter().filter(|class| class.is_unused).count(),
+            })
+            .collect();
+        sections.sort_by(|a, b| a.file.cmp(&b.file));
+        ReportPage::from_slice(&sections, offset, limit)
+    }
+
+    /* ========================================================================================== */
+    pub fn duplicate_blocks_page(&self, offset: usize, limit: usize) -> ReportPage<DuplicateBlock> {
+        ReportPage::from_slice(&self.duplicate_blocks, offset, limit)
+    }
+
+    /* ========================================================================================== */
+    pub fn file_classes_page(&self, file: &str, offset: usize, limit: usize) -> ReportPage<UnusedClass> {
+        let mut classes: Vec<UnusedClass> = self.by_file.get(file).cloned().unwrap_or_default();
+        classes.sort_by(|a, b| a.class.line.cmp(&b.class.line).then_with(|| a.class.name.cmp(&b.class.name)));
+        ReportPage::from_slice(&classes, offset, limit)
+    }
+}
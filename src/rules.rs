@@ -0,0 +1,129 @@
+use crate::config::{RulesConfig, Severity};
+use crate::output_style::OutputStyle;
+use crate::unused_detector::UnusedReport;
+
+/// Kinds of findings in an unused-classes report, each with its own configurable severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    UnusedClass,
+    DeadCodeUsage,
+    UsedOnlyInCategory,
+    DeadCombination,
+    UnusedDataAttribute,
+    UnusedFontFace,
+    UnusedAsset,
+}
+
+pub const ALL_RULES: &[Rule] = &[Rule::UnusedClass, Rule::DeadCodeUsage, Rule::UsedOnlyInCategory, Rule::DeadCombination, Rule::UnusedDataAttribute, Rule::UnusedFontFace, Rule::UnusedAsset];
+
+#[derive(Debug, Clone)]
+pub struct RuleResult {
+    pub rule: Rule,
+    pub severity: Severity,
+    pub findings: usize,
+}
+
+impl Rule {
+    /// Name as used for the key in `[rules]`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::UnusedClass => "unused-class",
+            Rule::DeadCodeUsage => "dead-code-usage",
+            Rule::UsedOnlyInCategory => "used-only-in-category",
+            Rule::DeadCombination => "dead-combination",
+            Rule::UnusedDataAttribute => "unused-data-attribute",
+            Rule::UnusedFontFace => "unused-font-face",
+            Rule::UnusedAsset => "unused-asset",
+        }
+    }
+}
+
+impl RulesConfig {
+    pub fn severity(&self, rule: Rule) -> Severity {
+        match rule {
+            Rule::UnusedClass => self.unused_class,
+            Rule::DeadCodeUsage => self.dead_code_usage,
+            Rule::UsedOnlyInCategory => self.used_only_in_category,
+            Rule::DeadCombination => self.dead_combination,
+            Rule::UnusedDataAttribute => self.unused_data_attribute,
+            Rule::UnusedFontFace => self.unused_font_face,
+            Rule::UnusedAsset => self.unused_asset,
+        }
+    }
+}
+
+impl UnusedReport {
+    pub fn findings(&self, rule: Rule) -> usize {
+        match rule {
+            Rule::UnusedClass => self.unused_classes.len(),
+            Rule::DeadCodeUsage => self.dead_code_classes.len(),
+            Rule::UsedOnlyInCategory => self.category_only_classes.len(),
+            Rule::DeadCombination => self.dead_combinations.len(),
+            Rule::UnusedDataAttribute => self.unused_data_attributes.len(),
+            Rule::UnusedFontFace => self.unused_font_faces.len(),
+            Rule::UnusedAsset => self.unused_assets.len(),
+        }
+    }
+
+    /* ========================================================================================== */
+    pub fn rule_results(&self, rules: &RulesConfig) -> Vec<RuleResult> {
+        ALL_RULES.iter()
+            .map(|rule| RuleResult { rule: *rule, severity: rules.severity(*rule), findings: self.findings(*rule) })
+            .filter(|result| result.severity != Severity::Off && result.findings > 0)
+            .collect()
+    }
+
+    /* ========================================================================================== */
+    pub fn failed_rules(&self, rules: &RulesConfig) -> Vec<Rule> {
+        self.rule_results(rules)
+            .into_iter()
+            .filter(|result| result.severity == Severity::Error)
+            .map(|result| result.rule)
+            .collect()
+    }
+
+    /* ========================================================================================== */
+    pub fn print_rule_results(&self, rules: &RulesConfig, style: &OutputStyle) {
+        let mut results = self.rule_results(rules);
+        if results.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("📏"), style.text("report.rules_heading"));
+        style.print_section_line();
+        // Most severe first, so a failing rule is never scrolled out of view
+        results.sort_by_key(|result| std::cmp::Reverse(result.severity));
+        for result in &results {
+            println!("   {} {}", style.icon(severity_icon(result.severity)), style.message("report.rule_findings", &[
+                &result.rule.name(),
+                &severity_name(result.severity),
+                &style.count(result.findings),
+            ]));
+        }
+
+        let failed = self.failed_rules(rules);
+        if !failed.is_empty() {
+            let names: Vec<&str> = failed.iter().map(Rule::name).collect();
+            println!("\n{} {}", style.icon("❌"), style.message("report.rules_failed", &[&names.join(", ")]));
+        }
+    }
+}
+
+/* ============================================================================================== */
+fn severity_icon(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "❌",
+        Severity::Warn => "⚠️",
+        Severity::Info | Severity::Off => "💡",
+    }
+}
+
+/* ============================================================================================== */
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Off => "off",
+        Severity::Info => "info",
+        Severity::Warn => "warn",
+        Severity::Error => "error",
+    }
+}
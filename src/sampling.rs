@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const DEFAULT_SAMPLE_SEED: u64 = 1;
+
+#[derive(Debug, Clone, Copy)]
+pub struct FileSampler {
+    rate: f64,
+    seed: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleInfo {
+    pub rate: f64,
+    pub seed: u64,
+    pub sampled_files: usize,
+    pub total_files: usize,
+}
+
+/// Unused percentage of a sampled run. Files are sampled, not classes, and usage in the files
+/// left out is simply missing, so a full scan can only find the same share or less.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct UnusedEstimate {
+    pub upper_bound: f64,
+}
+
+impl FileSampler {
+    pub fn new(rate: f64, seed: u64) -> Self {
+        Self { rate: rate.clamp(0.0, 1.0), seed }
+    }
+
+    /* ========================================================================================== */
+    pub fn is_sampled(&self, path: &Path, project_root: &str) -> bool {
+        // Hashing the relative path keeps the choice stable across runs and as files come and go
+        let relative = path.strip_prefix(project_root).unwrap_or(path);
+        let hash = fnv1a(self.seed, relative.to_string_lossy().replace('\\', "/").as_bytes());
+        (hash as f64 / u64::MAX as f64) < self.rate
+    }
+
+    /* ========================================================================================== */
+    pub fn info(&self, sampled_files: usize, total_files: usize) -> SampleInfo {
+        SampleInfo {
+            rate: self.rate,
+            seed: self.seed,
+            sampled_files,
+            total_files,
+        }
+    }
+}
+
+impl UnusedEstimate {
+    /// `unused` out of `total` classes found in the sampled files, in percent
+    pub fn from_counts(unused: usize, total: usize) -> Option<Self> {
+        if total == 0 {
+            return None;
+        }
+        Some(Self { upper_bound: unused as f64 / total as f64 * 100.0 })
+    }
+}
+
+/* ============================================================================================== */
+fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325 ^ seed;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
@@ -0,0 +1,160 @@
+use crate::config::{Config, FileCategoryConfig, UsagePolicy};
+use crate::utils::get_file_extension;
+use std::path::{Component, Path, PathBuf};
+
+/// Category of every stylesheet, regardless of configured categories
+pub const STYLES_CATEGORY: &str = "styles";
+/// Category of usage files no configured category matches
+pub const OTHER_CATEGORY: &str = "other";
+
+/// Which files take part in a scan and which of them define classes.
+/// Built from `[scan]` config and shared by the walker, scanner and detector.
+#[derive(Debug, Clone)]
+pub struct ScanFilter {
+    exclude_dirs: Vec<Vec<String>>,
+    include_extensions: Vec<String>,
+    css_extensions: Vec<String>,
+    component_extensions: Vec<String>,
+    data_extensions: Vec<String>,
+    categories: Vec<FileCategory>,
+    root: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileCategory {
+    pub name: String,
+    pub policy: UsagePolicy,
+    extensions: Vec<String>,
+    paths: Vec<Vec<String>>,
+    stem_suffixes: Vec<String>,
+}
+
+impl Default for ScanFilter {
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
+}
+
+impl ScanFilter {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            // "vendor/legacy" style entries match consecutive path components
+            exclude_dirs: config.scan.exclude_dirs.iter().map(|dir| split_path(dir)).collect(),
+            include_extensions: config.scan.include_extensions.clone(),
+            css_extensions: config.scan.css_extensions.clone(),
+            component_extensions: config.scan.component_extensions.clone(),
+            data_extensions: config.scan.data_extensions.clone(),
+            categories: config.scan.categories.iter().map(FileCategory::from_config).collect(),
+            root: None,
+        }
+    }
+
+    /* ========================================================================================== */
+    pub fn with_root(mut self, root: &str) -> Self {
+        // Category paths are matched below the root, so a project inside `~/docs/` isn't all docs
+        self.root = Some(PathBuf::from(root));
+        self
+    }
+
+    /* ========================================================================================== */
+    pub fn accepts(&self, path: &Path) -> bool {
+        !self.is_excluded(path) && (self.is_css_file(path) || self.is_usage_file(path))
+    }
+
+    /* ========================================================================================== */
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        let components = normal_components(path);
+        self.exclude_dirs.iter().any(|excluded| contains_segments(&components, excluded))
+    }
+
+    /* ========================================================================================== */
+    pub fn is_css_file(&self, path: &Path) -> bool {
+        get_file_extension(path).is_some_and(|ext| self.css_extensions.iter().any(|css_ext| css_ext == ext))
+    }
+
+    /* ========================================================================================== */
+    pub fn is_usage_file(&self, path: &Path) -> bool {
+        get_file_extension(path).is_some_and(|ext| self.include_extensions.iter().any(|include_ext| include_ext == ext))
+            || self.is_component_file(path)
+            || self.is_data_file(path)
+    }
+
+    /* ========================================================================================== */
+    pub fn is_component_file(&self, path: &Path) -> bool {
+        get_file_extension(path).is_some_and(|ext| self.component_extensions.iter().any(|component_ext| component_ext == ext))
+    }
+
+    /* ========================================================================================== */
+    pub fn is_data_file(&self, path: &Path) -> bool {
+        get_file_extension(path).is_some_and(|ext| self.data_extensions.iter().any(|data_ext| data_ext == ext))
+    }
+
+    /* ========================================================================================== */
+    pub fn category(&self, path: &Path) -> Option<&FileCategory> {
+        let relative = self.root.as_ref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        self.categories.iter().find(|category| category.matches(relative))
+    }
+
+    /* ========================================================================================== */
+    pub fn category_name(&self, path: &Path) -> &str {
+        if self.is_css_file(path) {
+            return STYLES_CATEGORY;
+        }
+        self.category(path).map_or(OTHER_CATEGORY, |category| category.name.as_str())
+    }
+
+    /* ========================================================================================== */
+    pub fn usage_policy(&self, path: &Path) -> UsagePolicy {
+        self.category(path).map(|category| category.policy).unwrap_or_default()
+    }
+}
+
+impl FileCategory {
+    fn from_config(config: &FileCategoryConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            policy: config.policy,
+            extensions: config.extensions.clone(),
+            paths: config.paths.iter().map(|path| split_path(path)).collect(),
+            stem_suffixes: config.stem_suffixes.clone(),
+        }
+    }
+
+    /* ========================================================================================== */
+    fn matches(&self, relative_path: &Path) -> bool {
+        // Any one rule is enough, so "tests" can list both directories and `.test` stems
+        let extension_matches = get_file_extension(relative_path)
+            .is_some_and(|ext| self.extensions.iter().any(|category_ext| category_ext == ext));
+        let stem_matches = relative_path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| self.stem_suffixes.iter().any(|suffix| stem.ends_with(suffix.as_str())));
+
+        // The file name itself is not a directory
+        let components = normal_components(relative_path.parent().unwrap_or(Path::new("")));
+        let path_matches = self.paths.iter().any(|segments| contains_segments(&components, segments));
+
+        extension_matches || stem_matches || path_matches
+    }
+}
+
+/* ============================================================================================== */
+fn split_path(path: &str) -> Vec<String> {
+    path.trim_matches('/').split('/').map(str::to_string).collect()
+}
+
+/* ============================================================================================== */
+fn normal_components(path: &Path) -> Vec<&str> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect()
+}
+
+/* ============================================================================================== */
+fn contains_segments(components: &[&str], segments: &[String]) -> bool {
+    !segments.is_empty() && components.windows(segments.len()).any(|window| window == segments)
+}
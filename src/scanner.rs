@@ -1,15 +1,70 @@
-use crate::text_processor::TextProcessor;
+use crate::text_processor::{is_whole_token, lines_with_offsets, token_indices, TextPosition, TextProcessor};
 use crate::config::Config;
+use crate::scan_filter::ScanFilter;
+use crate::config::UsagePolicy;
 use crate::utils::{separate_items_by_condition};
 use crate::parallel_processor::ParallelProcessor;
+use crate::content_cache::ContentCache;
 use crate::traits::{ThreadCountConfigurable, ConfigConfigurable, ProgressConfigurable};
 use crate::ProcessorBuilder;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
 use std::path::PathBuf;
 
+/// Longest search word accepted, in characters; class names and patterns are far shorter
+pub const MAX_WORD_LENGTH: usize = 512;
+
 pub struct FileScanner {
     thread_count: Option<usize>,
     config: Option<Config>,
+    scan_filter: ScanFilter,
+    case_sensitive: bool,
+    regex: bool,
+}
+
+/// Search options the GUI sends along with a find-word request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindWordOptions {
+    #[serde(default = "default_case_sensitive")]
+    pub case_sensitive: bool,
+    /// Treat the word as a regular expression
+    #[serde(default)]
+    pub regex: bool,
+    /// Show matches even when the word is not CSS-only (`--all`)
+    #[serde(default)]
+    pub include_all: bool,
+    /// Globs relative to the directory, e.g. `src/**/*.scss`; empty searches everything
+    #[serde(default)]
+    pub scope: Vec<String>,
+    #[serde(default)]
+    pub threads: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordSearchResult {
+    pub result: ScanResult,
+    /// Whether the matches should be listed, with the same rule as the CLI's `--all`
+    pub show_matches: bool,
+}
+
+// How one search compares text; built once per search from the word and the options
+enum WordMatcher {
+    Exact(String),
+    ExactIgnoreCase(String),
+    /// Names with characters the word tokenizer splits on, matched as whole class tokens
+    Token(String),
+    TokenIgnoreCase(Regex),
+    Pattern(Regex),
+}
+
+/// A search word that can't mean anything useful: empty and blank words would match every file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidWord {
+    Empty,
+    Blank,
+    TooLong { length: usize },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,6 +72,21 @@ pub struct ScanResult {
     pub css_files: Vec<String>,
     pub other_files: Vec<String>,
     pub is_css_only: bool,
+    /// Number of exact occurrences per matching file path
+    #[serde(default)]
+    pub occurrences: HashMap<String, usize>,
+    /// Matching files per category; stylesheets are under `styles`, unmatched files under `other`
+    #[serde(default)]
+    pub categories: BTreeMap<String, Vec<String>>,
+    /// Categories of the non-CSS matches when every one of them has a weak usage policy
+    #[serde(default)]
+    pub used_only_in: Vec<String>,
+}
+
+impl Default for FileScanner {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FileScanner {
@@ -24,12 +94,26 @@ impl FileScanner {
         Self {
             thread_count: None,
             config: None,
+            scan_filter: ScanFilter::default(),
+            case_sensitive: true,
+            regex: false,
         }
     }
 
+    /* ========================================================================================== */
+    pub fn with_match_options(mut self, case_sensitive: bool, regex: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self.regex = regex;
+        self
+    }
+
     /* ========================================================================================== */
     pub fn scan(&self, target_word: String, files_with_content: Vec<(PathBuf, String)>) -> Result<ScanResult, Box<dyn std::error::Error>> {
+        validate_word(&target_word)?;
         let processor = TextProcessor::new();
+        let matcher = self.word_matcher(&target_word)?;
+        // Identical files (vendored copies and the like) are counted once
+        let content_cache: ContentCache<usize> = ContentCache::new();
         // Keep this on silent or it'll spam the hell out of console
         let parallel_processor = ParallelProcessor::new().with_progress(false)
                                                                                 .configure_threads(self.thread_count);
@@ -37,63 +121,121 @@ impl FileScanner {
         let results = parallel_processor.process(
             files_with_content,
             |(file_path, content)| -> Result<Option<ScanFileResult>, Box<dyn std::error::Error + Send + Sync>> {
-                let has_match = if self.contains_special_chars(&target_word) {
-                    content.contains(&target_word)
-                } else {
-                    processor.find_exact_words(content, &target_word)
-                };
+                let occurrences = *content_cache.get_or_compute(content, || matcher.count(&processor, content));
                 
-                if has_match {
+                if occurrences > 0 {
                     let file_path_str = file_path.to_string_lossy().to_string();
-                    let extension = file_path.extension().and_then(|ext| ext.to_str());
-                    let is_css = self.is_css_file(extension);
+                    let is_css = self.scan_filter.is_css_file(file_path);
+                    let category = self.scan_filter.category_name(file_path).to_string();
+                    let policy = self.scan_filter.usage_policy(file_path);
                     
                     Ok(Some(ScanFileResult {
                         file_path: file_path_str,
                         is_css,
+                        occurrences,
+                        category,
+                        policy,
                     }))
                 } else {
                     Ok(None)
                 }
             },
-            "Scanning files"
+            "progress.scanning_files"
         )?;
 
         self.process_scan_results(results.into_iter().flatten().collect())
     }
 
     /* ========================================================================================== */
-    fn is_css_file(&self, extension: Option<&str>) -> bool {
-        if let Some(config) = &self.config {
-            extension.map_or(false, |ext| {
-                config.scan.css_extensions.iter().any(|css_ext| css_ext == ext)
+    pub fn find_word_positions(&self, content: &str, target_word: &str) -> Vec<TextPosition> {
+        let processor = TextProcessor::new();
+        // An invalid pattern already failed the scan that found this file
+        let Ok(matcher) = self.word_matcher(target_word) else {
+            return Vec::new();
+        };
+
+        lines_with_offsets(content)
+            .enumerate()
+            .filter_map(|(line_index, (line_offset, line))| {
+                let index = matcher.find(&processor, line)?;
+                Some(TextPosition::in_line(line_index + 1, line, line_offset, index))
             })
+            .collect()
+    }
+
+    /* ========================================================================================== */
+    pub fn contains_word_in(&self, content: &str, words: &HashSet<&str>, target_word: &str) -> bool {
+        // `words` is `TextProcessor::word_set(content)`, tokenized once and shared across lookups
+        if self.contains_special_chars(target_word) {
+            token_indices(content, target_word).next().is_some()
         } else {
-            matches!(extension, Some("css") | Some("scss"))
+            words.contains(target_word)
         }
     }
 
+    /* ========================================================================================== */
+    pub fn with_scan_filter(mut self, scan_filter: ScanFilter) -> Self {
+        self.scan_filter = scan_filter;
+        self
+    }
+
     /* ========================================================================================== */
     fn process_scan_results(&self, results: Vec<ScanFileResult>) -> Result<ScanResult, Box<dyn std::error::Error>> {
+        let occurrences: HashMap<String, usize> = results
+            .iter()
+            .map(|r| (r.file_path.clone(), r.occurrences))
+            .collect();
+
+        let mut categories: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for result in &results {
+            categories.entry(result.category.clone()).or_default().push(result.file_path.clone());
+        }
+
         let (css_results, other_results) = separate_items_by_condition(
             results,
             |result| result.is_css
         );
 
+        // Files in ignored categories are listed, but don't count against CSS-only
+        let usage_results: Vec<&ScanFileResult> = other_results.iter()
+            .filter(|r| r.policy != UsagePolicy::Ignore)
+            .collect();
+        let used_only_in: Vec<String> = if !usage_results.is_empty() && usage_results.iter().all(|r| r.policy == UsagePolicy::Weak) {
+            usage_results.iter().map(|r| r.category.clone()).collect::<BTreeSet<String>>().into_iter().collect()
+        } else {
+            Vec::new()
+        };
+
+        let is_css_only = !css_results.is_empty() && usage_results.is_empty();
         let css_files: Vec<String> = css_results.into_iter().map(|r| r.file_path).collect();
         let other_files: Vec<String> = other_results.into_iter().map(|r| r.file_path).collect();
 
-        let is_css_only = !css_files.is_empty() && other_files.is_empty();
-
         Ok(ScanResult {
             css_files,
             other_files,
             is_css_only,
+            occurrences,
+            categories,
+            used_only_in,
+        })
+    }
+
+    /* ========================================================================================== */
+    fn word_matcher(&self, word: &str) -> Result<WordMatcher, regex::Error> {
+        if self.regex {
+            return RegexBuilder::new(word).case_insensitive(!self.case_sensitive).build().map(WordMatcher::Pattern);
+        }
+
+        Ok(match (self.contains_special_chars(word), self.case_sensitive) {
+            (true, true) => WordMatcher::Token(word.to_string()),
+            (true, false) => WordMatcher::TokenIgnoreCase(RegexBuilder::new(&regex::escape(word)).case_insensitive(true).build()?),
+            (false, true) => WordMatcher::Exact(word.to_string()),
+            (false, false) => WordMatcher::ExactIgnoreCase(word.to_lowercase()),
         })
     }
 
     /* ========================================================================================== */
-    fn contains_special_chars(&self, word: &str) -> bool {
+    pub fn contains_special_chars(&self, word: &str) -> bool {
         word.chars().any(|c| !c.is_alphanumeric() && c != '_' && c != '-')
     }
 
@@ -108,14 +250,97 @@ impl ThreadCountConfigurable for FileScanner {
 
 impl ConfigConfigurable for FileScanner {
     fn with_config(mut self, config: Config) -> Self {
+        self.scan_filter = config.scan_filter();
         self.config = Some(config);
         self
     }
 }
 
+impl WordMatcher {
+    fn count(&self, processor: &TextProcessor, content: &str) -> usize {
+        match self {
+            WordMatcher::Exact(word) => processor.count_exact_words(content, word),
+            WordMatcher::ExactIgnoreCase(word) => content
+                .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+                .filter(|token| token.to_lowercase() == *word)
+                .count(),
+            WordMatcher::Token(word) => token_indices(content, word).count(),
+            WordMatcher::TokenIgnoreCase(pattern) => pattern.find_iter(content)
+                .filter(|found| is_whole_token(content, found.start(), found.end()))
+                .count(),
+            WordMatcher::Pattern(pattern) => pattern.find_iter(content).count(),
+        }
+    }
+
+    /* ========================================================================================== */
+    /// Byte index of the first match in `content`
+    fn find(&self, processor: &TextProcessor, content: &str) -> Option<usize> {
+        match self {
+            WordMatcher::Exact(word) => processor.word_indices(content).into_iter()
+                .find(|(_, token)| token == word)
+                .map(|(index, _)| index),
+            WordMatcher::ExactIgnoreCase(word) => processor.word_indices(content).into_iter()
+                .find(|(_, token)| token.to_lowercase() == *word)
+                .map(|(index, _)| index),
+            WordMatcher::Token(word) => token_indices(content, word).next(),
+            WordMatcher::TokenIgnoreCase(pattern) => pattern.find_iter(content)
+                .find(|found| is_whole_token(content, found.start(), found.end()))
+                .map(|found| found.start()),
+            WordMatcher::Pattern(pattern) => pattern.find(content).map(|found| found.start()),
+        }
+    }
+}
+
+impl Default for FindWordOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: default_case_sensitive(),
+            regex: false,
+            include_all: false,
+            scope: Vec::new(),
+            threads: None,
+        }
+    }
+}
+
+fn default_case_sensitive() -> bool {
+    true
+}
+
 // Helper struct for internal processing
 #[derive(Debug)]
 struct ScanFileResult {
     file_path: String,
     is_css: bool,
-}
\ No newline at end of file
+    occurrences: usize,
+    category: String,
+    policy: UsagePolicy,
+}
+
+/* ============================================================================================== */
+/*                              Words rejected before any file is read                            */
+/* ============================================================================================== */
+pub fn validate_word(word: &str) -> Result<(), InvalidWord> {
+    let length = word.chars().count();
+    if word.is_empty() {
+        Err(InvalidWord::Empty)
+    } else if word.trim().is_empty() {
+        Err(InvalidWord::Blank)
+    } else if length > MAX_WORD_LENGTH {
+        Err(InvalidWord::TooLong { length })
+    } else {
+        Ok(())
+    }
+}
+
+impl fmt::Display for InvalidWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidWord::Empty => write!(f, "The search word is empty"),
+            InvalidWord::Blank => write!(f, "The search word is only whitespace"),
+            InvalidWord::TooLong { length } => write!(f, "The search word is {} characters long, at most {} are allowed", length, MAX_WORD_LENGTH),
+        }
+    }
+}
+
+impl std::error::Error for InvalidWord {}
@@ -0,0 +1,81 @@
+use crate::text_processor::{lines_with_offsets, TextPosition};
+use grass::{InputSyntax, NullLogger, Options, OutputStyle};
+use std::path::{Path, PathBuf};
+
+/// Expands SCSS with an embedded Sass compiler, so selectors built by `@each`, `@for` and mixins
+/// can be extracted from what the browser actually gets
+pub struct ScssCompiler {
+    load_paths: Vec<PathBuf>,
+}
+
+impl ScssCompiler {
+    /// `load_paths` are searched for `@use`/`@import` after the stylesheet's own directory
+    pub fn new(load_paths: Vec<PathBuf>) -> Self {
+        Self { load_paths }
+    }
+
+    /* ========================================================================================== */
+    pub fn is_compilable(path: &Path) -> bool {
+        path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("scss") || extension.eq_ignore_ascii_case("sass"))
+    }
+
+    /* ========================================================================================== */
+    pub fn compile(&self, path: &Path, content: &str) -> Result<String, String> {
+        let syntax = match path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("sass")) {
+            true => InputSyntax::Sass,
+            false => InputSyntax::Scss,
+        };
+        // `@debug` and `@warn` output would land in the middle of the report
+        let mut options = Options::default()
+            .style(OutputStyle::Expanded)
+            .input_syntax(syntax)
+            .logger(&NullLogger)
+            .quiet(true);
+        if let Some(directory) = path.parent() {
+            options = options.load_path(directory);
+        }
+        options = options.load_paths(&self.load_paths);
+
+        grass::from_string(content, &options).map_err(|e| e.to_string())
+    }
+}
+
+/* ============================================================================================== */
+/*                     Where in the SCSS source a class of the compiled output comes from         */
+/* ============================================================================================== */
+/// Where `.class` is written out, or else the selector that builds the longest part of it:
+/// `.text-#{$color}` for `text-red`, `&__title` nested in `.card` for `card__title`
+pub fn source_position(source: &str, class: &str) -> Option<TextPosition> {
+    let literal = format!(".{}", class);
+    let mut partial: Option<(TextPosition, usize)> = None;
+    let mut consider = |position: TextPosition, length: usize| {
+        if length > 0 && partial.is_none_or(|(_, longest)| length > longest) {
+            partial = Some((position, length));
+        }
+    };
+
+    for (line_index, (line_offset, line)) in lines_with_offsets(source).enumerate() {
+        let at = |index: usize| TextPosition::in_line(line_index + 1, line, line_offset, index);
+        if let Some((start, _)) = line.match_indices(&literal).find(|(start, _)| !is_name_char(line[start + literal.len()..].chars().next())) {
+            return Some(at(start + 1));
+        }
+        for (start, _) in line.match_indices("#{") {
+            if let Some((dot, prefix)) = line[..start].rsplit_once('.') && class.starts_with(prefix) {
+                consider(at(dot.len() + 1), prefix.len());
+            }
+        }
+        for (start, _) in line.match_indices('&') {
+            let suffix: String = line[start + 1..].chars().take_while(|c| is_name_char(Some(*c))).collect();
+            if class.ends_with(&suffix) {
+                consider(at(start), suffix.len());
+            }
+        }
+    }
+
+    partial.map(|(position, _)| position)
+}
+
+/* ============================================================================================== */
+fn is_name_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
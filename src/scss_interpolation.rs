@@ -0,0 +1,366 @@
+use crate::css_parser::CssClass;
+use crate::text_processor::position_at;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Expansions beyond this many classes per selector are reported as dynamic instead
+const MAX_EXPANSIONS: usize = 1000;
+
+const EACH_PATTERN: &str = r"^@each\s+(\$[\w-]+(?:\s*,\s*\$[\w-]+)*)\s+in\s+(.+)$";
+const FOR_PATTERN: &str = r"^@for\s+\$([\w-]+)\s+from\s+(\S+)\s+(through|to)\s+(\S+)$";
+const PARAMETER_PATTERN: &str = r"\$([\w-]+)";
+const VARIABLE_PATTERN: &str = r"(?m)^\$([\w-]+)\s*:\s*([^;]+?)\s*(?:!default|!global)?\s*;";
+
+/// A class selector built with `#{...}` whose values can't be known without compiling,
+/// e.g. from a mixin parameter. Never reported as unused.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicDefinition {
+    /// The class as written, e.g. `.btn-#{$variant}`
+    pub selector: String,
+    pub file: String,
+    pub line: usize,
+    #[serde(default)]
+    pub column: usize,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// What the interpolated selectors of one stylesheet turned into
+#[derive(Debug, Default)]
+pub struct InterpolatedClasses {
+    pub file: String,
+    /// Classes spelled out from loops and variables with known values
+    pub expanded: Vec<CssClass>,
+    pub dynamic: Vec<DynamicDefinition>,
+    /// Literal heads like `text-` of `.text-#{$color}`, with their line; the class pattern
+    /// picks these up as if they were classes
+    pub fragments: Vec<(String, usize)>,
+}
+
+enum Part {
+    Literal(String),
+    Interpolation(String),
+}
+
+/// Values a variable takes inside a block; `None` when only the compiler knows them
+type Bindings = Vec<(String, Option<Vec<String>>)>;
+
+struct BlockPatterns {
+    each: Regex,
+    for_loop: Regex,
+    parameter: Regex,
+}
+
+/* ============================================================================================== */
+/*                  `#{$var}` selectors: expanded when the values are in the file                 */
+/* ============================================================================================== */
+pub fn interpolated_classes(path: &Path, content: &str) -> InterpolatedClasses {
+    let file = path.to_string_lossy().to_string();
+    let variables = file_variables(content);
+    let patterns = BlockPatterns {
+        each: Regex::new(EACH_PATTERN).unwrap(),
+        for_loop: Regex::new(FOR_PATTERN).unwrap(),
+        parameter: Regex::new(PARAMETER_PATTERN).unwrap(),
+    };
+    let (offsets, chars): (Vec<usize>, Vec<char>) = content.char_indices().unzip();
+    let mut result = InterpolatedClasses { file: file.clone(), ..Default::default() };
+    let mut frames: Vec<Bindings> = Vec::new();
+    let mut buffer = String::new();
+    // Where the statement in `buffer` starts, in bytes
+    let mut buffer_offset = 0;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let next = chars.get(index + 1).copied();
+        match c {
+            '/' if next == Some('*') => {
+                index = find_comment_end(&chars, index + 2);
+                continue;
+            }
+            '/' if next == Some('/') && index.checked_sub(1).is_none_or(|prev| chars[prev].is_whitespace() || "{};".contains(chars[prev])) => {
+                while index < chars.len() && chars[index] != '\n' {
+                    index += 1;
+                }
+                continue;
+            }
+            '"' | '\'' => {
+                let end = chars[index + 1..].iter().position(|quote| *quote == c).map_or(chars.len(), |offset| index + offset + 2);
+                buffer.extend(&chars[index..end]);
+                index = end;
+                continue;
+            }
+            '#' if next == Some('{') => {
+                let end = chars[index..].iter().position(|close| *close == '}').map_or(chars.len(), |offset| index + offset + 1);
+                if buffer.trim().is_empty() {
+                    buffer_offset = offsets[index];
+                }
+                buffer.extend(&chars[index..end]);
+                index = end;
+                continue;
+            }
+            '{' => {
+                let bindings = block_bindings(&patterns, buffer.trim(), &frames, &variables);
+                if !buffer.trim_start().starts_with('@') {
+                    add_selector_classes(&mut result, content, &buffer, buffer_offset, &frames, &variables);
+                }
+                frames.push(bindings);
+                buffer.clear();
+            }
+            ';' | '}' => {
+                buffer.clear();
+                if c == '}' {
+                    frames.pop();
+                }
+            }
+            _ => {
+                if !c.is_whitespace() && buffer.trim().is_empty() {
+                    buffer_offset = offsets[index];
+                }
+                buffer.push(c);
+            }
+        }
+        index += 1;
+    }
+
+    result
+}
+
+/* ============================================================================================== */
+fn add_selector_classes(
+    result: &mut InterpolatedClasses,
+    content: &str,
+    prelude: &str,
+    prelude_offset: usize,
+    frames: &[Bindings],
+    variables: &HashMap<String, String>,
+) {
+    for token in interpolated_tokens(prelude) {
+        // The buffer drops comments, so the token is looked up in the file itself
+        let selector = format!(".{}", token);
+        let offset = content[prelude_offset..].find(&selector).map_or(prelude_offset, |index| prelude_offset + index + 1);
+        let position = position_at(content, offset);
+        let parts = split_parts(token);
+        if let Some(Part::Literal(head)) = parts.first() {
+            result.fragments.push((head.clone(), position.line));
+        }
+
+        match expand(&parts, frames, variables) {
+            Some(names) => result.expanded.extend(names.into_iter().map(|name| CssClass {
+                name,
+                file: result.file.clone(),
+                line: position.line,
+                column: position.column,
+                offset: position.offset,
+                layer: None,
+                scope: None,
+                declarations: None,
+                doc: None,
+            })),
+            None => result.dynamic.push(DynamicDefinition {
+                selector,
+                file: result.file.clone(),
+                line: position.line,
+                column: position.column,
+                offset: position.offset,
+            }),
+        }
+    }
+}
+
+/* ============================================================================================== */
+/// Class names in a selector list that contain an interpolation, without the dot
+fn interpolated_tokens(prelude: &str) -> Vec<&str> {
+    let bytes = prelude.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] != b'.' {
+            index += 1;
+            continue;
+        }
+        let start = index + 1;
+        let mut end = start;
+        while end < bytes.len() {
+            if bytes[end] == b'#' && bytes.get(end + 1) == Some(&b'{') {
+                end = prelude[end..].find('}').map_or(bytes.len(), |close| end + close + 1);
+            } else if bytes[end].is_ascii_alphanumeric() || bytes[end] == b'-' || bytes[end] == b'_' || bytes[end] >= 0x80 {
+                end += 1;
+            } else {
+                break;
+            }
+        }
+        if prelude[start..end].contains("#{") {
+            tokens.push(&prelude[start..end]);
+        }
+        index = end.max(start);
+    }
+
+    tokens
+}
+
+/* ============================================================================================== */
+fn split_parts(token: &str) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut rest = token;
+    while let Some(start) = rest.find("#{") {
+        if start > 0 {
+            parts.push(Part::Literal(rest[..start].to_string()));
+        }
+        let end = rest[start..].find('}').map_or(rest.len(), |close| start + close);
+        parts.push(Part::Interpolation(rest[start + 2..end].trim().to_string()));
+        rest = rest.get(end + 1..).unwrap_or("");
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Literal(rest.to_string()));
+    }
+    parts
+}
+
+/* ============================================================================================== */
+/// Every name the parts spell out, or `None` when one interpolation has no known values
+fn expand(parts: &[Part], frames: &[Bindings], variables: &HashMap<String, String>) -> Option<Vec<String>> {
+    let mut names = vec![String::new()];
+    for part in parts {
+        let values = match part {
+            Part::Literal(literal) => vec![literal.clone()],
+            Part::Interpolation(expression) => resolve(expression, frames, variables)?,
+        };
+        if names.len() * values.len() > MAX_EXPANSIONS {
+            return None;
+        }
+        names = names.iter()
+            .flat_map(|name| values.iter().map(move |value| format!("{}{}", name, value)))
+            .collect();
+    }
+    Some(names)
+}
+
+/* ============================================================================================== */
+fn resolve(expression: &str, frames: &[Bindings], variables: &HashMap<String, String>) -> Option<Vec<String>> {
+    let name = expression.strip_prefix('$')?;
+    // The innermost block binding the name wins, as in Sass scoping
+    for bindings in frames.iter().rev() {
+        if let Some((_, values)) = bindings.iter().find(|(bound, _)| bound == name) {
+            return values.clone();
+        }
+    }
+    let value = unquote(variables.get(name)?);
+    is_name_fragment(value).then(|| vec![value.to_string()])
+}
+
+/* ============================================================================================== */
+/// Names an `@each`, `@for`, `@mixin` or `@function` binds for the block it opens
+fn block_bindings(patterns: &BlockPatterns, prelude: &str, frames: &[Bindings], variables: &HashMap<String, String>) -> Bindings {
+    if let Some(captures) = patterns.each.captures(prelude) {
+        let names: Vec<String> = captures[1].split(',').map(|name| name.trim().trim_start_matches('$').to_string()).collect();
+        let items = list_items(captures[2].trim(), frames, variables);
+        return names.iter().enumerate()
+            .map(|(position, name)| {
+                // `@each $key, $value in $map`: only the keys are plain names
+                let values = items.as_ref()
+                    .filter(|(_, is_map)| position == 0 && (*is_map == (names.len() > 1)))
+                    .map(|(items, _)| items.clone());
+                (name.clone(), values)
+            })
+            .collect();
+    }
+
+    if let Some(captures) = patterns.for_loop.captures(prelude) {
+        let bound = |value: &str| -> Option<i64> {
+            value.parse().ok().or_else(|| resolve(value, frames, variables)?.first()?.parse().ok())
+        };
+        let values = bound(&captures[2]).zip(bound(&captures[4])).and_then(|(from, to)| {
+            let to = if &captures[3] == "to" { to - 1 } else { to };
+            ((to - from).unsigned_abs() < MAX_EXPANSIONS as u64).then(|| (from.min(to)..=from.max(to)).map(|i| i.to_string()).collect())
+        });
+        return vec![(captures[1].to_string(), values)];
+    }
+
+    // Parameters shadow globals of the same name and are only known per @include
+    if prelude.starts_with("@mixin") || prelude.starts_with("@function") {
+        return patterns.parameter.captures_iter(prelude).map(|captures| (captures[1].to_string(), None)).collect();
+    }
+
+    Vec::new()
+}
+
+/* ============================================================================================== */
+/// Items of a literal list or map (keys), or of a variable holding one; the flag is set for maps
+fn list_items(expression: &str, frames: &[Bindings], variables: &HashMap<String, String>) -> Option<(Vec<String>, bool)> {
+    let expression = expression.strip_prefix("map-keys(").or_else(|| expression.strip_prefix("map.keys("))
+        .and_then(|inner| inner.strip_suffix(')'))
+        .unwrap_or(expression);
+    if let Some(name) = expression.strip_prefix('$') {
+        // A loop variable holds single items, not lists
+        if frames.iter().any(|bindings| bindings.iter().any(|(bound, _)| bound == name)) {
+            return None;
+        }
+        return list_items(variables.get(name)?, &[], &HashMap::new());
+    }
+
+    let inner = expression.strip_prefix('(').and_then(|inner| inner.strip_suffix(')')).unwrap_or(expression);
+    let items = split_top_level(inner, ',');
+    let items: Vec<&str> = match items.as_slice() {
+        [single] if !single.contains(':') => single.split_whitespace().collect(),
+        _ => items,
+    };
+    let is_map = items.iter().all(|item| split_top_level(item, ':').len() == 2);
+    let names: Vec<String> = items.iter()
+        .map(|item| match is_map {
+            true => unquote(split_top_level(item, ':')[0]).to_string(),
+            false => unquote(item).to_string(),
+        })
+        .collect();
+
+    names.iter().all(|name| is_name_fragment(name)).then_some((names, is_map))
+}
+
+/* ============================================================================================== */
+fn split_top_level(list: &str, separator: char) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in list.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if c == separator && depth == 0 => {
+                items.push(list[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(list[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/* ============================================================================================== */
+/// Top-level `$name: value;` declarations; a later one replaces an earlier one
+fn file_variables(content: &str) -> HashMap<String, String> {
+    Regex::new(VARIABLE_PATTERN).unwrap().captures_iter(content)
+        .map(|captures| (captures[1].to_string(), captures[2].trim().to_string()))
+        .collect()
+}
+
+/* ============================================================================================== */
+fn find_comment_end(chars: &[char], from: usize) -> usize {
+    (from..chars.len().saturating_sub(1))
+        .find(|&index| chars[index] == '*' && chars[index + 1] == '/')
+        .map_or(chars.len(), |end| end + 2)
+}
+
+/* ============================================================================================== */
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+/* ============================================================================================== */
+fn is_name_fragment(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
@@ -0,0 +1,168 @@
+use crate::config::Config;
+use crate::file_provider::FileProvider;
+use crate::traits::{ConfigConfigurable, ProgressConfigurable};
+use crate::unused_detector::{UnusedDetector, UnusedReport};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Name of the golden report next to a fixture's files
+pub const GOLDEN_FILE: &str = "expected.golden";
+const CONFIG_FILE: &str = "tag-finder.toml";
+// Embedded fixtures are served from memory below this made-up directory
+const SELFTEST_ROOT: &str = "selftest";
+
+/// A small project from `tests/fixtures`, compiled into the binary so installs can be checked
+pub struct Fixture {
+    pub name: &'static str,
+    /// Paths relative to the fixture directory, with their content
+    pub files: &'static [(&'static str, &'static str)],
+    /// The fixture's `tag-finder.toml`, if it has one
+    pub config: Option<&'static str>,
+    pub expected: &'static str,
+}
+
+/// A fixture's golden report next to what this build produced
+#[derive(Debug, Clone)]
+pub struct FixtureOutcome {
+    pub name: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+macro_rules! fixture_file {
+    ($fixture:literal, $path:literal) => {
+        ($path, include_str!(concat!("../tests/fixtures/", $fixture, "/", $path)))
+    };
+}
+
+const FIXTURES: &[Fixture] = &[
+    Fixture {
+        name: "html",
+        files: &[fixture_file!("html", "index.html"), fixture_file!("html", "styles.css")],
+        config: None,
+        expected: include_str!("../tests/fixtures/html/expected.golden"),
+    },
+    Fixture {
+        name: "php",
+        files: &[fixture_file!("php", "style.css"), fixture_file!("php", "templates/header.php")],
+        config: None,
+        expected: include_str!("../tests/fixtures/php/expected.golden"),
+    },
+    Fixture {
+        name: "react",
+        files: &[fixture_file!("react", "src/App.css"), fixture_file!("react", "src/App.jsx")],
+        config: None,
+        expected: include_str!("../tests/fixtures/react/expected.golden"),
+    },
+    Fixture {
+        name: "scss-nested",
+        files: &[fixture_file!("scss-nested", "index.html"), fixture_file!("scss-nested", "styles/main.scss")],
+        config: Some(include_str!("../tests/fixtures/scss-nested/tag-finder.toml")),
+        expected: include_str!("../tests/fixtures/scss-nested/expected.golden"),
+    },
+    Fixture {
+        name: "tailwind",
+        files: &[fixture_file!("tailwind", "index.html"), fixture_file!("tailwind", "styles.css")],
+        config: None,
+        expected: include_str!("../tests/fixtures/tailwind/expected.golden"),
+    },
+    Fixture {
+        name: "vue",
+        files: &[
+            fixture_file!("vue", "src/components/Card.vue"),
+            fixture_file!("vue", "src/global.css"),
+            fixture_file!("vue", "src/main.js"),
+        ],
+        config: Some(include_str!("../tests/fixtures/vue/tag-finder.toml")),
+        expected: include_str!("../tests/fixtures/vue/expected.golden"),
+    },
+];
+
+// Serves one embedded fixture, so the self-test needs no writable directory
+struct FixtureProvider {
+    files: BTreeMap<PathBuf, &'static str>,
+}
+
+impl FileProvider for FixtureProvider {
+    fn list(&self) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        Ok(self.files.keys().cloned().collect())
+    }
+
+    /* ========================================================================================== */
+    fn read(&self, path: &Path) -> std::io::Result<String> {
+        self.files.get(path)
+            .map(|content| content.to_string())
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, format!("{} is not a fixture file", path.display())))
+    }
+}
+
+impl FixtureOutcome {
+    pub fn passed(&self) -> bool {
+        self.expected == self.actual
+    }
+
+    /* ========================================================================================== */
+    /// Golden lines this build didn't produce, then lines it produced that aren't in the golden report
+    pub fn differences(&self) -> (Vec<&str>, Vec<&str>) {
+        let expected: BTreeSet<&str> = self.expected.lines().collect();
+        let actual: BTreeSet<&str> = self.actual.lines().collect();
+        (expected.difference(&actual).copied().collect(), actual.difference(&expected).copied().collect())
+    }
+}
+
+/* ============================================================================================== */
+pub fn fixtures() -> &'static [Fixture] {
+    FIXTURES
+}
+
+/* ============================================================================================== */
+/// Analyzes every embedded fixture and compares it with its golden report
+pub fn run_selftest() -> Result<Vec<FixtureOutcome>, Box<dyn std::error::Error>> {
+    FIXTURES.iter()
+        .map(|fixture| {
+            let root = Path::new(SELFTEST_ROOT).join(fixture.name);
+            let provider = FixtureProvider {
+                files: fixture.files.iter().map(|(path, content)| (root.join(path), *content)).collect(),
+            };
+            let config = match fixture.config {
+                Some(config) => toml::from_str(config).map_err(|e| format!("Invalid config of fixture '{}': {}", fixture.name, e))?,
+                None => Config::default(),
+            };
+            let report = UnusedDetector::new(root.to_string_lossy().to_string())
+                .with_config(config)
+                .with_progress(false)
+                .with_file_provider(Some(Arc::new(provider)))
+                .generate_report()?;
+
+            Ok(FixtureOutcome {
+                name: fixture.name.to_string(),
+                expected: fixture.expected.to_string(),
+                actual: golden_report(&report),
+            })
+        })
+        .collect()
+}
+
+/* ============================================================================================== */
+/// The parts of a report that measure accuracy, one line per class: stable across runs, paths
+/// and platforms, so it can be compared with a checked-in golden file
+pub fn golden_report(report: &UnusedReport) -> String {
+    let unused: BTreeSet<&str> = report.unused_classes.iter().map(|class| class.name.as_str()).collect();
+    let used: BTreeSet<&str> = report.used_classes.iter().map(|class| class.name.as_str()).collect();
+
+    let mut golden = format!("total {}\n", report.total_classes);
+    for name in &unused {
+        golden.push_str(&format!("unused {}\n", name));
+    }
+    for name in &used {
+        golden.push_str(&format!("used {}\n", name));
+    }
+    golden
+}
+
+/* ============================================================================================== */
+/// Files of a fixture directory that make up the project, rather than its golden report or config
+pub fn is_fixture_project_file(relative: &Path) -> bool {
+    relative != Path::new(GOLDEN_FILE) && relative != Path::new(CONFIG_FILE)
+}
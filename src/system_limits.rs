@@ -0,0 +1,124 @@
+use crate::parallel_processor::DEFAULT_MEMORY_BUDGET_MB;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+// Never squeeze the read-ahead below this, however little memory is free
+const MIN_MEMORY_BUDGET_MB: usize = 32;
+
+/* ============================================================================================== */
+/*               What this process may actually use, as opposed to what the host has             */
+/* ============================================================================================== */
+/// Cores the process can run on, capped by a cgroup v2 CPU quota. A CI container limited to two
+/// CPUs on a 64-core host gets 2, so thread pools don't oversubscribe it.
+pub fn available_cores() -> usize {
+    static CORES: OnceLock<usize> = OnceLock::new();
+    *CORES.get_or_init(|| {
+        let schedulable = std::thread::available_parallelism().map_or(1, |cores| cores.get());
+        cgroup_cpu_limit().map_or(schedulable, |limit| limit.min(schedulable))
+    })
+}
+
+/* ============================================================================================== */
+/// The cgroup v2 memory limit, or the system's available memory when there is none
+pub fn available_memory_bytes() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok().and_then(|content| mem_available(&content));
+    match (cgroup_memory_limit(), meminfo) {
+        (Some(limit), Some(available)) => Some(limit.min(available)),
+        (limit, available) => limit.or(available),
+    }
+}
+
+/* ============================================================================================== */
+/// `DEFAULT_MEMORY_BUDGET_MB`, or a quarter of the available memory on machines where that is less
+pub fn default_memory_budget_mb() -> usize {
+    static BUDGET: OnceLock<usize> = OnceLock::new();
+    *BUDGET.get_or_init(|| {
+        available_memory_bytes()
+            .map(|bytes| (bytes / 4 / (1024 * 1024)) as usize)
+            .map_or(DEFAULT_MEMORY_BUDGET_MB, |quarter| quarter.clamp(MIN_MEMORY_BUDGET_MB, DEFAULT_MEMORY_BUDGET_MB))
+    })
+}
+
+/* ============================================================================================== */
+fn cgroup_cpu_limit() -> Option<usize> {
+    // A limit anywhere up the tree applies
+    cgroup_directories().iter()
+        .filter_map(|directory| cpu_max_cores(&fs::read_to_string(directory.join("cpu.max")).ok()?))
+        .min()
+}
+
+/* ============================================================================================== */
+fn cpu_max_cores(cpu_max: &str) -> Option<usize> {
+    // `cpu.max` is "<quota> <period>" or "max <period>"
+    let mut fields = cpu_max.split_whitespace();
+    let quota: u64 = fields.next()?.parse().ok()?;
+    let period: u64 = fields.next()?.parse().ok().filter(|period| *period > 0)?;
+    Some(quota.div_ceil(period).max(1) as usize)
+}
+
+/* ============================================================================================== */
+fn cgroup_memory_limit() -> Option<u64> {
+    cgroup_directories().iter()
+        .filter_map(|directory| fs::read_to_string(directory.join("memory.max")).ok()?.trim().parse().ok())
+        .min()
+}
+
+/* ============================================================================================== */
+/// The process's own cgroup v2 directory and its ancestors up to the root
+fn cgroup_directories() -> Vec<PathBuf> {
+    let content = fs::read_to_string("/proc/self/cgroup").unwrap_or_default();
+    let Some(own_path) = unified_cgroup_path(&content) else {
+        return Vec::new();
+    };
+
+    let own_directory = Path::new(CGROUP_ROOT).join(own_path.trim_start_matches('/'));
+    own_directory.ancestors()
+        .take_while(|directory| directory.starts_with(CGROUP_ROOT))
+        .map(Path::to_path_buf)
+        .collect()
+}
+
+/* ============================================================================================== */
+fn unified_cgroup_path(proc_cgroup: &str) -> Option<&str> {
+    // The unified hierarchy is the single "0::<path>" line
+    proc_cgroup.lines().find_map(|line| line.strip_prefix("0::"))
+}
+
+/* ============================================================================================== */
+fn mem_available(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|line| line.starts_with("MemAvailable:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/* ============================================================================================== */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_quota_rounds_up_to_whole_cores() {
+        assert_eq!(cpu_max_cores("200000 100000\n"), Some(2));
+        assert_eq!(cpu_max_cores("150000 100000"), Some(2));
+        assert_eq!(cpu_max_cores("5000 100000"), Some(1));
+        assert_eq!(cpu_max_cores("max 100000"), None);
+        assert_eq!(cpu_max_cores("100000 0"), None);
+        assert_eq!(cpu_max_cores(""), None);
+    }
+
+    #[test]
+    fn finds_the_unified_hierarchy() {
+        let proc_cgroup = "12:memory:/docker/abc\n0::/system.slice/ci.service\n";
+        assert_eq!(unified_cgroup_path(proc_cgroup), Some("/system.slice/ci.service"));
+        assert_eq!(unified_cgroup_path("12:memory:/docker/abc\n"), None);
+    }
+
+    #[test]
+    fn reads_available_memory_in_bytes() {
+        let meminfo = "MemTotal:       16384000 kB\nMemFree:         1024000 kB\nMemAvailable:    8192000 kB\n";
+        assert_eq!(mem_available(meminfo), Some(8192000 * 1024));
+        assert_eq!(mem_available("MemTotal: 1 kB\n"), None);
+    }
+}
@@ -1,5 +1,26 @@
-use regex::Regex;
-use std::collections::HashMap;
+use aho_corasick::AhoCorasick;
+use regex::{Regex, RegexSet, RegexSetBuilder};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+use crate::config::NamingConvention;
+
+// JS DOM APIs (and Astro's class:list directive) that assemble class names at runtime.
+// Capture group 1 is the argument expression.
+const CLASS_API_PATTERNS: &[(&str, &str)] = &[
+    ("class_list", r"classList\.(?:add|remove|toggle|replace|contains)\(([^)]*)\)"),
+    ("set_attribute_class", r#"setAttribute\(\s*["']class["']\s*,\s*([^)]*)\)"#),
+    ("class_name_assign", r"className\s*\+?=\s*([^;]*)"),
+    ("astro_class_list", r"class:list=\{([^}]*)"),
+];
+// Besides letters and digits, the characters a Tailwind-style class token is made of: next to a
+// name like `md:flex`, one of these means the hit is part of another class (`lg:md:flex`, `md:flex-1`)
+const TOKEN_CHARS: &str = "_-:[]/.!@%";
+// Hundreds of patterns with several regexes each outgrow regex's default 10 MB program limit
+const COMBINED_SIZE_LIMIT: usize = 256 * 1024 * 1024;
+
+// Inline style attributes and <style>/<mj-style> blocks, stripped from every usage file
+static STYLE_BLOCK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<(?:mj-)?style\b[^>]*>.*?</(?:mj-)?style>").unwrap());
+static STYLE_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)\sstyle\s*=\s*(?:"[^"]*"|'[^']*')"#).unwrap());
 
 pub struct TextProcessor {
     patterns: Vec<(String, Regex)>,
@@ -9,8 +30,19 @@ pub struct TextProcessor {
 pub struct TextMatch {
     pub pattern_name: String,
     pub matched_text: String,
+    pub line: usize,
+    /// 1-based, in characters, as editors count them
+    pub column: usize,
+    /// Bytes from the start of the content
+    pub offset: usize,
+}
+
+/// Where a match starts: 1-based line and character column, and its byte offset in the content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextPosition {
     pub line: usize,
     pub column: usize,
+    pub offset: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -19,6 +51,128 @@ pub struct DynamicPattern {
     pub suffix: String,
     pub pattern: String, // e.g., "type-{}"
     pub matching_classes: Vec<String>, // e.g., ["type-fire", "type-water"]
+    /// Preset families: any file matching this uses every class of the family
+    pub trigger: Option<Regex>,
+}
+
+/// A pattern's usage regexes, compiled once and reused for every file
+pub struct PatternMatcher {
+    trigger: Option<Regex>,
+    /// Interpolations and concatenations that build `prefix…suffix`
+    usage: Vec<Regex>,
+    prefix: String,
+}
+
+impl DynamicPattern {
+    pub fn matcher(&self) -> PatternMatcher {
+        PatternMatcher {
+            trigger: self.trigger.clone(),
+            usage: self.usage_patterns().iter().filter_map(|pattern| Regex::new(pattern).ok()).collect(),
+            prefix: self.prefix.clone(),
+        }
+    }
+
+    /* ========================================================================================== */
+    /// Interpolations and concatenations that build `prefix…suffix`, none for trigger-based patterns
+    fn usage_patterns(&self) -> Vec<String> {
+        if self.trigger.is_some() {
+            return Vec::new();
+        }
+
+        let prefix = regex::escape(&self.prefix);
+        let suffix = regex::escape(&self.suffix);
+        vec![
+            format!(r"{}\$\{{[^}}]*\}}{}", prefix, suffix), // template literal
+            format!(r"{}\{{[^}}]*\}}{}", prefix, suffix), // string interpolation
+            format!(r"{}['`][^'`]*['`]{}", prefix, suffix), // template strings
+            format!(r#"["'`]{}\$\{{.*?\}}{}["'`]"#, prefix, suffix), // variable interpolation
+            // Concatenation like "type-" + variable + suffix
+            format!(r#"["'`]{}["'`]\s*\+\s*\w+\s*\+\s*["'`]{}["'`]"#, prefix, suffix),
+            format!(r#"["'`]{}["'`]\s*\+\s*\w+"#, prefix),
+        ]
+    }
+}
+
+impl PatternMatcher {
+    /// `assembled_arguments` comes from `TextProcessor::assembled_class_arguments` for the same content
+    pub fn is_used_in(&self, content: &str, assembled_arguments: &[String]) -> bool {
+        if let Some(trigger) = &self.trigger {
+            return trigger.is_match(content);
+        }
+
+        self.usage.iter().any(|regex| regex.is_match(content))
+            || assembled_arguments.iter().any(|argument| argument.contains(&self.prefix))
+    }
+}
+
+/// All patterns of a run behind one Aho-Corasick automaton over their literal prefixes and one
+/// `RegexSet` over preset triggers, so each file is scanned once whatever the number of patterns.
+/// Every usage regex contains its prefix, so only patterns whose prefix occurs run their own regexes.
+pub struct PatternSet {
+    matchers: Vec<PatternMatcher>,
+    prefixes: AhoCorasick,
+    /// Index in `prefixes` -> pattern index
+    prefix_owners: Vec<usize>,
+    /// `None` when the combined triggers outgrow the size limit, each is then checked on its own
+    triggers: Option<RegexSet>,
+    trigger_owners: Vec<usize>,
+}
+
+impl PatternSet {
+    pub fn new(patterns: &[DynamicPattern]) -> Self {
+        let (triggered, prefixed): (Vec<_>, Vec<_>) = patterns.iter().enumerate()
+            .partition(|(_, pattern)| pattern.trigger.is_some());
+
+        let triggers = RegexSetBuilder::new(triggered.iter().filter_map(|(_, pattern)| pattern.trigger.as_ref()).map(Regex::as_str))
+            .size_limit(COMBINED_SIZE_LIMIT)
+            .build()
+            .ok();
+
+        Self {
+            matchers: patterns.iter().map(DynamicPattern::matcher).collect(),
+            prefixes: AhoCorasick::new(prefixed.iter().map(|(_, pattern)| &pattern.prefix)).unwrap(),
+            prefix_owners: prefixed.iter().map(|(index, _)| *index).collect(),
+            triggers,
+            trigger_owners: triggered.iter().map(|(index, _)| *index).collect(),
+        }
+    }
+
+    /* ========================================================================================== */
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    /* ========================================================================================== */
+    /// Indexes of the patterns `content` uses, ascending; same answers as `PatternMatcher::is_used_in` per pattern
+    pub fn used_patterns(&self, content: &str, assembled_arguments: &[String]) -> Vec<usize> {
+        let mut used: Vec<usize> = match &self.triggers {
+            Some(triggers) => triggers.matches(content).into_iter().map(|index| self.trigger_owners[index]).collect(),
+            None => self.trigger_owners.iter().copied().filter(|index| self.matchers[*index].is_used_in(content, assembled_arguments)).collect(),
+        };
+
+        // An assembled classList argument containing the prefix is enough on its own
+        used.extend(assembled_arguments.iter()
+            .flat_map(|argument| self.prefixes.find_overlapping_iter(argument.as_str()))
+            .map(|found| self.prefix_owners[found.pattern().as_usize()]));
+
+        let mut candidates: Vec<usize> = self.prefixes.find_overlapping_iter(content)
+            .map(|found| self.prefix_owners[found.pattern().as_usize()])
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        used.extend(candidates.into_iter()
+            .filter(|index| self.matchers[*index].usage.iter().any(|regex| regex.is_match(content))));
+
+        used.sort_unstable();
+        used.dedup();
+        used
+    }
+}
+
+impl Default for TextProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TextProcessor {
@@ -35,11 +189,20 @@ impl TextProcessor {
         Ok(self)
     }
 
+    /* ========================================================================================== */
+    pub fn with_class_api_patterns(mut self) -> Self {
+        for (name, pattern) in CLASS_API_PATTERNS {
+            // Patterns are static, so compilation can't fail at runtime
+            self.patterns.push((name.to_string(), Regex::new(pattern).unwrap()));
+        }
+        self
+    }
+
     /* ========================================================================================== */
     pub fn process_content(&self, content: &str) -> Vec<TextMatch> {
         let mut matches = Vec::new();
         
-        for (line_num, line) in content.lines().enumerate() {
+        for (line_num, (line_offset, line)) in lines_with_offsets(content).enumerate() {
             if self.is_ignored_line(line) {
                 continue;
             }
@@ -47,11 +210,13 @@ impl TextProcessor {
             for (pattern_name, regex) in &self.patterns {
                 for cap in regex.captures_iter(line) {
                     if let Some(matched) = cap.get(1) {
+                        let position = TextPosition::in_line(line_num + 1, line, line_offset, matched.start());
                         matches.push(TextMatch {
                             pattern_name: pattern_name.clone(),
                             matched_text: matched.as_str().to_string(),
-                            line: line_num + 1,
-                            column: matched.start(),
+                            line: position.line,
+                            column: position.column,
+                            offset: position.offset,
                         });
                     }
                 }
@@ -68,6 +233,45 @@ impl TextProcessor {
             .any(|word| word == target_word)
     }
 
+    /* ========================================================================================== */
+    pub fn word_set<'a>(&self, content: &'a str) -> HashSet<&'a str> {
+        // Tokenized once so many names can be checked against the same file
+        content
+            .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+            .filter(|word| !word.is_empty())
+            .collect()
+    }
+
+    /* ========================================================================================== */
+    /// Words split the same way as `word_set`, with the byte index each one starts at
+    pub fn word_indices<'a>(&self, content: &'a str) -> Vec<(usize, &'a str)> {
+        let mut words = Vec::new();
+        let mut start = None;
+        for (index, c) in content.char_indices() {
+            let is_word_char = c.is_alphanumeric() || c == '_' || c == '-';
+            match (is_word_char, start) {
+                (true, None) => start = Some(index),
+                (false, Some(word_start)) => {
+                    words.push((word_start, &content[word_start..index]));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(word_start) = start {
+            words.push((word_start, &content[word_start..]));
+        }
+        words
+    }
+
+    /* ========================================================================================== */
+    pub fn count_exact_words(&self, content: &str, target_word: &str) -> usize {
+        content
+            .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+            .filter(|word| *word == target_word)
+            .count()
+    }
+
     /* ========================================================================================== */
     pub fn detect_dynamic_patterns(&self, class_names: &[String]) -> Vec<DynamicPattern> {
         let mut pattern_groups: HashMap<String, Vec<String>> = HashMap::new();
@@ -93,25 +297,81 @@ impl TextProcessor {
     }
 
     /* ========================================================================================== */
+    pub fn detect_convention_patterns(&self, class_names: &[String], convention: NamingConvention) -> Vec<DynamicPattern> {
+        match convention {
+            NamingConvention::None => self.detect_dynamic_patterns(class_names),
+            NamingConvention::Bem | NamingConvention::Suit => self.detect_modifier_families(class_names, convention),
+        }
+    }
+
+    /* ========================================================================================== */
+    /// Convenience for a single file; compile a `PatternMatcher` once when checking many
     pub fn find_pattern_usage(&self, content: &str, pattern: &DynamicPattern) -> bool {
-        // Search for various forms of the pattern
-        let search_patterns = vec![
-        format!(r"{}\$\{{[^}}]*\}}{}", regex::escape(&pattern.prefix), regex::escape(&pattern.suffix)), // template literal
-        format!(r"{}\{{[^}}]*\}}{}", regex::escape(&pattern.prefix), regex::escape(&pattern.suffix)), // string interpolation
-        format!(r"{}['`][^'`]*['`]{}", regex::escape(&pattern.prefix), regex::escape(&pattern.suffix)), // template strings
-        format!(r#"["'`]{}\$\{{.*?\}}{}["'`]"#, regex::escape(&pattern.prefix), regex::escape(&pattern.suffix)), // variable interpolation
-    ];
-        
-        for search_pattern in search_patterns {
-            if let Ok(regex) = Regex::new(&search_pattern) {
-                if regex.is_match(content) {
-                    return true;
-                }
+        pattern.matcher().is_used_in(content, &self.assembled_class_arguments(content))
+    }
+
+    /* ========================================================================================== */
+    /// Arguments of classList/setAttribute/className calls that build the class at runtime.
+    /// The same for every pattern, so collected once per file.
+    pub fn assembled_class_arguments(&self, content: &str) -> Vec<String> {
+        self.process_content(content)
+            .into_iter()
+            .filter(|text_match| CLASS_API_PATTERNS.iter().any(|(name, _)| *name == text_match.pattern_name))
+            .map(|text_match| text_match.matched_text)
+            // Only assembled arguments count, literal class names are handled by exact matching
+            .filter(|argument| argument.contains('+') || argument.contains("${") || argument.contains("dataset"))
+            .collect()
+    }
+
+    /* ========================================================================================== */
+    pub fn strip_style_sections(&self, content: &str) -> String {
+        // Inline style attributes and <style>/<mj-style> blocks hold declarations, not class usage.
+        // Newlines are kept so line numbers stay valid for later passes.
+        let keep_newlines = |caps: &regex::Captures| -> String {
+            caps[0].chars().filter(|c| *c == '\n').collect()
+        };
+
+        let without_blocks = STYLE_BLOCK.replace_all(content, keep_newlines);
+        STYLE_ATTRIBUTE.replace_all(&without_blocks, keep_newlines).into_owned()
+    }
+
+    /* ========================================================================================== */
+    fn detect_modifier_families(&self, class_names: &[String], convention: NamingConvention) -> Vec<DynamicPattern> {
+        let mut families: HashMap<String, Vec<String>> = HashMap::new();
+
+        for class_name in class_names {
+            if let Some(prefix) = self.extract_modifier_prefix(class_name, convention) {
+                families.entry(prefix).or_default().push(class_name.clone());
             }
         }
-        
-        // Also check for direct string concatenation patterns
-        self.find_string_concatenation_usage(content, pattern)
+
+        // A single template-built modifier is still a family, so no minimum size here
+        families
+            .into_iter()
+            .map(|(prefix, classes)| DynamicPattern {
+                pattern: format!("{}*", prefix),
+                prefix,
+                suffix: String::new(),
+                matching_classes: classes,
+                trigger: None,
+            })
+            .collect()
+    }
+
+    /* ========================================================================================== */
+    fn extract_modifier_prefix(&self, class_name: &str, convention: NamingConvention) -> Option<String> {
+        // Both BEM and SUIT put modifiers after the last "--", e.g. card__title--large or Button--primary
+        if let Some(modifier_pos) = class_name.rfind("--")
+            && modifier_pos > 0 {
+            return Some(class_name[..modifier_pos + 2].to_string());
+        }
+
+        // SUIT state classes (is-active, is-open) are always toggled at runtime
+        if convention == NamingConvention::Suit && class_name.starts_with("is-") {
+            return Some("is-".to_string());
+        }
+
+        None
     }
 
     /* ========================================================================================== */
@@ -186,35 +446,84 @@ impl TextProcessor {
                 suffix,
                 pattern,
                 matching_classes: classes,
+                trigger: None,
             })
         } else {
             None
         }
     }
 
-    /* ========================================================================================== */
-    fn find_string_concatenation_usage(&self, content: &str, pattern: &DynamicPattern) -> bool {
-        // Look for patterns like: "type-" + variable + suffix
-        let concat_patterns = vec![
-            format!(r#"["'`]{}\$\{{[^}}]*\}}{}["'`]"#, regex::escape(&pattern.prefix), regex::escape(&pattern.suffix)),
-            format!(r#"["'`]{}["'`]\s*\+\s*\w+\s*\+\s*["'`]{}["'`]"#, regex::escape(&pattern.prefix), regex::escape(&pattern.suffix)),
-            format!(r#"["'`]{}["'`]\s*\+\s*\w+"#, regex::escape(&pattern.prefix)),
-        ];
-        
-        for concat_pattern in concat_patterns {
-            if let Ok(regex) = Regex::new(&concat_pattern) {
-                if regex.is_match(content) {
-                    return true;
-                }
-            }
-        }
-        
-        false
-    }
-
     /* ========================================================================================== */
     fn is_ignored_line(&self, line: &str) -> bool {
         let trimmed = line.trim();
         trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.is_empty()
     }
-}
\ No newline at end of file
+}
+impl TextPosition {
+    /// Byte `index` of `line`, which is line `line_number` and starts at byte `line_offset`
+    pub fn in_line(line_number: usize, line: &str, line_offset: usize, index: usize) -> Self {
+        Self {
+            line: line_number,
+            column: line[..index].chars().count() + 1,
+            offset: line_offset + index,
+        }
+    }
+}
+
+/* ============================================================================================== */
+/// The lines `str::lines` yields, each with the byte offset it starts at
+pub fn lines_with_offsets(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content.split_inclusive('\n').scan(0, |offset, raw| {
+        let start = *offset;
+        *offset += raw.len();
+        let line = raw.strip_suffix('\n').map_or(raw, |line| line.strip_suffix('\r').unwrap_or(line));
+        Some((start, line))
+    })
+}
+
+/* ============================================================================================== */
+/// Line and column of byte `offset`, for matches found outside a line-by-line scan
+pub fn position_at(content: &str, offset: usize) -> TextPosition {
+    let before = &content[..offset];
+    let line_offset = before.rfind('\n').map_or(0, |newline| newline + 1);
+    TextPosition::in_line(before.matches('\n').count() + 1, &content[line_offset..], line_offset, offset - line_offset)
+}
+
+/* ============================================================================================== */
+/// Byte indexes where `token` appears whole, not inside a longer class token; for names the
+/// word tokenizer would split, like `md:flex` or `w-[10px]`
+pub fn token_indices<'a>(content: &'a str, token: &'a str) -> impl Iterator<Item = usize> + 'a {
+    content.match_indices(token)
+        .map(|(index, _)| index)
+        .filter(move |index| is_whole_token(content, *index, index + token.len()))
+}
+
+/* ============================================================================================== */
+/// Whether `content[start..end]` is bounded by characters that can't continue a class token
+pub fn is_whole_token(content: &str, start: usize, end: usize) -> bool {
+    let is_token_char = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || TOKEN_CHARS.contains(c));
+    !is_token_char(content[..start].chars().next_back()) && !is_token_char(content[end..].chars().next())
+}
+
+/* ============================================================================================== */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tailwind_names_match_only_whole_tokens() {
+        let content = r#"<div class="lg:md:flex md:flex-1 md:flex"></div>"#;
+        let found: Vec<usize> = token_indices(content, "md:flex").collect();
+        assert_eq!(found, vec![content.rfind("md:flex").unwrap()]);
+        assert_eq!(token_indices("lg:md:flex md:flex-1", "md:flex").count(), 0);
+        assert_eq!(token_indices(r#"class="md:flex""#, "md:flex").count(), 1);
+    }
+
+    #[test]
+    fn whole_token_boundaries() {
+        assert!(is_whole_token("a w-1.5 b", 2, 7));
+        assert!(!is_whole_token("w-1.5x", 0, 5));
+        assert!(!is_whole_token("!w-[10px]", 1, 9));
+        assert!(is_whole_token("'w-[10px]'", 1, 9));
+    }
+}
@@ -10,6 +10,16 @@ pub trait ProgressConfigurable {
     fn with_progress(self, show_progress: bool) -> Self;
 }
 
+pub trait ObserverConfigurable {
+    /// Where progress, warnings and per-class results go; silent unless set
+    fn with_observer(self, observer: std::sync::Arc<dyn crate::observer::AnalysisObserver>) -> Self;
+}
+
+pub trait MemoryBudgetConfigurable {
+    /// Cap on file contents held in memory at once while streaming
+    fn with_memory_budget_mb(self, megabytes: usize) -> Self;
+}
+
 pub trait ProcessorBuilder: ThreadCountConfigurable + Sized {
     fn configure_threads(self, thread_count: Option<usize>) -> Self {
         match thread_count {
@@ -2,388 +2,2121 @@ use crate::css_parser::{CssClass, CssParser};
 use crate::{utils::*, ProcessorBuilder};
 use crate::scanner::FileScanner;
 use crate::file_walker::FileWalker;
-use crate::config::Config;
-use crate::text_processor::{TextProcessor, DynamicPattern};
+use crate::file_provider::FileProvider;
+use crate::diagnostics::Diagnostics;
+use crate::error::TagFinderError;
+use crate::archive::is_archive_path;
+use crate::git_blame::GitBlame;
+use crate::git_diff::AddedLines;
+use crate::config::{Config, OutputConfig, ReportSort, UsagePolicy};
+use crate::scan_filter::ScanFilter;
+use crate::data_file::data_file_text;
+use crate::indented_template::{is_indented_template, template_usage_text};
+use crate::component_file::component_style_text;
+use crate::compound_selector::{element_class_sets, extract_compound_selectors, script_applied_classes, CompoundSelector};
+use crate::output_style::OutputStyle;
+use crate::explanation::{ClassExplanation, ClassVerdict, PatternEvidence, UsageHit};
+use crate::class_index::ClassIndex;
+use crate::css_rules::{extract_rules, CssRule};
+use crate::redundant_rules::{find_redundant_rules, RedundantRule};
+use crate::duplicate_blocks::{find_duplicate_blocks, DuplicateBlock};
+use crate::media_queries::{media_query_usage, MediaQueryUsage};
+use crate::assets::{extract_font_faces, unused_assets, unused_font_faces, used_font_families, FontFace, UnusedAsset};
+use crate::data_attributes::{data_attribute_selectors, used_data_attributes, DataAttributeSelector};
+use crate::co_usage_graph::{record_co_usage, CoUsageGraph};
+use crate::scss_compiler::{source_position, ScssCompiler};
+use crate::scss_interpolation::{interpolated_classes, DynamicDefinition, InterpolatedClasses};
+use crate::owners::OwnerMap;
+use crate::report_filter::ReportFilter;
+use crate::presets::Preset;
+use crate::observer::{AnalysisObserver, AnalysisStage, SilentObserver};
+use crate::text_processor::{lines_with_offsets, token_indices, DynamicPattern, PatternSet, TextPosition, TextProcessor};
 use crate::parallel_processor::ParallelProcessor;
-use crate::traits::{ConfigConfigurable, ThreadCountConfigurable};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use crate::content_cache::ContentCache;
+use crate::system_limits::default_memory_budget_mb;
+use crate::import_resolver::ImportResolver;
+use crate::bundle_metafile::BundleMetafile;
+use crate::sampling::{FileSampler, SampleInfo, UnusedEstimate};
+use crate::deadline::{Deadline, ReportStatus, TimeoutInfo};
+use crate::version_info::REPORT_SCHEMA_VERSION;
+use crate::decision_log::{decision_key, ClassDecision, DecisionEvidence, DecisionStatus};
+use crate::traits::{ConfigConfigurable, MemoryBudgetConfigurable, ObserverConfigurable, ProgressConfigurable, ThreadCountConfigurable};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Classification per definition site, keyed by `decision_key`
+type DecisionMap = HashMap<String, ClassDecision>;
+
+// (unused, used, by_file, decisions)
+type UsageAnalysis = (Vec<CssClass>, Vec<CssClass>, HashMap<String, Vec<UnusedClass>>, DecisionMap);
+
+// (stylesheets to extract classes from, SCSS source of the compiled ones by file)
+type CompiledStylesheets = (Vec<(PathBuf, String)>, HashMap<String, String>);
+
+// (stylesheets with their content, usage files to stream)
+type ProjectFiles = (Vec<(PathBuf, String)>, Vec<PathBuf>);
 
 pub struct UnusedDetector {
     directory: String,
     thread_count: Option<usize>,
+    /// Threads reading files; `thread_count` covers parsing and matching
+    io_thread_count: Option<usize>,
     config: Option<Config>,
+    scan_filter: ScanFilter,
+    entry_points: Vec<String>,
+    bundle_metafile: Option<String>,
+    sampler: Option<FileSampler>,
+    memory_budget_mb: usize,
+    file_list: Option<Vec<PathBuf>>,
+    file_provider: Option<Arc<dyn FileProvider>>,
+    min_age: Option<Duration>,
+    /// Ref whose merge base splits unused classes into newly added and legacy ones
+    diff_base: Option<String>,
+    with_authors: bool,
+    /// Count class pairs per element for `CoUsageGraph`, skipped otherwise since it grows quadratically
+    with_co_usage: bool,
+    /// Narrows the finished report to some files or class prefixes
+    report_filter: ReportFilter,
+    /// Wall-clock limit of `generate_report`; usage files not read by then are skipped
+    timeout: Option<Duration>,
+    show_progress: bool,
+    observer: Arc<dyn AnalysisObserver>,
+    /// Built on first use and shared by every pass, so an archive is only unpacked once
+    walker: OnceLock<FileWalker>,
+}
+
+// Usage collected while streaming, one per analyzer thread until merged
+#[derive(Default)]
+struct UsageTally {
+    /// Usage files left unread because the time limit passed
+    skipped_files: usize,
+    /// Class name -> usage files containing it
+    exact_files: HashMap<String, Vec<String>>,
+    /// Class names found in script modules the bundler never included
+    dead_code_names: HashSet<String>,
+    /// Class names found in at least one file without a weak usage policy
+    strong_names: HashSet<String>,
+    /// Pattern index -> (is weak, a file using it); strong files win, then the smallest path, so reruns agree
+    pattern_files: HashMap<usize, (bool, String)>,
+    /// Time spent matching all patterns in one pass, shared evenly between them in decisions
+    pattern_time: Duration,
+    /// Compound selector indexes whose classes appear together on some element
+    co_applied: HashSet<usize>,
+    /// Class names added through classList calls, which may land on any element
+    script_applied: HashSet<String>,
+    /// `data-*` attribute names set, read or queried in usage files
+    data_attributes: HashSet<String>,
+    /// Sorted class pair -> elements carrying both, only with `with_co_usage`
+    co_usage: HashMap<(String, String), usize>,
+    /// Every hit of the class `explain_class` asked for
+    explained: ExplainedUsage,
+}
+
+// Where the explained class appears, which the tally otherwise only records per file
+#[derive(Default)]
+struct ExplainedUsage {
+    usage_hits: Vec<UsageHit>,
+    ignored_hits: Vec<UsageHit>,
+    /// Hits in script modules the bundler never included
+    dead_code_hits: Vec<UsageHit>,
+    /// Pattern index -> every usage file using it
+    pattern_files: HashMap<usize, Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UnusedClass {
     pub class: CssClass,
     pub is_unused: bool,
+    #[serde(default)]
+    pub low_confidence: bool,
+    /// Only referenced from script modules the bundler never included
+    #[serde(default)]
+    pub dead_code_only: bool,
+    /// Unused, but the definition is younger than `--min-age`
+    #[serde(default)]
+    pub recent: bool,
+    /// Unused, but the definition wasn't added since `--diff-base`
+    #[serde(default)]
+    pub legacy: bool,
+    /// No usage found before `--timeout` stopped the analysis
+    #[serde(default)]
+    pub not_analyzed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CategoryOnlyUsage {
+    pub class: CssClass,
+    pub categories: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UnusedReport {
+    /// `REPORT_SCHEMA_VERSION` of the build that wrote the report
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
     pub total_classes: usize,
     pub unused_classes: Vec<CssClass>,
     pub used_classes: Vec<CssClass>,
     pub by_file: HashMap<String, Vec<UnusedClass>>,
+    /// Unused classes matching a configured stop word, kept out of `unused_classes`
+    #[serde(default)]
+    pub low_confidence_classes: Vec<CssClass>,
+    /// Classes whose only usages are in un-bundled script modules
+    #[serde(default)]
+    pub dead_code_classes: Vec<CssClass>,
+    /// Unused classes defined less than `--min-age` ago, kept out of `unused_classes`
+    #[serde(default)]
+    pub recent_classes: Vec<CssClass>,
+    /// Unused classes whose definition line wasn't added since `--diff-base`, kept out of `unused_classes`
+    #[serde(default)]
+    pub legacy_classes: Vec<CssClass>,
+    /// No usage in the files scanned before `--timeout` stopped the analysis, so neither used nor unused
+    #[serde(default)]
+    pub not_analyzed_classes: Vec<CssClass>,
+    /// Last git author of each unused class's definition line, keyed by `decision_key`
+    #[serde(default)]
+    pub authors: HashMap<String, String>,
+    /// Owner from `[owners]` of each stylesheet matching one of its globs
+    #[serde(default)]
+    pub owners: HashMap<String, String>,
+    /// Unused classes grouped by the owner of their stylesheet; unowned ones are left out
+    #[serde(default)]
+    pub by_owner: BTreeMap<String, Vec<CssClass>>,
+    /// Used classes whose only usages are in weak categories such as tests or docs
+    #[serde(default)]
+    pub category_only_classes: Vec<CategoryOnlyUsage>,
+    /// Compound selectors like `.promo.banner--xmas` whose classes are used, but never on one element
+    #[serde(default)]
+    pub dead_combinations: Vec<CompoundSelector>,
+    /// Rules of used classes whose declarations are all overridden by a later rule for the same selector
+    #[serde(default)]
+    pub redundant_rules: Vec<RedundantRule>,
+    /// Rules repeating the same declarations, candidates for a shared class or mixin
+    #[serde(default)]
+    pub duplicate_blocks: Vec<DuplicateBlock>,
+    /// Every `@media` condition with its rules; `unused` ones only style unused classes
+    #[serde(default)]
+    pub media_queries: Vec<MediaQueryUsage>,
+    /// `[data-*]` attribute selectors whose attribute no usage file sets, reads or queries
+    #[serde(default)]
+    pub unused_data_attributes: Vec<DataAttributeSelector>,
+    /// `@font-face` families no font declaration or font stack variable names
+    #[serde(default)]
+    pub unused_font_faces: Vec<FontFace>,
+    /// `url(…)` files referenced only from rules targeting unused classes and from unused font faces
+    #[serde(default)]
+    pub unused_assets: Vec<UnusedAsset>,
+    /// SCSS selectors built from values only the compiler knows, e.g. `.btn-#{$variant}` in a mixin
+    #[serde(default)]
+    pub dynamic_definitions: Vec<DynamicDefinition>,
+    /// Classes applied together on one element, only when requested with `with_co_usage_graph`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co_usage_graph: Option<CoUsageGraph>,
+    /// Final status and evidence for every class, sorted by definition site
+    #[serde(default)]
+    pub decisions: Vec<ClassDecision>,
+    /// Set when only a sample of the usage files was analyzed
+    #[serde(default)]
+    pub sample: Option<SampleInfo>,
+    /// Paths left out because they couldn't be read
+    #[serde(default)]
+    pub diagnostics: Diagnostics,
+    /// Order of `unused_classes`, each file's classes and the files in detailed and by-file output
+    #[serde(default)]
+    pub sort: ReportSort,
+    /// Set when the report was narrowed to some files or class prefixes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<ReportFilter>,
+    /// `timed_out` when `--timeout` stopped the analysis; the report is then partial
+    #[serde(default)]
+    pub status: ReportStatus,
+    /// How far a timed out analysis got
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<TimeoutInfo>,
+}
+
+// Reports written before the version was recorded
+fn first_schema_version() -> u32 {
+    1
 }
 
 impl UnusedDetector {
     pub fn new(directory: String) -> Self {
         Self { 
+            scan_filter: ScanFilter::default().with_root(&directory),
             directory,
             thread_count: None,
+            io_thread_count: None,
             config: None,
+            entry_points: Vec::new(),
+            bundle_metafile: None,
+            sampler: None,
+            memory_budget_mb: default_memory_budget_mb(),
+            file_list: None,
+            file_provider: None,
+            min_age: None,
+            diff_base: None,
+            with_authors: false,
+            with_co_usage: false,
+            report_filter: ReportFilter::default(),
+            timeout: None,
+            show_progress: true,
+            observer: Arc::new(SilentObserver),
+            walker: OnceLock::new(),
         }
     }
 
     /* ========================================================================================== */
-    pub fn generate_report(&self) -> Result<UnusedReport, Box<dyn std::error::Error>> {
-        // Single walker for all operations
-        let mut walker = FileWalker::new(self.directory.clone()).configure_threads(self.thread_count);
+    pub fn with_sample(mut self, sampler: Option<FileSampler>) -> Self {
+        self.sampler = sampler;
+        self
+    }
 
-        if let Some(config) = &self.config {
-            walker = walker.with_config(config.clone());
-        }
+    /* ========================================================================================== */
+    pub fn with_file_provider(mut self, file_provider: Option<Arc<dyn FileProvider>>) -> Self {
+        // Files come from the embedder's storage; `directory` is the root their paths live under
+        self.file_provider = file_provider;
+        self
+    }
 
-        // Get files and split
-        let all_files_with_content = walker.walk_with_content_parallel()?;
-        let css_files_with_content = self.filter_css_files(all_files_with_content.clone());
+    /* ========================================================================================== */
+    pub fn with_file_list(mut self, file_list: Option<Vec<PathBuf>>) -> Self {
+        // e.g. the output of `git ls-files`, analyzed instead of walking the directory
+        self.file_list = file_list;
+        self
+    }
 
-        // Extract classes
-        let classes = self.extract_classes(css_files_with_content)?;
+    /* ========================================================================================== */
+    pub fn with_bundle_metafile(mut self, metafile_path: Option<String>) -> Self {
+        self.bundle_metafile = metafile_path;
+        self
+    }
 
-        // Detect dynamic patterns
-        let dynamic_patterns = self.detect_patterns(&classes);
+    /* ========================================================================================== */
+    pub fn with_io_threads(mut self, count: Option<usize>) -> Self {
+        self.io_thread_count = count;
+        self
+    }
 
-        // Check usage status
-        let (unused_classes, used_classes, by_file) = self.analyze_class_usage(&classes, all_files_with_content, &dynamic_patterns)?;
+    /* ========================================================================================== */
+    pub fn with_min_age(mut self, min_age: Option<Duration>) -> Self {
+        self.min_age = min_age;
+        self
+    }
 
-        Ok(UnusedReport {
-            total_classes: classes.len(),
-            unused_classes,
-            used_classes,
-            by_file,
-        })
+    /* ========================================================================================== */
+    pub fn with_diff_base(mut self, diff_base: Option<String>) -> Self {
+        // e.g. `origin/main`, so CI fails on debt a branch introduces rather than on what it inherited
+        self.diff_base = diff_base;
+        self
     }
 
     /* ========================================================================================== */
-    fn filter_css_files(&self, files_with_content: Vec<(PathBuf, String)>) -> Vec<(PathBuf, String)> {
-        if let Some(config) = &self.config {
-            files_with_content
-                .into_iter()
-                .filter(|(path, _)| config.is_css_file(path))
-                .collect()
-                
-        } else {
-            // Fallback to default CSS extensions if no config
-            files_with_content
-                .into_iter()
-                .filter(|(path, _)| {
-                    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                        matches!(ext, "css" | "scss")
-                    } else {
-                        false
-                    }
-                })
-                .collect()
-        }
+    pub fn with_authors(mut self, with_authors: bool) -> Self {
+        // Blames each unused class's definition line, for handing cleanup to whoever wrote it
+        self.with_authors = with_authors;
+        self
     }
 
     /* ========================================================================================== */
-    fn extract_classes(&self, files_with_content: Vec<(PathBuf, String)>) -> Result<Vec<CssClass>, Box<dyn std::error::Error>> {
-        println!("🔍 Extracting CSS classes...");
-        let css_parser = CssParser::new()
-            .with_thread_count(self.thread_count.unwrap_or(num_cpus::get()));
-        let classes = css_parser.extract_classes_parallel(files_with_content)?;
-        println!("📊 Found {} CSS classes. Checking usage...", classes.len());
-        Ok(classes)
+    pub fn with_co_usage_graph(mut self, with_co_usage: bool) -> Self {
+        self.with_co_usage = with_co_usage;
+        self
     }
 
     /* ========================================================================================== */
-    fn detect_patterns(&self, classes: &[CssClass]) -> Vec<DynamicPattern> {
-        println!("🔍 Detecting dynamic patterns...");
-        let processor = TextProcessor::new();
-        let class_names: Vec<String> = classes.iter().map(|c| c.name.clone()).collect();
-        let patterns = processor.detect_dynamic_patterns(&class_names);
-        
-        if !patterns.is_empty() {
-            println!("📊 Found {} dynamic patterns:", patterns.len());
-            for pattern in &patterns {
-                println!("   {} (covers {} classes)", pattern.pattern, pattern.matching_classes.len());
-            }
-        }
-        
-        patterns
+    pub fn with_report_filter(mut self, report_filter: ReportFilter) -> Self {
+        // Applied after classification, so usage outside the filtered area still counts
+        self.report_filter = report_filter;
+        self
     }
 
     /* ========================================================================================== */
-    fn analyze_class_usage(
-        &self,
-        classes: &[CssClass],
-        all_files_with_content: Vec<(PathBuf, String)>,
-        dynamic_patterns: &[DynamicPattern],
-    ) -> Result<(Vec<CssClass>, Vec<CssClass>, HashMap<String, Vec<UnusedClass>>), Box<dyn std::error::Error>> {
-        // Step 1: Check exact matches
-        let (used_classes, potentially_unused_classes) = self.check_exact_matches(classes, &all_files_with_content)?;
-
-        // Step 2: Check dynamic patterns for remaining classes
-        if !potentially_unused_classes.is_empty() && !dynamic_patterns.is_empty() {
-            let files_arc = Arc::new(all_files_with_content);
-            let patterns_arc = Arc::new(dynamic_patterns.to_vec());
-
-            let (final_used_classes, unused_classes) = self.check_dynamic_patterns(
-                used_classes, 
-                potentially_unused_classes, 
-                &files_arc, 
-                &patterns_arc
-            )?;
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
 
-            let by_file = self.build_by_file_structure(&final_used_classes, &unused_classes);
-            println!("✅ Analysis complete!");
-            Ok((unused_classes, final_used_classes, by_file))
-        } else {
-            let by_file = self.build_by_file_structure(&used_classes, &potentially_unused_classes);
-            println!("✅ Analysis complete!");
-             Ok((potentially_unused_classes, used_classes, by_file))
-        }
-        
-        
+    /* ========================================================================================== */
+    pub fn with_entry_points(mut self, entry_points: Vec<String>) -> Self {
+        self.entry_points = entry_points;
+        self
     }
 
     /* ========================================================================================== */
-    fn check_exact_matches(
-        &self,
-        classes: &[CssClass],
-        files_with_content: &[(PathBuf, String)],
-    ) -> Result<(Vec<CssClass>, Vec<CssClass>), Box<dyn std::error::Error>> {
-        println!("🔍 Analyzing {} classes using {} threads...", classes.len(), get_thread_count_or_default(self.thread_count));
+    pub fn generate_report(&self) -> Result<UnusedReport, Box<dyn std::error::Error>> {
+        let deadline = self.timeout.map(Deadline::starting_now);
+        self.check_directory()?;
+        let (css_files_with_content, usage_paths) = self.read_stylesheets_and_list_usage()?;
 
-        let processor = ParallelProcessor::new().configure_threads(self.thread_count);
+        let compound_selectors: Vec<CompoundSelector> = css_files_with_content.iter()
+            .flat_map(|(path, content)| extract_compound_selectors(path, content))
+            .collect();
+        let css_rules: Vec<CssRule> = css_files_with_content.iter()
+            .flat_map(|(path, content)| extract_rules(path, content))
+            .collect();
+        let font_faces: Vec<FontFace> = css_files_with_content.iter()
+            .flat_map(|(path, content)| extract_font_faces(path, content))
+            .collect();
+        let used_font_families: HashSet<String> = css_files_with_content.iter()
+            .flat_map(|(_, content)| used_font_families(content))
+            .collect();
 
-        println!("   Step 1: Checking exact matches...");
+        let (classes, dynamic_definitions) = self.defined_classes(css_files_with_content, &css_rules)?;
 
-        let files_arc = Arc::new(files_with_content.to_vec());
-        let exact_results = processor.process(
-            classes.to_vec(), 
-            |class| -> Result<(CssClass, bool), Box<dyn std::error::Error + Send + Sync>> {
-                let is_unused = self.is_class_unused_exact(class, &files_arc)?;
-                Ok((class.clone(), is_unused))
-            },
-            "Analyzing exact matches for"
-        )?;
+        // Detect dynamic patterns
+        let dynamic_patterns = self.detect_patterns(&classes)?;
+
+        // Stylesheets always stay in, only usage files are sampled
+        let (usage_paths, sample) = self.sample_usage_files(usage_paths);
+
+        // Check usage status
+        let usage_file_count = usage_paths.len();
+        let tally = self.scan_usage(&classes, &dynamic_patterns, &compound_selectors, usage_paths, deadline.as_ref(), None)?;
+        let (unused_classes, used_classes, mut by_file, mut decisions) = self.classify_classes(&classes, &dynamic_patterns, &tally);
+
+        // Usage may be in the files left unread, so a class without any is only not analyzed yet
+        let timeout = deadline.filter(|_| tally.skipped_files > 0).map(|deadline| TimeoutInfo {
+            limit_seconds: deadline.limit().as_secs(),
+            scanned_files: usage_file_count - tally.skipped_files,
+            total_files: usage_file_count,
+        });
+        let (not_analyzed_classes, unused_classes) = separate_items_by_condition(unused_classes, |_| timeout.is_some());
+        mark_not_analyzed(&mut by_file, &not_analyzed_classes);
 
-        let (used_classes, potentially_unused_classes) = separate_items_by_condition(
-            exact_results,
-            |(_, is_unused)| !is_unused  // true condition = used classes
+        // Generic names are demoted rather than dropped
+        let (low_confidence_classes, unused_classes) = separate_items_by_condition(
+            unused_classes,
+            |class| self.is_stop_word(&class.name)
+        );
+
+        let (dead_code_classes, unused_classes) = separate_items_by_condition(
+            unused_classes,
+            |class| tally.dead_code_names.contains(&class.name)
         );
+        mark_dead_code_usage(&mut by_file, &dead_code_classes);
 
-        let used_classes: Vec<CssClass> = used_classes.into_iter().map(|(class, _)| class).collect();
-        let potentially_unused_classes: Vec<CssClass> = potentially_unused_classes.into_iter().map(|(class, _)| class).collect();
+        // Fresh classes may be waiting for markup that hasn't landed yet
+        let mut blame = GitBlame::new();
+        let (recent_classes, unused_classes) = self.separate_recent_classes(unused_classes, &mut blame);
+        let (legacy_classes, unused_classes) = self.separate_legacy_classes(unused_classes)?;
+        let authors = self.unused_authors(&unused_classes, &mut blame);
+        mark_recent(&mut by_file, &recent_classes);
+        mark_legacy(&mut by_file, &legacy_classes);
+        let owners = self.stylesheet_owners(&by_file)?;
+        let by_owner = group_by_owner(&unused_classes, &owners);
 
-        println!("   Step 1 complete: {} used via exact match, {} need pattern check", 
-            used_classes.len(), potentially_unused_classes.len());
+        mark_decisions(&mut decisions, &low_confidence_classes, DecisionStatus::LowConfidence);
+        mark_decisions(&mut decisions, &dead_code_classes, DecisionStatus::DeadCodeOnly);
+        mark_decisions(&mut decisions, &recent_classes, DecisionStatus::Recent);
+        mark_decisions(&mut decisions, &legacy_classes, DecisionStatus::Legacy);
+        mark_decisions(&mut decisions, &not_analyzed_classes, DecisionStatus::NotAnalyzed);
+        let category_only_classes = category_only_usage(&used_classes, &decisions);
+        let dead_combinations = dead_combinations(compound_selectors, &used_classes, &tally);
+        let used_names: HashSet<&str> = used_classes.iter().map(|class| class.name.as_str()).collect();
+        let redundant_rules = find_redundant_rules(&css_rules, &used_names);
+        let duplicate_blocks = find_duplicate_blocks(&css_rules);
+        let unused_names: HashSet<&str> = unused_classes.iter().map(|class| class.name.as_str()).collect();
+        let media_queries = media_query_usage(&css_rules, &unused_names);
+        let unused_data_attributes: Vec<DataAttributeSelector> = data_attribute_selectors(&css_rules).into_iter()
+            .filter(|selector| !tally.data_attributes.contains(&selector.attribute))
+            .collect();
+        let unused_font_faces = unused_font_faces(&font_faces, &used_font_families);
+        let unused_assets = unused_assets(&css_rules, &unused_names, &font_faces, &unused_font_faces);
+        let co_usage_graph = self.with_co_usage.then(|| {
+            let defined: Vec<&str> = classes.iter().map(|class| class.name.as_str()).collect();
+            CoUsageGraph::new(&defined, &used_names, &tally.co_usage)
+        });
+        let mut decisions: Vec<ClassDecision> = decisions.into_values().collect();
+        decisions.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)).then_with(|| a.class.cmp(&b.class)));
 
-        Ok((used_classes, potentially_unused_classes))
+        let diagnostics = self.file_walker().diagnostics();
+        if let Some(first) = diagnostics.read_failures.first() {
+            let style = self.output_style();
+            self.observer.on_warning(&style.message("progress.read_failures", &[&diagnostics.read_failures.len(), &first.path, &first.error]));
+        }
+        for decision in &decisions {
+            self.observer.on_class_classified(decision);
+        }
+
+        let mut report = UnusedReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            total_classes: classes.len(),
+            unused_classes,
+            used_classes,
+            by_file,
+            low_confidence_classes,
+            dead_code_classes,
+            recent_classes,
+            legacy_classes,
+            not_analyzed_classes,
+            authors,
+            owners,
+            by_owner,
+            category_only_classes,
+            dead_combinations,
+            redundant_rules,
+            duplicate_blocks,
+            media_queries,
+            unused_data_attributes,
+            unused_font_faces,
+            unused_assets,
+            dynamic_definitions,
+            co_usage_graph,
+            decisions,
+            sample,
+            diagnostics,
+            sort: ReportSort::default(),
+            filter: None,
+            status: if timeout.is_some() { ReportStatus::TimedOut } else { ReportStatus::Complete },
+            timeout,
+        };
+        report.apply_filter(&self.report_filter, &self.directory)?;
+        report.sort_by(self.config.as_ref().map_or_else(ReportSort::default, |config| config.output.sort));
+        Ok(report)
     }
 
     /* ========================================================================================== */
-    fn check_dynamic_patterns(
-        &self,
-        mut used_classes: Vec<CssClass>,
-        potentially_unused_classes: Vec<CssClass>,
-        files_arc: &Arc<Vec<(PathBuf, String)>>,
-        patterns_arc: &Arc<Vec<DynamicPattern>>,
-    ) -> Result<(Vec<CssClass>, Vec<CssClass>), Box<dyn std::error::Error>> {
-        if potentially_unused_classes.is_empty() || patterns_arc.is_empty() {
-            return Ok((used_classes, potentially_unused_classes));
-        }
+    pub fn explain_class(&self, class_name: &str) -> Result<ClassExplanation, Box<dyn std::error::Error>> {
+        self.check_directory()?;
+        let class_name = class_name.trim_start_matches('.');
 
-        println!("   Step 2: Checking dynamic patterns for remaining {} classes...", potentially_unused_classes.len());
-        
-        let processor = ParallelProcessor::new().configure_threads(self.thread_count);
-        
-        let pattern_results = processor.process(
-            potentially_unused_classes,
-            |class| -> Result<(CssClass, bool), Box<dyn std::error::Error + Send + Sync>> {
-                let is_used_via_pattern = self.is_class_unused_dynamic(class, files_arc, patterns_arc)?;
-                Ok((class.clone(), is_used_via_pattern))
-            },
-            "Analyzing dynamic matches for"
-        )?;
+        // The same extraction, scan and classification as `generate_report`, narrowed to one name
+        let (css_files_with_content, usage_paths) = self.read_stylesheets_and_list_usage()?;
+        let css_rules: Vec<CssRule> = css_files_with_content.iter()
+            .flat_map(|(path, content)| extract_rules(path, content))
+            .collect();
+        let (classes, _) = self.defined_classes(css_files_with_content, &css_rules)?;
+        let definitions: Vec<CssClass> = classes.iter()
+            .filter(|class| class.name == class_name)
+            .cloned()
+            .collect();
+        let covering_patterns: Vec<DynamicPattern> = self.detect_patterns(&classes)?
+            .into_iter()
+            .filter(|pattern| pattern.matching_classes.iter().any(|name| name == class_name))
+            .collect();
 
-        let (pattern_used_classes, unused_classes) = separate_items_by_condition(
-            pattern_results,
-            |(_, is_used)| *is_used
-        );
+        let mut tally = self.scan_usage(&definitions, &covering_patterns, &[], usage_paths, None, Some(class_name))?;
+        let (_, _, _, decisions) = self.classify_classes(&definitions, &covering_patterns, &tally);
+        let decision = definitions.first().and_then(|class| decisions.get(&decision_key(class)));
 
-        used_classes.extend(pattern_used_classes.into_iter().map(|(class, _)| class));
-        let unused_classes: Vec<CssClass> = unused_classes.into_iter().map(|(class, _)| class).collect();
+        let verdict = match decision.map(|decision| decision.status) {
+            None => ClassVerdict::NotDefined,
+            Some(DecisionStatus::Used) => ClassVerdict::Used,
+            Some(DecisionStatus::UsedViaPattern) => ClassVerdict::UsedViaPattern,
+            Some(_) if tally.dead_code_names.contains(class_name) => ClassVerdict::DeadCodeOnly,
+            Some(_) => ClassVerdict::Unused,
+        };
 
-        println!("   Step 2 complete: {} used via dynamic pattern, {} remain unused", 
-            used_classes.len(), unused_classes.len());
+        let explained = &mut tally.explained;
+        let patterns = covering_patterns.into_iter().enumerate()
+            .map(|(index, pattern)| PatternEvidence {
+                used_in: explained.pattern_files.remove(&index).unwrap_or_default(),
+                pattern: pattern.pattern,
+            })
+            .collect();
+        let rules = self.describe_rules(class_name, !explained.ignored_hits.is_empty());
 
-        Ok((used_classes, unused_classes))
+        Ok(ClassExplanation {
+            class_name: class_name.to_string(),
+            verdict,
+            used_only_in: decision.map(|decision| decision.used_only_in.clone()).unwrap_or_default(),
+            definitions,
+            usage_hits: std::mem::take(&mut explained.usage_hits),
+            ignored_hits: std::mem::take(&mut explained.ignored_hits),
+            dead_code_hits: std::mem::take(&mut explained.dead_code_hits),
+            patterns,
+            rules,
+        })
     }
 
     /* ========================================================================================== */
-    fn build_by_file_structure(&self, used_classes: &[CssClass], unused_classes: &[CssClass]) -> HashMap<String, Vec<UnusedClass>> {
-        let mut by_file: HashMap<String, Vec<UnusedClass>> = HashMap::new();
-        
-        for class in used_classes {
-            by_file
-                .entry(class.file.clone())
-                .or_default()
-                .push(UnusedClass {
-                    class: class.clone(),
-                    is_unused: false,
-                });
+    pub fn build_index(&self) -> Result<ClassIndex, Box<dyn std::error::Error>> {
+        self.check_directory()?;
+        let raw_files_with_content = self.read_files()?;
+        let css_files_with_content = self.filter_css_files(raw_files_with_content.clone())?;
+        let css_rules: Vec<CssRule> = css_files_with_content.iter()
+            .flat_map(|(path, content)| extract_rules(path, content))
+            .collect();
+        let mut classes = self.extract_classes(css_files_with_content)?;
+        annotate_rule_context(&mut classes, &css_rules, 0);
+
+        let mut index = ClassIndex::new(self.directory.clone());
+        for class in &classes {
+            let entry = index.classes.entry(class.name.clone()).or_default();
+            entry.definitions.push(UsageHit {
+                file: class.file.clone(),
+                line: class.line,
+                column: class.column,
+                offset: class.offset,
+            });
+            if entry.doc.is_none() {
+                entry.doc = class.doc.clone();
+            }
         }
-        
-        for class in unused_classes {
-            by_file
-                .entry(class.file.clone())
-                .or_default()
-                .push(UnusedClass {
-                    class: class.clone(),
-                    is_unused: true,
-                });
+
+        let names: HashSet<&str> = classes.iter().map(|class| class.name.as_str()).collect();
+        let usage_files: Vec<(PathBuf, String)> = raw_files_with_content.into_iter()
+            .filter(|(path, _)| !self.is_css_path(path) && self.scan_filter.usage_policy(path) != UsagePolicy::Ignore)
+            .collect();
+
+        let hits = ParallelProcessor::new()
+            .configure_threads(self.thread_count)
+            .with_progress(self.show_progress)
+            .with_observer(Arc::clone(&self.observer))
+            .with_output_style(self.output_style())
+            .process_flat_map(usage_files, |(path, content)| self.usage_lines(path, content, &names), "progress.indexing_usage")?;
+
+        for (name, hit) in hits {
+            if let Some(class) = index.classes.get_mut(&name) {
+                class.usages.push(hit);
+            }
+        }
+        for class in index.classes.values_mut() {
+            class.definitions.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+            class.usages.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
         }
 
-        by_file
+        Ok(index)
     }
 
     /* ========================================================================================== */
-    fn is_class_unused_exact(&self, class: &CssClass, files_with_content: &Arc<Vec<(PathBuf, String)>>) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-        // First try regular scanning for exact matches (fastest)
+    fn usage_lines(&self, path: &Path, content: &str, names: &HashSet<&str>) -> Vec<(String, UsageHit)> {
+        let processor = TextProcessor::new();
         let scanner = FileScanner::new();
-        let result = scanner.scan(class.name.clone(), files_with_content.to_vec())
-            .map_err(|e| format!("Scanner error: {}", e))?;
-        Ok(result.is_css_only)
-    }
+        let text = self.usage_text(&processor, path, content);
+        // Data files and indented templates are rewritten before matching, so their hits are
+        // looked up in the original lines; everything else keeps its line numbers when stripped
+        let rewritten = self.scan_filter.is_data_file(path) || is_indented_template(path);
+        let usage_words = processor.word_set(&text);
+        let lines = if rewritten { content } else { text.as_str() };
 
-    /* ========================================================================================== */
-    fn is_class_unused_dynamic(&self, class: &CssClass, files_with_content: &Arc<Vec<(PathBuf, String)>>, dynamic_patterns: &Arc<Vec<DynamicPattern>>) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-        for pattern in dynamic_patterns.iter() {
-            if pattern.matching_classes.contains(&class.name) {
-                // Check if the pattern is used in any file
-                let processor = TextProcessor::new();
-                for (_, content) in files_with_content.iter() {
-                    if processor.find_pattern_usage(content, pattern) {
-                        return Ok(true); // Class is used via pattern
-                    }
+        // Names like `md:flex` never come out of the tokenizer whole
+        let special_names: Vec<&str> = names.iter().copied().filter(|name| scanner.contains_special_chars(name)).collect();
+
+        let file = path.to_string_lossy().to_string();
+        let mut hits = Vec::new();
+        for (line_index, (line_offset, line)) in lines_with_offsets(lines).enumerate() {
+            // First occurrence of each name on the line
+            let mut found: BTreeMap<&str, usize> = BTreeMap::new();
+            for (index, word) in processor.word_indices(line) {
+                if names.contains(word) && (!rewritten || usage_words.contains(word)) {
+                    found.entry(word).or_insert(index);
                 }
             }
+            for name in &special_names {
+                if let Some(index) = token_indices(line, name).next() {
+                    found.entry(name).or_insert(index);
+                }
+            }
+            hits.extend(found.into_iter().map(|(name, index)| {
+                (name.to_string(), UsageHit::new(file.clone(), TextPosition::in_line(line_index + 1, line, line_offset, index)))
+            }));
         }
-        Ok(false)
+        hits
     }
-}
 
-impl ThreadCountConfigurable for UnusedDetector {
-    fn with_thread_count(mut self, count: usize) -> Self {
-        self.thread_count = Some(count);
-        self
+    /* ========================================================================================== */
+    fn sample_usage_files(&self, usage_paths: Vec<PathBuf>) -> (Vec<PathBuf>, Option<SampleInfo>) {
+        let Some(sampler) = self.sampler else {
+            return (usage_paths, None);
+        };
+
+        let total_files = usage_paths.len();
+        let sampled: Vec<PathBuf> = usage_paths
+            .into_iter()
+            .filter(|path| sampler.is_sampled(path, &self.directory))
+            .collect();
+
+        let style = self.output_style();
+        self.stage(AnalysisStage::Sampling, style.message("progress.sampling", &[&sampled.len(), &total_files]));
+        let info = sampler.info(sampled.len(), total_files);
+        (sampled, Some(info))
     }
-}
+    /* ========================================================================================== */
+    fn load_bundle_metafile(&self, usage_paths: &[PathBuf]) -> Result<Option<BundleMetafile>, Box<dyn std::error::Error>> {
+        let Some(metafile_path) = &self.bundle_metafile else {
+            return Ok(None);
+        };
 
-impl ConfigConfigurable for UnusedDetector {
-    fn with_config(mut self, config: Config) -> Self {
-        self.config = Some(config);
-        self
+        let metafile = BundleMetafile::from_file(metafile_path)?;
+        let dead_modules = usage_paths.iter().filter(|path| metafile.is_dead_module(path, &self.directory)).count();
+
+        let style = self.output_style();
+        self.stage(AnalysisStage::BundleMetafile, style.message("progress.bundle_modules", &[&metafile.module_count(), &dead_modules]));
+        Ok(Some(metafile))
+    }
+    /* ========================================================================================== */
+    fn read_files(&self) -> Result<Vec<(PathBuf, String)>, Box<dyn std::error::Error>> {
+        // Single walker for all operations
+        self.file_walker().walk_with_content_parallel()
     }
-}
 
-impl UnusedReport {
-    pub fn print_summary(&self) {
-        println!("\n📋 UNUSED CSS CLASSES REPORT");
-        print_header_line(50);
-        println!("Total classes analyzed: {}", self.total_classes);
-        println!("Unused classes: {}", self.unused_classes.len());
-        println!("Used classes: {}", self.used_classes.len());
-        
-        if self.total_classes > 0 {
-            let percentage = (self.unused_classes.len() as f64 / self.total_classes as f64) * 100.0;
-            println!("Unused percentage: {:.1}%", percentage);
+    /* ========================================================================================== */
+    /// Without this, a mistyped path walks nothing and passes as a project without classes
+    fn check_directory(&self) -> Result<(), TagFinderError> {
+        // Archives are opened by the walker, and listed or provided files don't need the directory
+        if self.file_list.is_some() || self.file_provider.is_some() || is_archive_path(&self.directory) {
+            return Ok(());
+        }
+
+        let invalid = |reason: String| TagFinderError::InvalidDirectory { path: self.directory.clone(), reason };
+        let metadata = std::fs::metadata(&self.directory).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => invalid("no such directory".to_string()),
+            _ => invalid(e.to_string()),
+        })?;
+        if !metadata.is_dir() {
+            return Err(invalid("not a directory".to_string()));
         }
+        std::fs::read_dir(&self.directory).map_err(|e| invalid(format!("can't be read ({})", e)))?;
+        Ok(())
     }
+
     /* ========================================================================================== */
-    
-    pub fn print_detailed(&self) {
-        self.print_summary();
-        
-        if self.unused_classes.is_empty() {
-            return;
-        }
-        
-        println!("\n🗑️  UNUSED CLASSES:");
-        print_section_line(30);
-        
-        self.print_unused_classes_by_file();
-        println!("\n💡 TIP: Review these unused classes and consider removing them to clean up your CSS.");
+    fn walk_files(&self) -> Result<(Vec<PathBuf>, Vec<PathBuf>), Box<dyn std::error::Error>> {
+        let files = self.file_walker().walk()?;
+        let (css_paths, usage_paths) = separate_items_by_condition(files, |path| self.is_css_path(path));
+
+        // Usage in ignored categories never counts, so those files aren't read at all
+        let usage_paths = usage_paths
+            .into_iter()
+            .filter(|path| self.scan_filter.usage_policy(path) != UsagePolicy::Ignore)
+            .collect();
+        Ok((css_paths, usage_paths))
+    }
+
+    /* ========================================================================================== */
+    /// Stylesheets are read up front, usage files are only listed to be streamed through the analyzers
+    fn read_stylesheets_and_list_usage(&self) -> Result<ProjectFiles, Box<dyn std::error::Error>> {
+        let (mut css_paths, usage_paths) = self.walk_files()?;
+        // Components are read twice: once for their <style> blocks, then streamed as usage
+        css_paths.extend(usage_paths.iter().filter(|path| self.scan_filter.is_component_file(path)).cloned());
+        let css_files_with_content = self.filter_css_files(self.read_stylesheets(css_paths)?)?;
+        Ok((css_files_with_content, usage_paths))
+    }
+
+    /* ========================================================================================== */
+    /// Classes with the @layer and @scope blocks they're defined in, their first declarations and
+    /// @doc comments, next to the SCSS selectors that could not be expanded
+    fn defined_classes(&self, css_files_with_content: Vec<(PathBuf, String)>, css_rules: &[CssRule]) -> Result<(Vec<CssClass>, Vec<DynamicDefinition>), Box<dyn std::error::Error>> {
+        let (css_files_with_content, scss_sources) = self.compile_stylesheets(css_files_with_content)?;
+        // Uncompiled SCSS: `#{$var}` selectors are expanded where the values are in the file
+        let interpolations: Vec<InterpolatedClasses> = css_files_with_content.iter()
+            .filter(|(path, _)| ScssCompiler::is_compilable(path) && !scss_sources.contains_key(path.to_string_lossy().as_ref()))
+            .map(|(path, content)| interpolated_classes(path, content))
+            .collect();
+        let mut classes = self.extract_classes(css_files_with_content)?;
+        map_to_scss_source(&mut classes, &scss_sources);
+        let (mut classes, dynamic_definitions) = self.apply_interpolations(classes, interpolations);
+        annotate_rule_context(&mut classes, css_rules, self.preview_declarations());
+        Ok((self.drop_excluded_layers(classes), dynamic_definitions))
     }
+
     /* ========================================================================================== */
+    fn separate_recent_classes(&self, unused_classes: Vec<CssClass>, blame: &mut GitBlame) -> (Vec<CssClass>, Vec<CssClass>) {
+        let Some(min_age) = self.min_age else {
+            return (Vec::new(), unused_classes);
+        };
 
-    pub fn print_by_file(&self) {
-        self.print_summary();
-        println!("\n📁 BY FILE BREAKDOWN:");
-        print_section_line(40);
-        
-        let mut files: Vec<_> = self.by_file.keys().collect();
-        files.sort();
-        
-        for file in files {
-            self.print_file_breakdown(file);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        // Without blame (no git, untracked tree) a class counts as old, so nothing slips through silently
+        let recent: HashSet<String> = unused_classes.iter()
+            .filter(|class| blame.line(Path::new(&class.file), class.line)
+                .is_some_and(|line| now.saturating_sub(line.timestamp) < min_age.as_secs()))
+            .map(decision_key)
+            .collect();
+
+        separate_items_by_condition(unused_classes, |class| recent.contains(&decision_key(class)))
+    }
+
+    /* ========================================================================================== */
+    fn separate_legacy_classes(&self, unused_classes: Vec<CssClass>) -> Result<(Vec<CssClass>, Vec<CssClass>), Box<dyn std::error::Error>> {
+        let Some(base) = &self.diff_base else {
+            return Ok((Vec::new(), unused_classes));
+        };
+
+        // Only classes defined on a line the branch added count; an edited line counts as added
+        let added = AddedLines::since(&self.directory, base)?;
+        Ok(separate_items_by_condition(unused_classes, |class| !added.contains(&class.file, class.line)))
+    }
+
+    /* ========================================================================================== */
+    fn unused_authors(&self, unused_classes: &[CssClass], blame: &mut GitBlame) -> HashMap<String, String> {
+        if !self.with_authors {
+            return HashMap::new();
         }
+
+        unused_classes.iter()
+            .filter_map(|class| {
+                let line = blame.line(Path::new(&class.file), class.line)?;
+                Some((decision_key(class), line.author.clone()))
+            })
+            .collect()
     }
+
     /* ========================================================================================== */
+    fn stylesheet_owners(&self, by_file: &HashMap<String, Vec<UnusedClass>>) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+        let Some(config) = self.config.as_ref().filter(|config| !config.owners.is_empty()) else {
+            return Ok(HashMap::new());
+        };
 
-    fn print_unused_classes_by_file(&self) {
-        let mut files: Vec<_> = self.by_file.keys().collect();
-        files.sort();
-        
-        for file in files {
-            let unused_in_file = self.get_unused_classes_in_file(file);
-            
-            if unused_in_file.is_empty() {
-                continue;
+        let owner_map = OwnerMap::from_config(&config.owners)?;
+        Ok(by_file.keys()
+            .filter_map(|file| Some((file.clone(), owner_map.owner_of(&self.directory, file)?.to_string())))
+            .collect())
+    }
+
+    /* ========================================================================================== */
+    fn file_walker(&self) -> &FileWalker {
+        self.walker.get_or_init(|| {
+            let mut walker = FileWalker::new(self.directory.clone())
+                .configure_threads(self.io_threads())
+                .with_progress(self.show_progress)
+                .with_observer(Arc::clone(&self.observer))
+                .with_file_list(self.file_list.clone());
+            if let Some(provider) = &self.file_provider {
+                walker = walker.with_provider(Arc::clone(provider));
             }
-            
-            println!("\n📁 {}:", file);
-            for unused in unused_in_file {
-                println!("   .{} (line {})", unused.class.name, unused.class.line);
+
+            match &self.config {
+                Some(config) => walker.with_config(config.clone()),
+                None => walker,
+            }
+        })
+    }
+
+    /* ========================================================================================== */
+    fn read_stylesheets(&self, css_paths: Vec<PathBuf>) -> Result<Vec<(PathBuf, String)>, Box<dyn std::error::Error>> {
+        let processor = ParallelProcessor::new()
+            .configure_threads(self.io_threads())
+            .with_progress(self.show_progress)
+            .with_observer(Arc::clone(&self.observer))
+            .with_output_style(self.output_style());
+        let walker = self.file_walker();
+
+        let results = processor.process(
+            css_paths,
+            |path| -> Result<Option<(PathBuf, String)>, Box<dyn std::error::Error + Send + Sync>> {
+                // Skip files we can't read
+                Ok(walker.read(path).ok().map(|content| (path.clone(), content)))
+            },
+            "progress.reading_stylesheets"
+        )?;
+
+        Ok(results.into_iter().flatten().collect())
+    }
+    /* ========================================================================================== */
+    /// With `compile_scss`, SCSS files are swapped for their compiled CSS; their sources are
+    /// returned by file so class lines can be pointed back at them
+    fn compile_stylesheets(&self, files_with_content: Vec<(PathBuf, String)>) -> Result<CompiledStylesheets, Box<dyn std::error::Error>> {
+        let Some(config) = self.config.as_ref().filter(|config| config.analysis.compile_scss) else {
+            return Ok((files_with_content, HashMap::new()));
+        };
+        let style = self.output_style();
+        self.detail(style.text("progress.compiling_scss").to_string());
+
+        let root = Path::new(&self.directory);
+        let compiler = ScssCompiler::new(config.analysis.scss_load_paths.iter().map(|path| root.join(path)).collect());
+        let processor = ParallelProcessor::new()
+            .configure_threads(self.thread_count)
+            .with_progress(self.show_progress)
+            .with_observer(Arc::clone(&self.observer))
+            .with_output_style(self.output_style());
+        let results = processor.process(
+            files_with_content,
+            |(path, content)| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+                let compiled = ScssCompiler::is_compilable(path).then(|| compiler.compile(path, content));
+                Ok((path.clone(), content.clone(), compiled))
+            },
+            "progress.compiling_scss_files"
+        )?;
+
+        let mut files = Vec::with_capacity(results.len());
+        let mut sources = HashMap::new();
+        let mut failures = Vec::new();
+        for (path, content, compiled) in results {
+            match compiled {
+                Some(Ok(css)) => {
+                    sources.insert(path.to_string_lossy().to_string(), content);
+                    files.push((path, css));
+                }
+                Some(Err(e)) => {
+                    // Partials often need variables from elsewhere; their source is still usable
+                    failures.push((path.to_string_lossy().to_string(), e));
+                    files.push((path, content));
+                }
+                None => files.push((path, content)),
             }
         }
+
+        if let Some((path, error)) = failures.first() {
+            let first_line = error.lines().next().unwrap_or_default();
+            self.observer.on_warning(&style.message("progress.scss_compile_failures", &[&failures.len(), path, &first_line]));
+        }
+        Ok((files, sources))
     }
+
     /* ========================================================================================== */
+    /// Swaps the literal heads the class pattern took from interpolated selectors (`text-` of
+    /// `.text-#{$color}`) for the expanded names
+    fn apply_interpolations(&self, mut classes: Vec<CssClass>, interpolations: Vec<InterpolatedClasses>) -> (Vec<CssClass>, Vec<DynamicDefinition>) {
+        let fragments: HashSet<(&str, &str, usize)> = interpolations.iter()
+            .flat_map(|interpolation| interpolation.fragments.iter()
+                .map(|(name, line)| (interpolation.file.as_str(), name.as_str(), *line)))
+            .collect();
+        classes.retain(|class| !fragments.contains(&(class.file.as_str(), class.name.as_str(), class.line)));
 
-    fn print_file_breakdown(&self, file: &str) {
-        let classes = &self.by_file[file];
-        let unused_count = classes.iter().filter(|c| c.is_unused).count();
-        let total_count = classes.len();
-        
-        println!("\n{}", file);
-        println!("  Total: {}, Unused: {}, Used: {}", 
-            total_count, unused_count, total_count - unused_count);
-        
-        if unused_count == 0 {
-            return;
+        let mut seen: HashSet<(String, String)> = classes.iter().map(|class| (class.name.clone(), class.file.clone())).collect();
+        let mut expanded = 0;
+        let mut dynamic_definitions = Vec::new();
+        for interpolation in interpolations {
+            for class in interpolation.expanded {
+                if seen.insert((class.name.clone(), class.file.clone())) {
+                    expanded += 1;
+                    classes.push(class);
+                }
+            }
+            dynamic_definitions.extend(interpolation.dynamic);
         }
-        
-        println!("  Unused classes:");
-        for class in classes.iter().filter(|c| c.is_unused) {
-            println!("    .{} (line {})", class.class.name, class.class.line);
+
+        if expanded > 0 || !dynamic_definitions.is_empty() {
+            let style = self.output_style();
+            self.detail(style.message("progress.interpolated_classes", &[&expanded, &dynamic_definitions.len()]));
         }
+        (classes, dynamic_definitions)
     }
+
     /* ========================================================================================== */
+    fn describe_rules(&self, class_name: &str, has_ignored_hits: bool) -> Vec<String> {
+        let config = self.config.clone().unwrap_or_default();
+        let mut rules = vec![
+            format!("CSS extensions: {}", config.scan.css_extensions.join(", ")),
+            format!("Usage extensions: {}", config.scan.include_extensions.join(", ")),
+            format!("Component extensions: {}", config.scan.component_extensions.join(", ")),
+            format!("Excluded directories: {}", config.scan.exclude_dirs.join(", ")),
+            format!("Naming convention: {}", format!("{:?}", config.analysis.naming_convention).to_lowercase()),
+            format!("Class pattern: {}", config.analysis.class_pattern),
+            format!("Minimum class length: {}", config.analysis.min_class_length),
+        ];
 
-    fn get_unused_classes_in_file(&self, file: &str) -> Vec<&UnusedClass> {
-        self.by_file[file]
-            .iter()
-            .filter(|c| c.is_unused)
-            .collect()
+        if config.analysis.ignore_numeric {
+            rules.push("Purely numeric class names are never extracted".to_string());
+        }
+
+        if !config.analysis.presets.is_empty() {
+            rules.push(format!("Presets: {}", config.analysis.presets.join(", ")));
+        }
+
+        if config.is_stop_word(class_name) {
+            rules.push(format!("'{}' is a stop word, so an unused verdict is reported as low confidence", class_name));
+        }
+
+        if has_ignored_hits {
+            rules.push("Hits inside style=\"...\" attributes and <style> blocks are not counted as usage".to_string());
+        }
+
+        rules
     }
+
     /* ========================================================================================== */
-}
\ No newline at end of file
+    fn filter_css_files(&self, files_with_content: Vec<(PathBuf, String)>) -> Result<Vec<(PathBuf, String)>, Box<dyn std::error::Error>> {
+        let style = self.output_style();
+        let (component_files, css_files) = separate_items_by_condition(
+            files_with_content
+                .into_iter()
+                .filter(|(path, _)| self.is_css_path(path) || self.scan_filter.is_component_file(path))
+                .collect(),
+            |(path, _)| self.scan_filter.is_component_file(path)
+        );
+        let component_files: Vec<(PathBuf, String)> = component_files
+            .into_iter()
+            .map(|(path, content)| {
+                let styles = component_style_text(&content);
+                (path, styles)
+            })
+            .collect();
+
+        if self.entry_points.is_empty() {
+            return Ok(css_files.into_iter().chain(component_files).collect());
+        }
+
+        // Only stylesheets reachable from the entry points end up in the shipped bundle
+        let reachable = ImportResolver::new().collect_reachable(&self.resolve_entry_points())?;
+        let total = css_files.len();
+        let css_files: Vec<(PathBuf, String)> = css_files
+            .into_iter()
+            .filter(|(path, _)| std::fs::canonicalize(path).is_ok_and(|canonical| reachable.contains(&canonical)))
+            .collect();
+
+        self.stage(AnalysisStage::ResolvingImports, style.message("progress.reachable_stylesheets", &[&css_files.len(), &total]));
+        // Scoped component styles ship with their component, not through stylesheet imports
+        Ok(css_files.into_iter().chain(component_files).collect())
+    }
+
+    /* ========================================================================================== */
+    fn resolve_entry_points(&self) -> Vec<PathBuf> {
+        // Entries may be given relative to the cwd or to the analyzed directory
+        self.entry_points
+            .iter()
+            .map(|entry| {
+                let as_given = PathBuf::from(entry);
+                if as_given.exists() {
+                    as_given
+                } else {
+                    std::path::Path::new(&self.directory).join(entry)
+                }
+            })
+            .collect()
+    }
+
+    /* ========================================================================================== */
+    fn io_threads(&self) -> Option<usize> {
+        // Without a separate setting, reading uses the same threads as everything else
+        self.io_thread_count.or(self.thread_count)
+    }
+
+    /* ========================================================================================== */
+    fn stage(&self, stage: AnalysisStage, message: String) {
+        if self.show_progress {
+            self.observer.on_stage_start(stage, &message);
+        }
+    }
+
+    /* ========================================================================================== */
+    fn detail(&self, message: String) {
+        if self.show_progress {
+            self.observer.on_stage_detail(&message);
+        }
+    }
+
+    /* ========================================================================================== */
+    fn output_style(&self) -> OutputStyle {
+        self.config.as_ref()
+            .map(|config| OutputStyle::from_config(&config.output))
+            .unwrap_or_default()
+    }
+
+    /* ========================================================================================== */
+    fn preview_declarations(&self) -> usize {
+        self.config.as_ref()
+            .map_or_else(|| OutputConfig::default().preview_declarations, |config| config.output.preview_declarations)
+    }
+
+    /* ========================================================================================== */
+    fn is_stop_word(&self, class_name: &str) -> bool {
+        self.config.as_ref().is_some_and(|config| config.is_stop_word(class_name))
+    }
+
+    /* ========================================================================================== */
+    fn drop_excluded_layers(&self, classes: Vec<CssClass>) -> Vec<CssClass> {
+        let Some(config) = self.config.as_ref().filter(|config| !config.analysis.exclude_layers.is_empty()) else {
+            return classes;
+        };
+        let (excluded, classes) = separate_items_by_condition(
+            classes,
+            |class| class.layer.as_deref().is_some_and(|layer| config.is_excluded_layer(layer))
+        );
+        if !excluded.is_empty() {
+            let style = self.output_style();
+            self.detail(style.message("progress.excluded_layers", &[&excluded.len(), &config.analysis.exclude_layers.join(", ")]));
+        }
+        classes
+    }
+
+    /* ========================================================================================== */
+    fn is_css_path(&self, path: &std::path::Path) -> bool {
+        self.scan_filter.is_css_file(path)
+    }
+
+    /* ========================================================================================== */
+    fn usage_text(&self, processor: &TextProcessor, path: &Path, content: &str) -> String {
+        if self.scan_filter.is_data_file(path) {
+            // Unparseable data files are still searched as plain text rather than dropped
+            return data_file_text(path, content).unwrap_or_else(|_| content.to_string());
+        }
+
+        if is_indented_template(path) {
+            return template_usage_text(content);
+        }
+
+        // Style attributes and <style> blocks in templates are not usage
+        processor.strip_style_sections(content)
+    }
+
+    /* ========================================================================================== */
+    fn extract_classes(&self, files_with_content: Vec<(PathBuf, String)>) -> Result<Vec<CssClass>, Box<dyn std::error::Error>> {
+        let style = self.output_style();
+        self.stage(AnalysisStage::Extracting, style.text("progress.extracting").to_string());
+        let mut css_parser = CssParser::new()
+            .with_thread_count(get_thread_count_or_default(self.thread_count))
+            .with_progress(self.show_progress)
+            .with_observer(Arc::clone(&self.observer));
+
+        if let Some(config) = &self.config {
+            css_parser = css_parser.with_config(config.clone());
+        }
+
+        let classes = css_parser.extract_classes_parallel(files_with_content)?;
+        self.detail(style.message("progress.found_classes", &[&classes.len()]));
+        Ok(classes)
+    }
+
+    /* ========================================================================================== */
+    fn detect_patterns(&self, classes: &[CssClass]) -> Result<Vec<DynamicPattern>, Box<dyn std::error::Error>> {
+        let style = self.output_style();
+        self.stage(AnalysisStage::DetectingPatterns, style.text("progress.detecting_patterns").to_string());
+        let processor = TextProcessor::new();
+        let class_names: Vec<String> = classes.iter().map(|c| c.name.clone()).collect();
+        let convention = self.config.as_ref()
+            .map(|config| config.analysis.naming_convention)
+            .unwrap_or_default();
+        let mut patterns = processor.detect_convention_patterns(&class_names, convention);
+        patterns.extend(self.preset_patterns(&class_names)?);
+        
+        if !patterns.is_empty() {
+            self.detail(style.message("progress.found_patterns", &[&patterns.len()]));
+            for pattern in &patterns {
+                self.detail(format!("   {}", style.message("progress.pattern_covers", &[&pattern.pattern, &pattern.matching_classes.len()])));
+            }
+        }
+        
+        Ok(patterns)
+    }
+
+    /* ========================================================================================== */
+    fn preset_patterns(&self, class_names: &[String]) -> Result<Vec<DynamicPattern>, Box<dyn std::error::Error>> {
+        let Some(config) = &self.config else {
+            return Ok(Vec::new());
+        };
+
+        let root = Path::new(&self.directory);
+        let directories: Vec<PathBuf> = config.analysis.preset_dirs.iter().map(|directory| root.join(directory)).collect();
+        let mut patterns = Vec::new();
+        for name in &config.analysis.presets {
+            let preset = Preset::load(name, &directories)?;
+            for family in &preset.families {
+                let class_regex = family.class_regex()?;
+                let matching_classes: Vec<String> = class_names.iter().filter(|class| class_regex.is_match(class)).cloned().collect();
+                if matching_classes.is_empty() {
+                    continue;
+                }
+                patterns.push(DynamicPattern {
+                    prefix: String::new(),
+                    suffix: String::new(),
+                    pattern: format!("{} ({})", family.name, preset.name),
+                    matching_classes,
+                    trigger: Some(family.trigger_regex()?),
+                });
+            }
+        }
+        Ok(patterns)
+    }
+
+    /* ========================================================================================== */
+    fn scan_usage(
+        &self,
+        classes: &[CssClass],
+        dynamic_patterns: &[DynamicPattern],
+        compound_selectors: &[CompoundSelector],
+        usage_paths: Vec<PathBuf>,
+        deadline: Option<&Deadline>,
+        explained: Option<&str>,
+    ) -> Result<UsageTally, Box<dyn std::error::Error>> {
+        // Script modules the bundler never included can't apply classes at runtime
+        let metafile = self.load_bundle_metafile(&usage_paths)?;
+        let metafile = metafile.as_ref();
+
+        let style = self.output_style();
+        self.stage(AnalysisStage::Analyzing, style.message("progress.analyzing", &[&classes.len(), &get_thread_count_or_default(self.thread_count)]));
+
+        let defined: HashSet<&str> = classes.iter().map(|class| class.name.as_str()).collect();
+        let names: Vec<&str> = defined.iter().copied().collect();
+        let scanner = FileScanner::new();
+        let processor = TextProcessor::new();
+        let pattern_processor = TextProcessor::new().with_class_api_patterns();
+        // Compiled once into combined automatons, so each file is scanned once for all patterns
+        let pattern_set = PatternSet::new(dynamic_patterns);
+        // Vendored copies and generated duplicates are scanned once, see `FileUsage`
+        let content_cache: ContentCache<FileUsage> = ContentCache::new();
+        let walker = self.file_walker();
+        let skipped_files = AtomicUsize::new(0);
+
+        let tallies = ParallelProcessor::new()
+            .configure_threads(self.thread_count)
+            .with_io_threads(self.io_thread_count)
+            .with_progress(self.show_progress)
+            .with_observer(Arc::clone(&self.observer))
+            .with_output_style(self.output_style())
+            .with_memory_budget_mb(self.memory_budget_mb)
+            .stream_fold(
+                usage_paths,
+                |path: &Path| {
+                    // Past the deadline the remaining files are drained without being read
+                    if deadline.is_some_and(Deadline::is_past) {
+                        skipped_files.fetch_add(1, Ordering::Relaxed);
+                        return Err(std::io::ErrorKind::TimedOut.into());
+                    }
+                    walker.read(path)
+                },
+                UsageTally::default,
+                |tally: &mut UsageTally, path: &Path, raw_content: String| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                    let file = path.to_string_lossy().to_string();
+                    let content = self.usage_text(&processor, path, &raw_content);
+                    let is_dead_module = metafile.is_some_and(|metafile| metafile.is_dead_module(path, &self.directory));
+                    let is_weak = self.scan_filter.usage_policy(path) == UsagePolicy::Weak;
+                    // Only files actually scanned add to the pattern time
+                    let mut pattern_time = Duration::ZERO;
+                    let usage = content_cache.get_or_compute(&content, || {
+                        let words = processor.word_set(&content);
+                        let mut usage = FileUsage {
+                            names: names.iter().filter(|name| scanner.contains_word_in(&content, &words, name)).map(|name| name.to_string()).collect(),
+                            ..FileUsage::default()
+                        };
+
+                        // Compound selectors need their classes on the same element, not just in the same file
+                        let elements = element_class_sets(&content);
+                        if self.with_co_usage {
+                            for element in &elements {
+                                record_co_usage(&mut usage.co_usage, element, &defined);
+                            }
+                        }
+                        usage.script_applied = script_applied_classes(&content).into_iter().map(str::to_string).collect();
+                        usage.data_attributes = used_data_attributes(&content);
+                        usage.co_applied = compound_selectors.iter().enumerate()
+                            .filter(|(_, compound)| compound.is_applied_to(&elements))
+                            .map(|(index, _)| index)
+                            .collect();
+
+                        if !pattern_set.is_empty() {
+                            let started = Instant::now();
+                            let assembled_arguments = pattern_processor.assembled_class_arguments(&content);
+                            usage.used_patterns = pattern_set.used_patterns(&content, &assembled_arguments);
+                            pattern_time = started.elapsed();
+                        }
+                        usage
+                    });
+                    tally.pattern_time += pattern_time;
+
+                    if let Some(name) = explained {
+                        let (hits, ignored_hits) = self.explained_positions(&scanner, name, path, &raw_content, &content);
+                        tally.explained.record(&file, hits, ignored_hits, is_dead_module);
+                    }
+
+                    for name in &usage.names {
+                        if is_dead_module {
+                            tally.dead_code_names.insert(name.clone());
+                        } else {
+                            tally.exact_files.entry(name.clone()).or_default().push(file.clone());
+                            if !is_weak {
+                                tally.strong_names.insert(name.clone());
+                            }
+                        }
+                    }
+
+                    if is_dead_module {
+                        return Ok(());
+                    }
+
+                    for (pair, count) in &usage.co_usage {
+                        *tally.co_usage.entry(pair.clone()).or_default() += count;
+                    }
+                    tally.script_applied.extend(usage.script_applied.iter().cloned());
+                    tally.data_attributes.extend(usage.data_attributes.iter().cloned());
+                    tally.co_applied.extend(&usage.co_applied);
+                    for index in &usage.used_patterns {
+                        tally.record_pattern_file(*index, is_weak, &file);
+                        if explained.is_some() {
+                            tally.explained.pattern_files.entry(*index).or_default().push(file.clone());
+                        }
+                    }
+
+                    Ok(())
+                },
+                "progress.analyzing_usage"
+            )?;
+
+        if content_cache.reused() > 0 {
+            self.detail(style.message("progress.duplicate_content", &[&content_cache.reused()]));
+        }
+
+        let mut merged = UsageTally::default();
+        for tally in tallies {
+            merged.merge(tally);
+        }
+        merged.skipped_files = skipped_files.into_inner();
+        merged.explained.sort();
+        if let Some(deadline) = deadline.filter(|_| merged.skipped_files > 0) {
+            self.observer.on_warning(&style.message("progress.timed_out", &[deadline, &merged.skipped_files]));
+        }
+        Ok(merged)
+    }
+
+    /* ========================================================================================== */
+    /// Lines of one usage file naming the explained class, then hits that only existed inside style sections
+    fn explained_positions(&self, scanner: &FileScanner, name: &str, path: &Path, raw_content: &str, content: &str) -> (Vec<TextPosition>, Vec<TextPosition>) {
+        let positions = scanner.find_word_positions(content, name);
+        let raw_positions = scanner.find_word_positions(raw_content, name);
+
+        if self.scan_filter.is_data_file(path) || is_indented_template(path) {
+            // Rewritten content loses its positions, so point at the lines in the file itself
+            let hits = if positions.is_empty() { Vec::new() } else { raw_positions };
+            return (hits, Vec::new());
+        }
+
+        let ignored_hits = raw_positions.into_iter()
+            .filter(|raw| !positions.iter().any(|position| position.line == raw.line))
+            .collect();
+        (positions, ignored_hits)
+    }
+
+    /* ========================================================================================== */
+    fn classify_classes(&self, classes: &[CssClass], dynamic_patterns: &[DynamicPattern], tally: &UsageTally) -> UsageAnalysis {
+        let style = self.output_style();
+
+        let mut covering_patterns: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, pattern) in dynamic_patterns.iter().enumerate() {
+            for name in &pattern.matching_classes {
+                covering_patterns.entry(name.as_str()).or_default().push(index);
+            }
+        }
+
+        let mut decisions = DecisionMap::new();
+        let mut used_classes = Vec::new();
+        let mut unused_classes = Vec::new();
+        let mut pattern_used_count = 0;
+
+        for class in classes {
+            let covering = covering_patterns.get(class.name.as_str()).map(Vec::as_slice).unwrap_or_default();
+            let elapsed = (tally.pattern_time / dynamic_patterns.len().max(1) as u32) * covering.len() as u32;
+            let pattern_usage = covering.iter()
+                .filter_map(|index| tally.pattern_files.get(index).map(|usage| (&dynamic_patterns[*index], usage)))
+                .min_by(|(_, a), (_, b)| a.0.cmp(&b.0))
+                .map(|(pattern, (_, file))| (pattern, file));
+            let has_strong_usage = tally.strong_names.contains(&class.name)
+                || covering.iter().any(|index| tally.pattern_files.get(index).is_some_and(|(is_weak, _)| !is_weak));
+
+            let (status, evidence) = if let Some(files) = tally.exact_files.get(&class.name) {
+                (DecisionStatus::Used, files.iter().cloned().map(DecisionEvidence::exact).collect())
+            } else if let Some((pattern, file)) = pattern_usage {
+                pattern_used_count += 1;
+                (DecisionStatus::UsedViaPattern, vec![DecisionEvidence::pattern(file.clone(), pattern.pattern.clone())])
+            } else {
+                (DecisionStatus::Unused, Vec::new())
+            };
+
+            let mut decision = ClassDecision::new(class, status, evidence, elapsed);
+            if status != DecisionStatus::Unused && !has_strong_usage {
+                decision.used_only_in = self.evidence_categories(&decision.evidence);
+            }
+            decisions.insert(decision_key(class), decision);
+            if status == DecisionStatus::Unused {
+                unused_classes.push(class.clone());
+            } else {
+                used_classes.push(class.clone());
+            }
+        }
+
+        let exact_used_count = used_classes.len() - pattern_used_count;
+        self.detail(style.message("progress.step1_done", &[&exact_used_count, &(classes.len() - exact_used_count)]));
+        self.detail(style.message("progress.step2_done", &[&pattern_used_count, &unused_classes.len()]));
+
+        let by_file = self.build_by_file_structure(&used_classes, &unused_classes);
+        self.stage(AnalysisStage::Complete, style.text("progress.complete").to_string());
+        (unused_classes, used_classes, by_file, decisions)
+    }
+    /* ========================================================================================== */
+    fn evidence_categories(&self, evidence: &[DecisionEvidence]) -> Vec<String> {
+        let categories: BTreeSet<&str> = evidence.iter()
+            .map(|evidence| self.scan_filter.category_name(Path::new(&evidence.file)))
+            .collect();
+        categories.into_iter().map(str::to_string).collect()
+    }
+
+    /* ========================================================================================== */
+    fn build_by_file_structure(&self, used_classes: &[CssClass], unused_classes: &[CssClass]) -> HashMap<String, Vec<UnusedClass>> {
+        let mut by_file: HashMap<String, Vec<UnusedClass>> = HashMap::new();
+        
+        for class in used_classes {
+            by_file
+                .entry(class.file.clone())
+                .or_default()
+                .push(UnusedClass {
+                    class: class.clone(),
+                    is_unused: false,
+                    low_confidence: false,
+                    dead_code_only: false,
+                    recent: false,
+                    legacy: false,
+                    not_analyzed: false,
+                });
+        }
+        
+        for class in unused_classes {
+            by_file
+                .entry(class.file.clone())
+                .or_default()
+                .push(UnusedClass {
+                    class: class.clone(),
+                    is_unused: true,
+                    low_confidence: self.is_stop_word(&class.name),
+                    dead_code_only: false,
+                    recent: false,
+                    legacy: false,
+                    not_analyzed: false,
+                });
+        }
+
+        by_file
+    }
+}
+
+// What a usage file contributes, which depends only on its content; the path decides where it's recorded
+#[derive(Default)]
+struct FileUsage {
+    names: Vec<String>,
+    used_patterns: Vec<usize>,
+    co_applied: Vec<usize>,
+    script_applied: Vec<String>,
+    data_attributes: HashSet<String>,
+    co_usage: HashMap<(String, String), usize>,
+}
+
+impl UsageTally {
+    fn record_pattern_file(&mut self, index: usize, is_weak: bool, file: &str) {
+        let current = self.pattern_files.entry(index).or_insert_with(|| (is_weak, file.to_string()));
+        if (is_weak, file) < (current.0, current.1.as_str()) {
+            *current = (is_weak, file.to_string());
+        }
+    }
+
+    /* ========================================================================================== */
+    fn merge(&mut self, other: UsageTally) {
+        for (name, files) in other.exact_files {
+            let merged = self.exact_files.entry(name).or_default();
+            merged.extend(files);
+            merged.sort();
+        }
+        self.dead_code_names.extend(other.dead_code_names);
+        self.strong_names.extend(other.strong_names);
+        self.co_applied.extend(other.co_applied);
+        self.script_applied.extend(other.script_applied);
+        self.data_attributes.extend(other.data_attributes);
+        for (pair, count) in other.co_usage {
+            *self.co_usage.entry(pair).or_default() += count;
+        }
+        for (index, (is_weak, file)) in other.pattern_files {
+            self.record_pattern_file(index, is_weak, &file);
+        }
+        self.pattern_time += other.pattern_time;
+        self.explained.merge(other.explained);
+    }
+}
+
+impl ExplainedUsage {
+    fn record(&mut self, file: &str, hits: Vec<TextPosition>, ignored_hits: Vec<TextPosition>, is_dead_module: bool) {
+        let hits = hits.into_iter().map(|position| UsageHit::new(file.to_string(), position));
+        if is_dead_module {
+            self.dead_code_hits.extend(hits);
+        } else {
+            self.usage_hits.extend(hits);
+        }
+        self.ignored_hits.extend(ignored_hits.into_iter().map(|position| UsageHit::new(file.to_string(), position)));
+    }
+
+    /* ========================================================================================== */
+    fn merge(&mut self, other: ExplainedUsage) {
+        self.usage_hits.extend(other.usage_hits);
+        self.ignored_hits.extend(other.ignored_hits);
+        self.dead_code_hits.extend(other.dead_code_hits);
+        for (index, files) in other.pattern_files {
+            self.pattern_files.entry(index).or_default().extend(files);
+        }
+    }
+
+    /* ========================================================================================== */
+    /// Threads finish in any order, so hits are put back in file and line order
+    fn sort(&mut self) {
+        for hits in [&mut self.usage_hits, &mut self.ignored_hits, &mut self.dead_code_hits] {
+            hits.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+        }
+        for files in self.pattern_files.values_mut() {
+            files.sort();
+        }
+    }
+}
+
+impl MemoryBudgetConfigurable for UnusedDetector {
+    fn with_memory_budget_mb(mut self, megabytes: usize) -> Self {
+        self.memory_budget_mb = megabytes;
+        self
+    }
+}
+
+impl ObserverConfigurable for UnusedDetector {
+    fn with_observer(mut self, observer: Arc<dyn AnalysisObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+}
+
+impl ProgressConfigurable for UnusedDetector {
+    fn with_progress(mut self, show_progress: bool) -> Self {
+        // Off for output meant for scripts, where only the result may reach stdout
+        self.show_progress = show_progress;
+        self
+    }
+}
+
+impl ThreadCountConfigurable for UnusedDetector {
+    fn with_thread_count(mut self, count: usize) -> Self {
+        self.thread_count = Some(count);
+        self
+    }
+}
+
+impl ConfigConfigurable for UnusedDetector {
+    fn with_config(mut self, config: Config) -> Self {
+        self.scan_filter = config.scan_filter().with_root(&self.directory);
+        // Explicit thread counts (command line) win over the config file
+        self.thread_count = self.thread_count.or(config.scan.cpu_threads);
+        self.io_thread_count = self.io_thread_count.or(config.scan.io_threads);
+        self.config = Some(config);
+        self
+    }
+}
+
+impl UnusedReport {
+    pub fn print_summary(&self, style: &OutputStyle) {
+        println!("\n{} {}", style.icon("📋"), style.text("report.title"));
+        style.print_header_line();
+        if let Some(filter) = &self.filter {
+            println!("{}", style.message("report.filtered", &[&filter.describe()]));
+        }
+        if let Some(timeout) = &self.timeout {
+            println!("{}  {}", style.icon("⚠️"), style.message("report.timed_out", &[&timeout.limit_text(), &style.count(timeout.scanned_files), &style.count(timeout.total_files)]));
+        }
+        println!("{}", style.message("report.total", &[&style.count(self.total_classes)]));
+        println!("{}", style.message("report.unused", &[&style.count(self.unused_classes.len())]));
+        println!("{}", style.message("report.used", &[&style.count(self.used_classes.len())]));
+
+        if !self.low_confidence_classes.is_empty() {
+            println!("{}", style.message("report.low_confidence", &[&style.count(self.low_confidence_classes.len())]));
+        }
+
+        if !self.dead_code_classes.is_empty() {
+            println!("{}", style.message("report.dead_code", &[&style.count(self.dead_code_classes.len())]));
+        }
+
+        if !self.not_analyzed_classes.is_empty() {
+            println!("{}", style.message("report.not_analyzed", &[&style.count(self.not_analyzed_classes.len())]));
+        }
+
+        if !self.recent_classes.is_empty() {
+            println!("{}", style.message("report.recent", &[&style.count(self.recent_classes.len())]));
+        }
+
+        if !self.legacy_classes.is_empty() {
+            println!("{}", style.message("report.legacy", &[&style.count(self.legacy_classes.len())]));
+        }
+
+        for (categories, classes) in self.category_only_groups() {
+            println!("{}", style.message("report.used_only_in", &[&categories, &style.count(classes.len())]));
+        }
+
+        if !self.dead_combinations.is_empty() {
+            println!("{}", style.message("report.dead_combinations", &[&style.count(self.dead_combinations.len())]));
+        }
+
+        if !self.redundant_rules.is_empty() {
+            println!("{}", style.message("report.redundant_rules", &[&style.count(self.redundant_rules.len())]));
+        }
+
+        if !self.duplicate_blocks.is_empty() {
+            println!("{}", style.message("report.duplicate_blocks", &[&style.count(self.duplicate_blocks.len())]));
+        }
+
+        let unused_media_queries = self.media_queries.iter().filter(|media| media.unused).count();
+        if unused_media_queries > 0 {
+            println!("{}", style.message("report.unused_media_queries", &[&style.count(unused_media_queries), &style.count(self.media_queries.len())]));
+        }
+
+        if !self.unused_data_attributes.is_empty() {
+            println!("{}", style.message("report.unused_data_attributes", &[&style.count(self.unused_data_attributes.len())]));
+        }
+
+        if !self.unused_font_faces.is_empty() {
+            println!("{}", style.message("report.unused_font_faces", &[&style.count(self.unused_font_faces.len())]));
+        }
+
+        if !self.unused_assets.is_empty() {
+            println!("{}", style.message("report.unused_assets", &[&style.count(self.unused_assets.len())]));
+        }
+
+        if !self.dynamic_definitions.is_empty() {
+            println!("{}", style.message("report.dynamic_definitions", &[&style.count(self.dynamic_definitions.len())]));
+        }
+        
+        // Share of unused classes means nothing while some weren't analyzed; a sample's is printed as a bound below
+        if self.total_classes > 0 && self.timeout.is_none() && self.sample.is_none() {
+            let percentage = (self.unused_classes.len() as f64 / self.total_classes as f64) * 100.0;
+            println!("{}", style.message("report.percentage", &[&style.percentage(percentage)]));
+        }
+
+        if let (Some(sample), Some(estimate)) = (&self.sample, self.unused_estimate()) {
+            println!("{}", style.message("report.sample", &[&style.count(sample.sampled_files), &style.count(sample.total_files), &sample.seed]));
+            println!("{}", style.message("report.estimate", &[&style.percentage(estimate.upper_bound)]));
+            println!("{}", style.text("report.estimate_note"));
+        }
+    }
+    /* ========================================================================================== */
+    
+    pub fn print_detailed(&self, style: &OutputStyle) {
+        self.print_summary(style);
+        self.print_dead_code_classes(style);
+        self.print_not_analyzed_classes(style);
+        self.print_category_only_classes(style);
+        self.print_dead_combinations(style);
+        self.print_redundant_rules(style);
+        self.print_duplicate_blocks(style);
+        self.print_unused_media_queries(style);
+        self.print_unused_data_attributes(style);
+        self.print_unused_font_faces(style);
+        self.print_unused_assets(style);
+        self.print_dynamic_definitions(style);
+        self.print_by_owner(style);
+        self.print_by_author(style);
+        
+        if self.unused_classes.is_empty() && self.low_confidence_classes.is_empty() {
+            return;
+        }
+        
+        println!("\n{}  {}", style.icon("🗑️"), style.text("report.unused_heading"));
+        style.print_section_line();
+        
+        self.print_unused_classes_by_file(style);
+        println!("\n{} {}", style.icon("💡"), style.text("report.tip"));
+    }
+    /* ========================================================================================== */
+
+    fn print_dead_code_classes(&self, style: &OutputStyle) {
+        if self.dead_code_classes.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("🪦"), style.text("report.dead_code_heading"));
+        style.print_section_line();
+        for class in &self.dead_code_classes {
+            println!("   {}", style.message("report.class_in_file", &[&class.name, &class.file, &class.line]));
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_not_analyzed_classes(&self, style: &OutputStyle) {
+        if self.not_analyzed_classes.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("⏱️"), style.text("report.not_analyzed_heading"));
+        style.print_section_line();
+        for class in &self.not_analyzed_classes {
+            println!("   {}", style.message("report.class_in_file", &[&class.name, &class.file, &class.line]));
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_category_only_classes(&self, style: &OutputStyle) {
+        for (categories, classes) in self.category_only_groups() {
+            println!("\n{} {}", style.icon("🧪"), style.message("report.used_only_in_heading", &[&categories.to_uppercase()]));
+            style.print_section_line();
+            for class in classes {
+                println!("   {}", style.message("report.class_in_file", &[&class.name, &class.file, &class.line]));
+            }
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_dead_combinations(&self, style: &OutputStyle) {
+        if self.dead_combinations.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("🧩"), style.text("report.dead_combinations_heading"));
+        style.print_section_line();
+        for compound in &self.dead_combinations {
+            println!("   {}", style.message("report.combination_in_file", &[&compound.selector, &compound.file, &compound.line]));
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_redundant_rules(&self, style: &OutputStyle) {
+        if self.redundant_rules.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("♻️"), style.text("report.redundant_rules_heading"));
+        style.print_section_line();
+        for rule in &self.redundant_rules {
+            println!("   {}", style.message("report.redundant_rule", &[&rule.selector, &rule.specificity, &rule.file, &rule.line, &rule.overridden_at]));
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_duplicate_blocks(&self, style: &OutputStyle) {
+        if self.duplicate_blocks.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("👯"), style.text("report.duplicate_blocks_heading"));
+        style.print_section_line();
+        for block in &self.duplicate_blocks {
+            println!("   {}", style.message("report.duplicate_block", &[&block.locations.len(), &block.declarations.join("; ")]));
+            for location in &block.locations {
+                println!("     {}", style.message("report.combination_in_file", &[&location.selector, &location.file, &location.line]));
+            }
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_dynamic_definitions(&self, style: &OutputStyle) {
+        if self.dynamic_definitions.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("🌀"), style.text("report.dynamic_definitions_heading"));
+        style.print_section_line();
+        for definition in &self.dynamic_definitions {
+            println!("   {}", style.message("report.combination_in_file", &[&definition.selector, &definition.file, &definition.line]));
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_unused_media_queries(&self, style: &OutputStyle) {
+        let unused: Vec<&MediaQueryUsage> = self.media_queries.iter().filter(|media| media.unused).collect();
+        if unused.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("📐"), style.text("report.unused_media_queries_heading"));
+        style.print_section_line();
+        for media in unused {
+            println!("   {}", style.message("report.media_query_rules", &[&media.condition, &media.rules.len()]));
+            for rule in &media.rules {
+                println!("     {}", style.message("report.combination_in_file", &[&rule.selector, &rule.file, &rule.line]));
+            }
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_unused_data_attributes(&self, style: &OutputStyle) {
+        if self.unused_data_attributes.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("🏷️"), style.text("report.unused_data_attributes_heading"));
+        style.print_section_line();
+        for selector in &self.unused_data_attributes {
+            println!("   {}", style.message("report.data_attribute_in_file", &[&selector.attribute, &selector.selector, &selector.file, &selector.line]));
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_unused_font_faces(&self, style: &OutputStyle) {
+        if self.unused_font_faces.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("🔤"), style.text("report.unused_font_faces_heading"));
+        style.print_section_line();
+        for face in &self.unused_font_faces {
+            println!("   {}", style.message("report.combination_in_file", &[&face.family, &face.file, &face.line]));
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_unused_assets(&self, style: &OutputStyle) {
+        if self.unused_assets.is_empty() {
+            return;
+        }
+
+        println!("\n{} {}", style.icon("🖼️"), style.text("report.unused_assets_heading"));
+        style.print_section_line();
+        for asset in &self.unused_assets {
+            println!("   {}", asset.url);
+            for reference in &asset.references {
+                println!("     {}", style.message("report.combination_in_file", &[&reference.selector, &reference.file, &reference.line]));
+            }
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_by_owner(&self, style: &OutputStyle) {
+        for (owner, classes) in &self.by_owner {
+            println!("\n{} {}", style.icon("👥"), style.message("report.by_owner_heading", &[owner, &classes.len()]));
+            style.print_section_line();
+            for class in classes {
+                println!("   {}", style.message("report.class_in_file", &[&class.name, &class.file, &class.line]));
+            }
+        }
+    }
+    /* ========================================================================================== */
+
+    fn owner_note(&self, file: &str, style: &OutputStyle) -> String {
+        self.owners.get(file)
+            .map(|owner| style.message("report.owner_note", &[owner]))
+            .unwrap_or_default()
+    }
+    /* ========================================================================================== */
+
+    fn print_by_author(&self, style: &OutputStyle) {
+        for (author, classes) in self.author_groups() {
+            println!("\n{} {}", style.icon("👤"), style.message("report.by_author_heading", &[&author, &classes.len()]));
+            style.print_section_line();
+            for class in classes {
+                println!("   {}", style.message("report.class_in_file", &[&class.name, &class.file, &class.line]));
+            }
+        }
+    }
+    /* ========================================================================================== */
+
+    pub fn author_groups(&self) -> BTreeMap<&str, Vec<&CssClass>> {
+        let mut groups: BTreeMap<&str, Vec<&CssClass>> = BTreeMap::new();
+        for class in &self.unused_classes {
+            if let Some(author) = self.authors.get(&decision_key(class)) {
+                groups.entry(author.as_str()).or_default().push(class);
+            }
+        }
+        groups
+    }
+    /* ========================================================================================== */
+
+    pub fn author_note(&self, class: &CssClass, style: &OutputStyle) -> String {
+        self.authors.get(&decision_key(class))
+            .map(|author| style.message("report.author_note", &[author]))
+            .unwrap_or_default()
+    }
+    /* ========================================================================================== */
+
+    fn category_only_groups(&self) -> BTreeMap<String, Vec<&CssClass>> {
+        let mut groups: BTreeMap<String, Vec<&CssClass>> = BTreeMap::new();
+        for usage in &self.category_only_classes {
+            groups.entry(usage.categories.join(", ")).or_default().push(&usage.class);
+        }
+        groups
+    }
+    /* ========================================================================================== */
+
+    pub fn print_by_file(&self, style: &OutputStyle) {
+        self.print_summary(style);
+        self.print_by_owner(style);
+        self.print_by_author(style);
+        println!("\n{} {}", style.icon("📁"), style.text("report.by_file_heading"));
+        style.print_section_line();
+        
+        for file in self.file_order() {
+            self.print_file_breakdown(file, style);
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_unused_classes_by_file(&self, style: &OutputStyle) {
+        for file in self.file_order() {
+            let unused_in_file = self.get_unused_classes_in_file(file);
+            
+            if unused_in_file.is_empty() {
+                continue;
+            }
+            
+            println!("\n{} {}{}:", style.icon("📁"), file, self.owner_note(file, style));
+            for unused in unused_in_file {
+                println!("   {}{}{}{}", style.message("report.class_line", &[&unused.class.name, &unused.class.line]), context_note(&unused.class, style), low_confidence_note(unused, style), self.author_note(&unused.class, style));
+                if let Some(doc) = &unused.class.doc {
+                    println!("      /* {} */", doc);
+                }
+                if let Some(declarations) = declarations_preview(&unused.class, style) {
+                    println!("      {{ {} }}", declarations);
+                }
+            }
+        }
+    }
+    /* ========================================================================================== */
+
+    fn print_file_breakdown(&self, file: &str, style: &OutputStyle) {
+        let classes = &self.by_file[file];
+        let unused_count = classes.iter().filter(|c| c.is_unused).count();
+        let total_count = classes.len();
+        
+        println!("\n{}{}", file, self.owner_note(file, style));
+        println!("  {}", style.message("report.file_totals", &[&style.count(total_count), &style.count(unused_count), &style.count(total_count - unused_count)]));
+        
+        if unused_count > 0 {
+            println!("  {}", style.text("report.file_unused"));
+            for class in classes.iter().filter(|c| c.is_unused) {
+                println!("    {}{}{}{}", style.message("report.class_line", &[&class.class.name, &class.class.line]), context_note(&class.class, style), low_confidence_note(class, style), self.author_note(&class.class, style));
+            }
+        }
+
+        let dead_code: Vec<&UnusedClass> = classes.iter().filter(|c| c.dead_code_only).collect();
+        if !dead_code.is_empty() {
+            println!("  {}", style.text("report.file_dead_code"));
+            for class in dead_code {
+                println!("    {}", style.message("report.class_line", &[&class.class.name, &class.class.line]));
+            }
+        }
+
+        let not_analyzed: Vec<&UnusedClass> = classes.iter().filter(|c| c.not_analyzed).collect();
+        if !not_analyzed.is_empty() {
+            println!("  {}", style.text("report.file_not_analyzed"));
+            for class in not_analyzed {
+                println!("    {}", style.message("report.class_line", &[&class.class.name, &class.class.line]));
+            }
+        }
+    }
+    /* ========================================================================================== */
+
+    /// Reorders `unused_classes` and the classes of each file, and sets the file order of
+    /// detailed and by-file output
+    pub fn sort_by(&mut self, sort: ReportSort) {
+        self.sort = sort;
+        let rank: HashMap<String, usize> = self.file_order().into_iter()
+            .enumerate()
+            .map(|(index, file)| (file.to_string(), index))
+            .collect();
+
+        match sort {
+            ReportSort::Name => {
+                self.unused_classes.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.file.cmp(&b.file)).then(a.line.cmp(&b.line)));
+                for classes in self.by_file.values_mut() {
+                    classes.sort_by(|a, b| a.class.name.cmp(&b.class.name).then(a.class.line.cmp(&b.class.line)));
+                }
+            }
+            ReportSort::Count | ReportSort::File | ReportSort::Percentage => {
+                self.unused_classes.sort_by(|a, b| rank.get(&a.file).cmp(&rank.get(&b.file)).then(a.line.cmp(&b.line)));
+                for classes in self.by_file.values_mut() {
+                    classes.sort_by_key(|class| class.class.line);
+                }
+            }
+        }
+    }
+    /* ========================================================================================== */
+
+    /// Files by most unused classes or highest unused share for those sorts, alphabetical otherwise
+    fn file_order(&self) -> Vec<&str> {
+        // (file, unused, total)
+        let mut files: Vec<(&str, usize, usize)> = self.by_file.iter()
+            .map(|(file, classes)| (file.as_str(), classes.iter().filter(|class| class.is_unused).count(), classes.len()))
+            .collect();
+
+        match self.sort {
+            ReportSort::Count => files.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0))),
+            // unused_b / total_b against unused_a / total_a without dividing
+            ReportSort::Percentage => files.sort_by(|a, b| (b.1 * a.2).cmp(&(a.1 * b.2)).then(b.1.cmp(&a.1)).then(a.0.cmp(b.0))),
+            ReportSort::Name | ReportSort::File => files.sort_by(|a, b| a.0.cmp(b.0)),
+        }
+        files.into_iter().map(|(file, _, _)| file).collect()
+    }
+    /* ========================================================================================== */
+
+    pub fn unused_estimate(&self) -> Option<UnusedEstimate> {
+        UnusedEstimate::from_counts(self.unused_classes.len(), self.total_classes)
+    }
+    /* ========================================================================================== */
+
+    pub fn to_markdown(&self, selected: Option<&[String]>, style: &OutputStyle) -> String {
+        let mut markdown = format!("## {}\n\n", style.text("markdown.title"));
+        markdown.push_str(&format!("- {}\n", style.message("report.total", &[&style.count(self.total_classes)])));
+        markdown.push_str(&format!("- {}\n", style.message("report.unused", &[&style.count(self.unused_classes.len())])));
+        markdown.push_str(&format!("- {}\n", style.message("report.used", &[&style.count(self.used_classes.len())])));
+
+        let classes: Vec<&CssClass> = self.unused_classes
+            .iter()
+            .filter(|class| selected.is_none_or(|names| names.contains(&class.name)))
+            .collect();
+
+        if classes.is_empty() {
+            return markdown;
+        }
+
+        markdown.push_str(&format!("\n| {} | {} | {} | {} |\n|---|---|---|---|\n",
+            style.text("markdown.class"), style.text("markdown.file"), style.text("markdown.line"), style.text("markdown.column")));
+        for class in classes {
+            markdown.push_str(&format!("| {} | {} | {} | {} |\n",
+                markdown_code_cell(&format!(".{}", class.name)), markdown_code_cell(&class.file), class.line, class.column));
+        }
+
+        markdown
+    }
+    /* ========================================================================================== */
+
+    pub fn to_quickfix(&self, file: Option<&str>, style: &OutputStyle) -> String {
+        // "file:line:column: message" is understood by Vim's default errorformat and VS Code problem matchers
+        let mut classes: Vec<&CssClass> = self.unused_classes
+            .iter()
+            .filter(|class| file.is_none_or(|f| class.file == f))
+            .collect();
+        classes.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+
+        classes
+            .iter()
+            .map(|class| format!("{}:{}:{}: {}\n", class.file, class.line, class.column, style.message("quickfix.unused", &[&class.name])))
+            .collect()
+    }
+    /* ========================================================================================== */
+
+    fn get_unused_classes_in_file(&self, file: &str) -> Vec<&UnusedClass> {
+        self.by_file[file]
+            .iter()
+            .filter(|c| c.is_unused)
+            .collect()
+    }
+    /* ========================================================================================== */
+}
+
+fn map_to_scss_source(classes: &mut [CssClass], sources: &HashMap<String, String>) {
+    for class in classes.iter_mut() {
+        if let Some(source) = sources.get(&class.file) {
+            // Generated names without a matching selector point at the top of the file
+            let position = source_position(source, &class.name).unwrap_or(TextPosition { line: 1, column: 1, offset: 0 });
+            class.line = position.line;
+            class.column = position.column;
+            class.offset = position.offset;
+        }
+    }
+}
+
+fn annotate_rule_context(classes: &mut [CssClass], rules: &[CssRule], preview_declarations: usize) {
+    let mut rules_by_class: HashMap<(&str, Cow<str>), Vec<&CssRule>> = HashMap::new();
+    for rule in rules {
+        for name in rule.classes() {
+            rules_by_class.entry((rule.file.as_str(), name)).or_default().push(rule);
+        }
+    }
+
+    for class in classes.iter_mut() {
+        // Rules are in line order; the last one starting at or above the match holds its selector
+        let context = rules_by_class.get(&(class.file.as_str(), Cow::Borrowed(class.name.as_str())))
+            .and_then(|candidates| candidates.iter().rev().find(|rule| rule.line <= class.line))
+            .map(|rule| (rule.layer(), rule.scope(), rule.preview(preview_declarations), rule.doc.clone()));
+        if let Some((layer, scope, declarations, doc)) = context {
+            class.layer = layer;
+            class.scope = scope;
+            class.declarations = declarations;
+            class.doc = doc;
+        }
+    }
+}
+
+fn mark_dead_code_usage(by_file: &mut HashMap<String, Vec<UnusedClass>>, dead_code_classes: &[CssClass]) {
+    for class in dead_code_classes {
+        if let Some(entries) = by_file.get_mut(&class.file) {
+            for entry in entries.iter_mut().filter(|entry| entry.class.name == class.name) {
+                entry.is_unused = false;
+                entry.dead_code_only = true;
+            }
+        }
+    }
+}
+
+fn mark_not_analyzed(by_file: &mut HashMap<String, Vec<UnusedClass>>, not_analyzed_classes: &[CssClass]) {
+    for class in not_analyzed_classes {
+        if let Some(entries) = by_file.get_mut(&class.file) {
+            for entry in entries.iter_mut().filter(|entry| entry.class.name == class.name && entry.class.line == class.line) {
+                entry.is_unused = false;
+                entry.not_analyzed = true;
+            }
+        }
+    }
+}
+
+fn mark_recent(by_file: &mut HashMap<String, Vec<UnusedClass>>, recent_classes: &[CssClass]) {
+    for class in recent_classes {
+        if let Some(entries) = by_file.get_mut(&class.file) {
+            for entry in entries.iter_mut().filter(|entry| entry.class.name == class.name && entry.class.line == class.line) {
+                entry.recent = true;
+            }
+        }
+    }
+}
+
+fn mark_legacy(by_file: &mut HashMap<String, Vec<UnusedClass>>, legacy_classes: &[CssClass]) {
+    for class in legacy_classes {
+        if let Some(entries) = by_file.get_mut(&class.file) {
+            for entry in entries.iter_mut().filter(|entry| entry.class.name == class.name && entry.class.line == class.line) {
+                entry.legacy = true;
+            }
+        }
+    }
+}
+
+fn group_by_owner(unused_classes: &[CssClass], owners: &HashMap<String, String>) -> BTreeMap<String, Vec<CssClass>> {
+    let mut groups: BTreeMap<String, Vec<CssClass>> = BTreeMap::new();
+    for class in unused_classes {
+        if let Some(owner) = owners.get(&class.file) {
+            groups.entry(owner.clone()).or_default().push(class.clone());
+        }
+    }
+    groups
+}
+
+fn category_only_usage(used_classes: &[CssClass], decisions: &DecisionMap) -> Vec<CategoryOnlyUsage> {
+    used_classes.iter()
+        .filter_map(|class| {
+            let decision = decisions.get(&decision_key(class))?;
+            (!decision.used_only_in.is_empty()).then(|| CategoryOnlyUsage {
+                class: class.clone(),
+                categories: decision.used_only_in.clone(),
+            })
+        })
+        .collect()
+}
+
+fn dead_combinations(compound_selectors: Vec<CompoundSelector>, used_classes: &[CssClass], tally: &UsageTally) -> Vec<CompoundSelector> {
+    // A combination with an unused class is already covered by that class's own finding
+    let used_names: HashSet<&str> = used_classes.iter().map(|class| class.name.as_str()).collect();
+    compound_selectors
+        .into_iter()
+        .enumerate()
+        .filter(|(index, compound)| {
+            !tally.co_applied.contains(index)
+                && compound.classes.iter().all(|name| used_names.contains(name.as_str()))
+                && !compound.classes.iter().any(|name| tally.script_applied.contains(name))
+        })
+        .map(|(_, compound)| compound)
+        .collect()
+}
+
+/* ============================================================================================== */
+fn mark_decisions(decisions: &mut DecisionMap, classes: &[CssClass], status: DecisionStatus) {
+    for class in classes {
+        if let Some(decision) = decisions.get_mut(&decision_key(class)) {
+            decision.status = status;
+        }
+    }
+}
+
+fn context_note(class: &CssClass, style: &OutputStyle) -> String {
+    let layer = class.layer.as_ref().map(|layer| style.message("report.layer_note", &[layer]));
+    let scope = class.scope.as_ref().map(|scope| style.message("report.scope_note", &[scope]));
+    format!("{}{}", layer.unwrap_or_default(), scope.unwrap_or_default())
+}
+
+fn declarations_preview(class: &CssClass, style: &OutputStyle) -> Option<String> {
+    let declarations = class.declarations.as_ref()?;
+    Some(if style.ascii { declarations.replace('…', "...") } else { declarations.clone() })
+}
+
+fn low_confidence_note(class: &UnusedClass, style: &OutputStyle) -> &'static str {
+    if class.low_confidence {
+        style.text("report.low_confidence_note")
+    } else if class.recent {
+        style.text("report.recent_note")
+    } else if class.legacy {
+        style.text("report.legacy_note")
+    } else {
+        ""
+    }
+}
@@ -1,6 +1,9 @@
 use std::fs;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
+use crate::output_style::OutputStyle;
+use crate::observer::AnalysisObserver;
+use crate::system_limits::available_cores;
 
 /* ============================================================================================== */
 /*                                          Process utils                                         */
@@ -8,7 +11,7 @@ use std::sync::{Arc, Mutex};
 pub fn create_thread_pool(thread_count: Option<usize>) -> Result<rayon::ThreadPool, Box<dyn std::error::Error>> {
     let pool = match thread_count {
         Some(count) => rayon::ThreadPoolBuilder::new().num_threads(count).build()?,
-        None => rayon::ThreadPoolBuilder::new().build()?,
+        None => rayon::ThreadPoolBuilder::new().num_threads(available_cores()).build()?,
     };
     Ok(pool)
 }
@@ -39,7 +42,7 @@ pub fn calculate_progress_step_size(total: usize, target_updates: usize) -> usiz
 
 /* ============================================================================================== */
 pub fn get_thread_count_or_default(thread_count: Option<usize>) -> usize {
-    thread_count.unwrap_or_else(num_cpus::get)
+    thread_count.unwrap_or_else(available_cores)
 }
 /* ============================================================================================== */
 /*                                        Collection utils                                        */
@@ -68,7 +71,7 @@ where
 /* ============================================================================================== */
 pub fn has_extension(path: &std::path::Path, extensions: &[&str]) -> bool {
     if let Some(ext) = get_file_extension(path) {
-        extensions.iter().any(|allowed| *allowed == ext)
+        extensions.contains(&ext)
     } else {
         false
     }
@@ -80,16 +83,42 @@ pub fn get_file_extension(path: &std::path::Path) -> Option<&str> {
 }
 
 /* ============================================================================================== */
-/*                                         Printing utils                                         */
+pub fn glob_to_regex(glob: &str) -> Result<regex::Regex, regex::Error> {
+    // `**/` spans any number of directories, `*` and `?` stay within one path component
+    let mut pattern = String::from("^");
+    let mut rest = glob.trim_start_matches("./");
+    while let Some(c) = rest.chars().next() {
+        let step = if rest.starts_with("**/") {
+            pattern.push_str("(?:.*/)?");
+            3
+        } else if rest.starts_with("**") {
+            pattern.push_str(".*");
+            2
+        } else {
+            match c {
+                '*' => pattern.push_str("[^/]*"),
+                '?' => pattern.push_str("[^/]"),
+                _ => pattern.push_str(&regex::escape(&c.to_string())),
+            }
+            c.len_utf8()
+        };
+        rest = &rest[step..];
+    }
+    pattern.push('$');
+    regex::Regex::new(&pattern)
+}
+
 /* ============================================================================================== */
-pub fn update_progress(progress_counter: &Arc<Mutex<usize>>, total: usize, step_size: usize) {
+pub fn update_progress(progress_counter: &Arc<Mutex<usize>>, total: usize, step_size: usize, observer: &dyn AnalysisObserver) {
     let mut counter = progress_counter.lock().unwrap();
     *counter += 1;
-    if *counter % step_size == 0 || *counter == total {
-        println!("      Processed {}/{} items...", *counter, total);
+    if (*counter).is_multiple_of(step_size) || *counter == total {
+        observer.on_progress(*counter, total);
     }
 }
 
+/* ============================================================================================== */
+/*                                         Printing utils                                         */
 /* ============================================================================================== */
 pub fn print_header_line(width: usize) {
     println!("{spacer:=>width$}", spacer="=", width = width);
@@ -101,7 +130,14 @@ pub fn print_section_line(width: usize) {
 }
 
 /* ============================================================================================== */
-pub fn print_banner(banner_file: Option<&str>) {
+pub fn print_banner(banner_file: Option<&str>, style: &OutputStyle) {
+    if style.ascii {
+        // Banner art is full of box characters, keep it to a plain title line
+        println!("TAG FINDER - Find unused CSS classes and tags");
+        style.print_header_line();
+        return;
+    }
+
     // Read banner from file and yeet it out
     let banner_content = match banner_file {
         Some(file_path) => read_banner_from_file(file_path),
@@ -158,18 +194,66 @@ fn get_max_line_length(content: &str) -> usize {
         .unwrap_or(60) // Default to 60 if somehow empty
 }
 
+/* ============================================================================================== */
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/* ============================================================================================== */
+/// `text` as a code span that can sit in a Markdown table cell
+pub fn markdown_code_cell(text: &str) -> String {
+    // The fence has to be longer than any backtick run inside, and `|` would end the cell
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run + 1);
+    let padding = if text.starts_with('`') || text.ends_with('`') { " " } else { "" };
+    let text = text.replace('|', "\\|").replace(['\r', '\n'], " ");
+    format!("{fence}{padding}{text}{padding}{fence}")
+}
+
+/* ============================================================================================== */
+pub fn format_unix_date(timestamp: u64) -> String {
+    // Civil-from-days conversion, avoids pulling in a date crate for YYYY-MM-DD
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
 /* ============================================================================================== */
 /*                                      Error handling utils                                      */
 /* ============================================================================================== */
 pub fn convert_sync_error<E: std::error::Error + Send + Sync + 'static>(
     error: E
 ) -> Box<dyn std::error::Error> {
-    Box::new(std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))
+    Box::new(std::io::Error::other(error.to_string()))
 }
 
 /* ============================================================================================== */
 pub fn convert_thread_error<E: std::error::Error + Send + Sync + 'static>(
     error: E
 ) -> Box<dyn std::error::Error + Send + Sync> {
-    Box::new(std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))
-}
\ No newline at end of file
+    Box::new(std::io::Error::other(error.to_string()))
+}
+/* ============================================================================================== */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_code_cells_survive_pipes_and_backticks() {
+        assert_eq!(markdown_code_cell(".btn"), "`.btn`");
+        assert_eq!(markdown_code_cell("src/a|b.css"), "`src/a\\|b.css`");
+        assert_eq!(markdown_code_cell("it`s.css"), "``it`s.css``");
+        assert_eq!(markdown_code_cell("``odd``.css"), "``` ``odd``.css ```");
+    }
+}
@@ -0,0 +1,34 @@
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever `UnusedReport` changes in a way older readers can't handle
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// What the About panel shows, and what a bug report needs to reproduce a result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub core_version: String,
+    pub report_schema_version: u32,
+    /// Fingerprint of the effective config, so "same version, different result" can be told apart
+    pub config_hash: String,
+}
+
+impl VersionInfo {
+    pub fn for_config(config: &Config) -> Self {
+        Self {
+            core_version: env!("CARGO_PKG_VERSION").to_string(),
+            report_schema_version: REPORT_SCHEMA_VERSION,
+            config_hash: config_hash(config),
+        }
+    }
+}
+
+/* ============================================================================================== */
+pub fn config_hash(config: &Config) -> String {
+    // FNV-1a over the serialized config: stable across runs and Rust versions, unlike DefaultHasher
+    let serialized = toml::to_string(config).unwrap_or_default();
+    let hash = serialized.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,29 @@
+//! Ctrl-C sets a process-wide flag, so this runs in its own test binary where setting it can't
+//! cancel the other tests.
+
+use std::fs;
+use tag_finder::{request_cancel, write_output, ExitStatus, ParallelProcessor, ProgressConfigurable};
+
+#[test]
+fn cancelled_runs_stop_and_leave_no_temp_files() {
+    let directory = std::env::temp_dir().join(format!("tag-finder-cancel-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("report.json.gz").to_string_lossy().to_string();
+    write_output(&path, b"previous report").unwrap();
+
+    assert!(!request_cancel());
+    let written = write_output(&path, b"report cut short");
+    let processed = ParallelProcessor::new()
+        .with_progress(false)
+        .process(vec![1, 2, 3], |item| Ok(item * 2), "progress.reading_file_contents");
+    let entries: Vec<_> = fs::read_dir(&directory).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    let previous = tag_finder::read_output(&path).unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert!(written.is_err());
+    assert_eq!(previous, "previous report");
+    assert_eq!(entries, vec!["report.json.gz"]);
+    let error = processed.unwrap_err();
+    assert_eq!(ExitStatus::for_error(error.as_ref()), ExitStatus::Cancelled);
+    assert!(request_cancel(), "a second Ctrl-C sees the first");
+}
@@ -0,0 +1,10 @@
+<?php $current = $_GET['page'] ?? 'home'; ?>
+<header class="site-header">
+  <ul class="menu">
+    <?php foreach ($pages as $page): ?>
+      <li class="menu-item <?php echo $page === $current ? 'is-current' : ''; ?>">
+        <a href="?page=<?= $page ?>"><?= ucfirst($page) ?></a>
+      </li>
+    <?php endforeach; ?>
+  </ul>
+</header>
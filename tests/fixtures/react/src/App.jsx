@@ -0,0 +1,15 @@
+import './App.css';
+
+export function Button({ variant, children }) {
+  return <button className={`btn btn-${variant}`}>{children}</button>;
+}
+
+export default function App({ user }) {
+  return (
+    <div className="app">
+      <header className={user ? 'app-header app-header--signed-in' : 'app-header'}>
+        <Button variant="primary">Save</Button>
+      </header>
+    </div>
+  );
+}
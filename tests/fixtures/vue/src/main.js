@@ -0,0 +1,6 @@
+import { createApp } from 'vue';
+import Card from './components/Card.vue';
+
+const app = createApp(Card);
+document.body.classList.add('app-ready');
+app.mount('#app');
@@ -0,0 +1,82 @@
+//! Accuracy regression tests: every project under `tests/fixtures` is analyzed from disk and
+//! compared with its `expected.golden`. After an intended change in results, regenerate them with
+//! `UPDATE_GOLDEN=1 cargo test --test golden_reports` and review the diff.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tag_finder::{
+    fixtures, golden_report, is_fixture_project_file, run_selftest, Config, ConfigConfigurable, ProgressConfigurable,
+    UnusedDetector, GOLDEN_FILE,
+};
+use walkdir::WalkDir;
+
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+}
+
+fn fixture_directories() -> Vec<PathBuf> {
+    let mut directories: Vec<PathBuf> = fs::read_dir(fixture_root()).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect();
+    directories.sort();
+    directories
+}
+
+fn project_files(directory: &Path) -> BTreeSet<String> {
+    WalkDir::new(directory).into_iter()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.path().strip_prefix(directory).unwrap().to_path_buf())
+        .filter(|relative| is_fixture_project_file(relative))
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .collect()
+}
+
+#[test]
+fn fixtures_match_golden_reports() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+
+    for directory in fixture_directories() {
+        let config = Config::load_for_directory(&directory.to_string_lossy());
+        let report = UnusedDetector::new(directory.to_string_lossy().to_string())
+            .with_config(config)
+            .with_progress(false)
+            .generate_report()
+            .unwrap();
+        let actual = golden_report(&report);
+        let golden_path = directory.join(GOLDEN_FILE);
+
+        if update {
+            fs::write(&golden_path, &actual).unwrap();
+        } else if fs::read_to_string(&golden_path).unwrap_or_default() != actual {
+            failures.push(format!("{}:\n{}", golden_path.display(), actual));
+        }
+    }
+
+    assert!(failures.is_empty(), "Reports differ from the golden files, this build produced:\n\n{}", failures.join("\n"));
+}
+
+#[test]
+fn selftest_embeds_every_fixture() {
+    let on_disk: Vec<String> = fixture_directories().iter()
+        .map(|directory| directory.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    let embedded: Vec<String> = fixtures().iter().map(|fixture| fixture.name.to_string()).collect();
+    assert_eq!(embedded, on_disk, "src/selftest.rs should list every directory in tests/fixtures");
+
+    for fixture in fixtures() {
+        let files: BTreeSet<String> = fixture.files.iter().map(|(path, _)| path.to_string()).collect();
+        assert_eq!(files, project_files(&fixture_root().join(fixture.name)), "files of fixture '{}'", fixture.name);
+    }
+}
+
+#[test]
+fn selftest_passes() {
+    for outcome in run_selftest().unwrap() {
+        let (missing, unexpected) = outcome.differences();
+        assert!(outcome.passed(), "fixture '{}': missing {:?}, unexpected {:?}", outcome.name, missing, unexpected);
+    }
+}
@@ -0,0 +1,48 @@
+//! Timing of a generated project of a few thousand files, for spotting slowdowns such as
+//! per-file regex compilation. Ignored by default since wall-clock time depends on the machine;
+//! run with `cargo test --release --test scan_performance -- --ignored --nocapture`.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+use tag_finder::{ProgressConfigurable, UnusedDetector};
+
+const USAGE_FILES: usize = 3000;
+const CLASSES: usize = 500;
+
+// Removes the generated project however the test ends
+struct ScratchProject(PathBuf);
+
+impl Drop for ScratchProject {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+#[ignore]
+fn time_large_project_analysis() {
+    let project = ScratchProject(std::env::temp_dir().join(format!("tag-finder-performance-{}", std::process::id())));
+    fs::create_dir_all(project.0.join("pages")).unwrap();
+
+    let stylesheet: String = (0..CLASSES).map(|index| format!(".block-{} {{ color: red; }}\n", index)).collect();
+    fs::write(project.0.join("styles.css"), stylesheet).unwrap();
+    for page in 0..USAGE_FILES {
+        // Every other class is used somewhere, through markup, a style section and a data attribute
+        let class = (page * 2) % CLASSES;
+        fs::write(project.0.join("pages").join(format!("page-{}.html", page)), format!(
+            "<style>.inline-{0} {{ margin: 0 }}</style>\n<div class=\"block-{0} other\" data-state=\"open\">Page {1}</div>\n",
+            class, page,
+        )).unwrap();
+    }
+
+    let started = Instant::now();
+    let report = UnusedDetector::new(project.0.to_string_lossy().to_string())
+        .with_progress(false)
+        .generate_report()
+        .unwrap();
+    println!("Analyzed {} usage files in {:?}", USAGE_FILES, started.elapsed());
+
+    assert_eq!(report.total_classes, CLASSES);
+    assert_eq!(report.unused_classes.len(), CLASSES / 2);
+}